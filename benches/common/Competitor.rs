@@ -0,0 +1,27 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// A bounded multi-producer, single-consumer queue to compare against.
+pub trait Competitor<T: Message>
+{
+	/// Used to identify the competitor in benchmark results.
+	const Name: &'static str;
+
+	/// Can be sent to a producer thread.
+	type Producer: Send + 'static;
+
+	/// Used only on the thread which creates the queue.
+	type Consumer;
+
+	/// Creates a queue of `capacity` items with `number_of_producers` producers.
+	fn new(capacity: usize, number_of_producers: usize) -> (Self::Consumer, Vec<Self::Producer>);
+
+	/// Tries to send `burst`; returns the count of items, from the start of `burst`, which were sent.
+	///
+	/// Returns zero if the queue is full.
+	fn try_produce(producer: &Self::Producer, burst: &[T]) -> usize;
+
+	/// Passes every item ready to `received`; returns the count of items received.
+	fn try_consume<F: FnMut(T)>(consumer: &mut Self::Consumer, received: F) -> usize;
+}
@@ -0,0 +1,40 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// `crossbeam_queue::ArrayQueue`.
+pub struct CrossbeamArrayQueue;
+
+impl<T: Message> Competitor<T> for CrossbeamArrayQueue
+{
+	const Name: &'static str = "crossbeam_array_queue";
+
+	type Producer = Arc<ArrayQueue<T>>;
+
+	type Consumer = Arc<ArrayQueue<T>>;
+
+	#[inline(always)]
+	fn new(capacity: usize, number_of_producers: usize) -> (Self::Consumer, Vec<Self::Producer>)
+	{
+		let array_queue = Arc::new(ArrayQueue::new(capacity));
+		(array_queue.clone(), (0 .. number_of_producers).map(|_| array_queue.clone()).collect())
+	}
+
+	#[inline(always)]
+	fn try_produce(producer: &Self::Producer, burst: &[T]) -> usize
+	{
+		burst.iter().take_while(|&&item| producer.push(item).is_ok()).count()
+	}
+
+	#[inline(always)]
+	fn try_consume<F: FnMut(T)>(consumer: &mut Self::Consumer, mut received: F) -> usize
+	{
+		let mut count = 0;
+		while let Ok(item) = consumer.pop()
+		{
+			received(item);
+			count += 1;
+		}
+		count
+	}
+}
@@ -0,0 +1,34 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// `crossbeam_channel::bounded()`.
+pub struct CrossbeamChannel;
+
+impl<T: Message> Competitor<T> for CrossbeamChannel
+{
+	const Name: &'static str = "crossbeam_channel";
+
+	type Producer = CrossbeamSender<T>;
+
+	type Consumer = CrossbeamReceiver<T>;
+
+	#[inline(always)]
+	fn new(capacity: usize, number_of_producers: usize) -> (Self::Consumer, Vec<Self::Producer>)
+	{
+		let (sender, receiver) = bounded(capacity);
+		(receiver, (0 .. number_of_producers).map(|_| sender.clone()).collect())
+	}
+
+	#[inline(always)]
+	fn try_produce(producer: &Self::Producer, burst: &[T]) -> usize
+	{
+		burst.iter().take_while(|&&item| producer.try_send(item).is_ok()).count()
+	}
+
+	#[inline(always)]
+	fn try_consume<F: FnMut(T)>(consumer: &mut Self::Consumer, received: F) -> usize
+	{
+		consumer.try_iter().map(received).count()
+	}
+}
@@ -0,0 +1,47 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// This crate's `RingBuffer`; a burst is sent with one acquisition and received with one consumption.
+pub struct LockFreeRingBuffer;
+
+impl<T: Message> Competitor<T> for LockFreeRingBuffer
+{
+	const Name: &'static str = "lock_free_ring_buffer";
+
+	type Producer = RingBufferProducer<T>;
+
+	type Consumer = RingBufferConsumer<T>;
+
+	#[inline(always)]
+	fn new(capacity: usize, number_of_producers: usize) -> (Self::Consumer, Vec<Self::Producer>)
+	{
+		RingBuffer::new(capacity, number_of_producers)
+	}
+
+	#[inline(always)]
+	fn try_produce(producer: &Self::Producer, burst: &[T]) -> usize
+	{
+		match producer.acquire(burst.len())
+		{
+			Err(()) => 0,
+
+			Ok(mut slice_guard) =>
+			{
+				slice_guard.copy_from_slice(burst);
+				burst.len()
+			}
+		}
+	}
+
+	#[inline(always)]
+	fn try_consume<F: FnMut(T)>(consumer: &mut Self::Consumer, mut received: F) -> usize
+	{
+		consumer.drain_with(|batch|
+		{
+			let length = batch.len();
+			batch.iter().cloned().for_each(&mut received);
+			length
+		})
+	}
+}
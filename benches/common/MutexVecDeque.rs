@@ -0,0 +1,40 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// A `VecDeque` behind a `Mutex`; the baseline.
+///
+/// A burst is sent, and everything ready is received, whilst holding the lock once.
+pub struct MutexVecDeque;
+
+impl<T: Message> Competitor<T> for MutexVecDeque
+{
+	const Name: &'static str = "mutex_vec_deque";
+
+	type Producer = (Arc<Mutex<VecDeque<T>>>, usize);
+
+	type Consumer = Arc<Mutex<VecDeque<T>>>;
+
+	#[inline(always)]
+	fn new(capacity: usize, number_of_producers: usize) -> (Self::Consumer, Vec<Self::Producer>)
+	{
+		let vec_deque = Arc::new(Mutex::new(VecDeque::with_capacity(capacity)));
+		(vec_deque.clone(), (0 .. number_of_producers).map(|_| (vec_deque.clone(), capacity)).collect())
+	}
+
+	#[inline(always)]
+	fn try_produce(producer: &Self::Producer, burst: &[T]) -> usize
+	{
+		let &(ref vec_deque, capacity) = producer;
+		let mut vec_deque = vec_deque.lock().unwrap();
+		let count = min(capacity - vec_deque.len(), burst.len());
+		vec_deque.extend(&burst[.. count]);
+		count
+	}
+
+	#[inline(always)]
+	fn try_consume<F: FnMut(T)>(consumer: &mut Self::Consumer, received: F) -> usize
+	{
+		consumer.lock().unwrap().drain(..).map(received).count()
+	}
+}
@@ -0,0 +1,42 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Percentiles of latencies.
+#[derive(Debug)]
+pub struct Percentiles
+{
+	p50: Duration,
+	p90: Duration,
+	p99: Duration,
+	p99_9: Duration,
+	maximum: Duration,
+}
+
+impl ::std::fmt::Display for Percentiles
+{
+	fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result
+	{
+		write!(f, "p50 {:?}, p90 {:?}, p99 {:?}, p99.9 {:?}, maximum {:?}", self.p50, self.p90, self.p99, self.p99_9, self.maximum)
+	}
+}
+
+impl Percentiles
+{
+	/// `latencies` must not be empty.
+	pub fn new(mut latencies: Vec<Duration>) -> Self
+	{
+		latencies.sort();
+
+		let percentile = |per_mille: usize| latencies[(latencies.len() - 1) * per_mille / 1000];
+
+		Self
+		{
+			p50: percentile(500),
+			p90: percentile(900),
+			p99: percentile(990),
+			p99_9: percentile(999),
+			maximum: latencies[latencies.len() - 1],
+		}
+	}
+}
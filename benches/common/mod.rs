@@ -0,0 +1,48 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Competing bounded multi-producer, single-consumer queues and the harnesses which drive them.
+
+
+#![allow(dead_code)]
+
+
+use ::criterion::black_box;
+use ::crossbeam_channel::bounded;
+use ::crossbeam_channel::Receiver as CrossbeamReceiver;
+use ::crossbeam_channel::Sender as CrossbeamSender;
+use ::crossbeam_queue::ArrayQueue;
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+use ::std::collections::VecDeque;
+use ::std::cmp::min;
+use ::std::sync::Arc;
+use ::std::sync::Barrier;
+use ::std::sync::Mutex;
+use ::std::sync::atomic::AtomicBool;
+use ::std::sync::atomic::AtomicUsize;
+use ::std::sync::atomic::Ordering::Acquire;
+use ::std::sync::atomic::Ordering::Release;
+use ::std::sync::mpsc::Receiver;
+use ::std::sync::mpsc::sync_channel;
+use ::std::sync::mpsc::SyncSender;
+use ::std::thread::spawn;
+use ::std::thread::yield_now;
+use ::std::time::Duration;
+use ::std::time::Instant;
+
+
+include!("Competitor.rs");
+include!("CrossbeamArrayQueue.rs");
+include!("CrossbeamChannel.rs");
+include!("LockFreeRingBuffer.rs");
+include!("Message.rs");
+include!("MutexVecDeque.rs");
+include!("one_way_latencies.rs");
+include!("Percentiles.rs");
+include!("StdSyncChannel.rs");
+include!("throughput.rs");
+
+
+/// The capacity, in items, of every queue.
+pub const Capacity: usize = 1024;
@@ -0,0 +1,81 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Sends `messages` time-stamped items, one at a time, from a producer thread and returns the one-way latency of each, from just before it was sent until it was received on the current thread.
+///
+/// Each item is sent only once the previous one has been received, so that the latency does not include time spent queued behind it.
+///
+/// Whilst doing so, `number_of_loading_producers` other producer threads continuously send bursts of `burst_length` items to load the queue.
+pub fn one_way_latencies<C: Competitor<Duration>>(messages: usize, number_of_loading_producers: usize, burst_length: usize) -> Vec<Duration>
+{
+	let (mut consumer, mut producers) = C::new(Capacity, number_of_loading_producers + 1);
+	let epoch = Instant::now();
+	let received = Arc::new(AtomicUsize::new(0));
+	let stop = Arc::new(AtomicBool::new(false));
+
+	let time_stamping_producer = producers.pop().unwrap();
+	let time_stamping_producer_thread =
+	{
+		let received = received.clone();
+		spawn(move ||
+		{
+			for sent in 0 .. messages
+			{
+				while received.load(Acquire) != sent
+				{
+					yield_now()
+				}
+
+				// Never equal to `Duration::default()`, which the loading producers send.
+				let time_stamp = [epoch.elapsed() + Duration::from_nanos(1)];
+				while C::try_produce(&time_stamping_producer, &time_stamp) == 0
+				{
+					yield_now()
+				}
+			}
+		})
+	};
+
+	let loading_producer_threads: Vec<_> = producers.into_iter().map(|producer|
+	{
+		let stop = stop.clone();
+		spawn(move ||
+		{
+			let burst = vec![Duration::default(); burst_length];
+			while !stop.load(Acquire)
+			{
+				if C::try_produce(&producer, &burst) == 0
+				{
+					yield_now()
+				}
+			}
+		})
+	}).collect();
+
+	let mut latencies = Vec::with_capacity(messages);
+	while latencies.len() != messages
+	{
+		let count = C::try_consume(&mut consumer, |time_stamp|
+		{
+			if time_stamp != Duration::default()
+			{
+				latencies.push(epoch.elapsed() + Duration::from_nanos(1) - time_stamp);
+				received.fetch_add(1, Release);
+			}
+		});
+		if count == 0
+		{
+			yield_now()
+		}
+	}
+
+	stop.store(true, Release);
+	time_stamping_producer_thread.join().unwrap();
+	for loading_producer_thread in loading_producer_threads
+	{
+		loading_producer_thread.join().unwrap();
+	}
+
+	latencies
+}
@@ -0,0 +1,64 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Sends `messages` items, divided evenly between `number_of_producers` producer threads which each send bursts of `burst_length`, and receives them on the current thread.
+///
+/// Returns the time from when every thread is ready until the last item is received; `messages` should be a multiple of `number_of_producers`.
+pub fn throughput<T: Message, C: Competitor<T>>(number_of_producers: usize, burst_length: usize, messages: usize) -> Duration
+{
+	let (mut consumer, producers) = C::new(Capacity, number_of_producers);
+	let messages_per_producer = messages / number_of_producers;
+	let barrier = Arc::new(Barrier::new(number_of_producers + 1));
+
+	let producer_threads: Vec<_> = producers.into_iter().map(|producer|
+	{
+		let barrier = barrier.clone();
+		spawn(move ||
+		{
+			let burst = vec![T::default(); burst_length];
+			barrier.wait();
+
+			let mut remaining = messages_per_producer;
+			while remaining != 0
+			{
+				let burst = &burst[.. min(burst_length, remaining)];
+				let mut sent = 0;
+				while sent != burst.len()
+				{
+					let count = C::try_produce(&producer, &burst[sent ..]);
+					if count == 0
+					{
+						yield_now()
+					}
+					sent += count;
+				}
+				remaining -= burst.len();
+			}
+		})
+	}).collect();
+
+	let total = messages_per_producer * number_of_producers;
+	barrier.wait();
+	let start = Instant::now();
+
+	let mut received = 0;
+	while received != total
+	{
+		let count = C::try_consume(&mut consumer, |item| { black_box(item); });
+		if count == 0
+		{
+			yield_now()
+		}
+		received += count;
+	}
+
+	let elapsed = start.elapsed();
+
+	for producer_thread in producer_threads
+	{
+		producer_thread.join().unwrap();
+	}
+
+	elapsed
+}
@@ -0,0 +1,82 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! One-way latency of this crate's `RingBuffer` compared to `std::sync::mpsc::sync_channel()`, `crossbeam_queue::ArrayQueue`, `crossbeam_channel::bounded()` and a `Mutex<VecDeque>`, unloaded and whilst other producers load the queue.
+//!
+//! Run with `cargo bench --bench latency`.
+//! Criterion measures the mean latency; the percentiles of a separate run of `PercentileMessages` items are printed for each competitor.
+
+
+#![allow(non_upper_case_globals)]
+
+
+#[macro_use] extern crate criterion;
+extern crate crossbeam_channel;
+extern crate crossbeam_queue;
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+
+
+mod common;
+
+
+use self::common::*;
+use ::criterion::BenchmarkGroup;
+use ::criterion::BenchmarkId;
+use ::criterion::Criterion;
+use ::criterion::measurement::WallTime;
+use ::std::time::Duration;
+
+
+const PercentileMessages: usize = 10_000;
+
+/// Loading producers send bursts of this length.
+const LoadingBurstLength: usize = 8;
+
+fn bench<C: Competitor<Duration>>(group: &mut BenchmarkGroup<WallTime>, number_of_loading_producers: usize)
+{
+	let parameter = format!("{}_loading_producers", number_of_loading_producers);
+	let name = format!("{}/{}/{}", group_name(number_of_loading_producers), C::Name, parameter);
+
+	let mut percentiles_printed = false;
+	group.bench_function(BenchmarkId::new(C::Name, parameter), |bencher|
+	{
+		// Only once, and only if not filtered out.
+		if !percentiles_printed
+		{
+			println!("{}: {}", name, Percentiles::new(one_way_latencies::<C>(PercentileMessages, number_of_loading_producers, LoadingBurstLength)));
+			percentiles_printed = true;
+		}
+
+		bencher.iter_custom(|iterations| one_way_latencies::<C>(iterations as usize, number_of_loading_producers, LoadingBurstLength).iter().sum())
+	});
+}
+
+fn group_name(number_of_loading_producers: usize) -> &'static str
+{
+	if number_of_loading_producers == 0
+	{
+		"latency/unloaded"
+	}
+	else
+	{
+		"latency/loaded"
+	}
+}
+
+fn latency(criterion: &mut Criterion)
+{
+	for &number_of_loading_producers in &[0, 3]
+	{
+		let mut group = criterion.benchmark_group(group_name(number_of_loading_producers));
+		bench::<LockFreeRingBuffer>(&mut group, number_of_loading_producers);
+		bench::<StdSyncChannel>(&mut group, number_of_loading_producers);
+		bench::<CrossbeamArrayQueue>(&mut group, number_of_loading_producers);
+		bench::<CrossbeamChannel>(&mut group, number_of_loading_producers);
+		bench::<MutexVecDeque>(&mut group, number_of_loading_producers);
+		group.finish();
+	}
+}
+
+criterion_group!(benches, latency);
+criterion_main!(benches);
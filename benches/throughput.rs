@@ -0,0 +1,91 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Throughput of this crate's `RingBuffer` compared to `std::sync::mpsc::sync_channel()`, `crossbeam_queue::ArrayQueue`, `crossbeam_channel::bounded()` and a `Mutex<VecDeque>`.
+//!
+//! Run with `cargo bench --bench throughput`; every queue has a capacity of `common::Capacity` items.
+
+
+#![allow(non_upper_case_globals)]
+
+
+#[macro_use] extern crate criterion;
+extern crate crossbeam_channel;
+extern crate crossbeam_queue;
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+
+
+mod common;
+
+
+use self::common::*;
+use ::criterion::BenchmarkGroup;
+use ::criterion::BenchmarkId;
+use ::criterion::Criterion;
+use ::criterion::Throughput;
+use ::criterion::measurement::WallTime;
+
+
+/// A multiple of every number of producers.
+const MessagesPerIteration: usize = 8192;
+
+fn bench<T: Message, C: Competitor<T>>(group: &mut BenchmarkGroup<WallTime>, parameter: String, number_of_producers: usize, burst_length: usize)
+{
+	group.bench_function(BenchmarkId::new(C::Name, parameter), |bencher| bencher.iter_custom(|iterations|
+	{
+		throughput::<T, C>(number_of_producers, burst_length, MessagesPerIteration * iterations as usize)
+	}));
+}
+
+fn bench_every_competitor<T: Message>(group: &mut BenchmarkGroup<WallTime>, parameter: String, number_of_producers: usize, burst_length: usize)
+{
+	bench::<T, LockFreeRingBuffer>(group, parameter.clone(), number_of_producers, burst_length);
+	bench::<T, StdSyncChannel>(group, parameter.clone(), number_of_producers, burst_length);
+	bench::<T, CrossbeamArrayQueue>(group, parameter.clone(), number_of_producers, burst_length);
+	bench::<T, CrossbeamChannel>(group, parameter.clone(), number_of_producers, burst_length);
+	bench::<T, MutexVecDeque>(group, parameter, number_of_producers, burst_length);
+}
+
+/// One producer sending one 8 byte item at a time.
+fn single_item(criterion: &mut Criterion)
+{
+	let mut group = criterion.benchmark_group("single_item");
+	group.throughput(Throughput::Elements(MessagesPerIteration as u64));
+	bench_every_competitor::<u64>(&mut group, "1_producer".to_string(), 1, 1);
+	group.finish();
+}
+
+/// One producer sending bursts of varying length of items of varying size.
+fn burst(criterion: &mut Criterion)
+{
+	fn bench_item_size<T: Message>(criterion: &mut Criterion, item_size: usize)
+	{
+		let mut group = criterion.benchmark_group(format!("burst/{}_byte_items", item_size));
+		group.throughput(Throughput::Elements(MessagesPerIteration as u64));
+		for &burst_length in &[1, 8, 64]
+		{
+			bench_every_competitor::<T>(&mut group, format!("burst_length_{}", burst_length), 1, burst_length);
+		}
+		group.finish();
+	}
+
+	bench_item_size::<u64>(criterion, 8);
+	bench_item_size::<[u64; 8]>(criterion, 64);
+	bench_item_size::<[u64; 32]>(criterion, 256);
+}
+
+/// Increasing numbers of producers, each sending bursts of eight 8 byte items.
+fn producer_scaling(criterion: &mut Criterion)
+{
+	let mut group = criterion.benchmark_group("producer_scaling");
+	group.throughput(Throughput::Elements(MessagesPerIteration as u64));
+	for &number_of_producers in &[1, 2, 4, 8, 16, 32]
+	{
+		bench_every_competitor::<u64>(&mut group, format!("{}_producers", number_of_producers), number_of_producers, 8);
+	}
+	group.finish();
+}
+
+criterion_group!(benches, single_item, burst, producer_scaling);
+criterion_main!(benches);
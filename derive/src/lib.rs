@@ -0,0 +1,166 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+#![deny(missing_docs)]
+
+
+//! # lock-free-multi-producer-single-consumer-ring-buffer-derive
+//!
+//! `#[derive(RingMessage)]`, for the `MessageRingBuffer` of `lock-free-multi-producer-single-consumer-ring-buffer`; use it through that crate's `derive` feature.
+//!
+//! ## Usage
+//!
+//! ```
+//! use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+//!
+//! #[derive(RingMessage)]
+//! enum Message
+//! {
+//!     Ping,
+//!     Move(i32, i32),
+//!     Bulk { items: [u64; 16] },
+//! }
+//!
+//! let (mut consumer, producers) = MessageRingBuffer::<Message>::new(1024, 1);
+//!
+//! // Uses 16 bytes (a header and the two `i32`s), rather than enough for `Message::Bulk`.
+//! producers[0].produce(Message::Move(1, 2)).ok().unwrap();
+//!
+//! for message in consumer.consume()
+//! {
+//!     match message
+//!     {
+//!         Message::Ping => (),
+//!         Message::Move(x, y) => println!("moved to {}, {}", x, y),
+//!         Message::Bulk { items } => println!("{} items", items.len()),
+//!     }
+//! }
+//! ```
+//!
+//! Each variant is given a `RingMessageTag` in order of declaration, starting at `0`, so producers and the consumer must agree on the order of the variants.
+//! The fields of a variant are moved in and out as a tuple; they need not be `Copy`.
+
+
+extern crate proc_macro;
+
+
+use ::proc_macro::TokenStream;
+use ::proc_macro2::Span;
+use ::proc_macro2::TokenStream as TokenStream2;
+use ::quote::quote;
+use ::syn::Data;
+use ::syn::DeriveInput;
+use ::syn::Error;
+use ::syn::Fields;
+use ::syn::Ident;
+use ::syn::parse_macro_input;
+
+
+/// Implements `RingMessage` for an enum.
+///
+/// The enum must not implement `Drop` and can have at most 65,536 variants.
+#[proc_macro_derive(RingMessage)]
+pub fn derive_ring_message(input: TokenStream) -> TokenStream
+{
+	let input = parse_macro_input!(input as DeriveInput);
+	match ring_message(&input)
+	{
+		Ok(tokens) => tokens.into(),
+		Err(error) => error.to_compile_error().into(),
+	}
+}
+
+fn ring_message(input: &DeriveInput) -> Result<TokenStream2, Error>
+{
+	let variants = match input.data
+	{
+		Data::Enum(ref data_enum) => &data_enum.variants,
+
+		_ => return Err(Error::new_spanned(input, "RingMessage can only be derived for an enum")),
+	};
+
+	if variants.len() > (u16::MAX as usize) + 1
+	{
+		return Err(Error::new_spanned(input, "RingMessage can only be derived for an enum of at most 65,536 variants"))
+	}
+
+	let name = &input.ident;
+	let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+	let mut tag_and_size_arms = Vec::with_capacity(variants.len());
+	let mut write_payload_arms = Vec::with_capacity(variants.len());
+	let mut read_payload_arms = Vec::with_capacity(variants.len());
+
+	for (tag, variant) in variants.iter().enumerate()
+	{
+		let tag = tag as u16;
+		let variant_name = &variant.ident;
+
+		// Bound to `field_0`, `field_1`, etc, so that no field can shadow a parameter.
+		let bindings: Vec<Ident> = (0 .. variant.fields.len()).map(|index| Ident::new(&format!("field_{}", index), Span::call_site())).collect();
+		let field_types = variant.fields.iter().map(|field| &field.ty);
+		let payload_type = quote! { (#(#field_types,)*) };
+
+		let pattern = match variant.fields
+		{
+			Fields::Named(ref fields) =>
+			{
+				let field_names = fields.named.iter().map(|field| &field.ident);
+				quote! { #name::#variant_name { #(#field_names: #bindings),* } }
+			}
+
+			Fields::Unnamed(_) => quote! { #name::#variant_name(#(#bindings),*) },
+
+			Fields::Unit => quote! { #name::#variant_name },
+		};
+
+		tag_and_size_arms.push(quote! { #name::#variant_name { .. } => (#tag, ::std::mem::size_of::<#payload_type>()) });
+		write_payload_arms.push(quote! { #pattern => ::std::ptr::write_unaligned(payload as *mut #payload_type, (#(#bindings,)*)) });
+		read_payload_arms.push(quote!
+		{
+			#tag =>
+			{
+				let (#(#bindings,)*) = ::std::ptr::read_unaligned(payload as *const #payload_type);
+				#pattern
+			}
+		});
+	}
+
+	Ok
+	(
+		quote!
+		{
+			unsafe impl #impl_generics ::lock_free_multi_producer_single_consumer_ring_buffer::RingMessage for #name #type_generics #where_clause
+			{
+				#[inline(always)]
+				fn tag_and_size(&self) -> (::lock_free_multi_producer_single_consumer_ring_buffer::RingMessageTag, usize)
+				{
+					match *self
+					{
+						#(#tag_and_size_arms,)*
+					}
+				}
+
+				#[inline(always)]
+				unsafe fn write_payload(self, payload: *mut u8)
+				{
+					match self
+					{
+						#(#write_payload_arms,)*
+					}
+				}
+
+				#[inline(always)]
+				unsafe fn read_payload(tag: ::lock_free_multi_producer_single_consumer_ring_buffer::RingMessageTag, payload: *const u8) -> Self
+				{
+					match tag
+					{
+						#(#read_payload_arms)*
+						_ => unreachable!("unknown RingMessage tag {}", tag),
+					}
+				}
+			}
+		}
+	)
+}
@@ -0,0 +1,94 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! The input and checks shared by the fuzz targets.
+
+
+use ::arbitrary::Arbitrary;
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+use ::std::ops::Range;
+
+
+/// Indices and counts are reduced modulo the number of producers, reservations held and capacity when applied.
+#[derive(Arbitrary, Debug)]
+pub(crate) enum Operation
+{
+	Acquire
+	{
+		producer_index: u8,
+		count: u8,
+	},
+
+	Produce
+	{
+		producer_index: u8,
+		reservation_index: u8,
+	},
+
+	/// Consume, then release at most `maximum_to_release` items.
+	Consume
+	{
+		maximum_to_release: u8,
+	},
+}
+
+#[derive(Arbitrary, Debug)]
+pub(crate) struct Input
+{
+	capacity: u8,
+	maximum_reservations_per_producer: Vec<u8>,
+	pub(crate) operations: Vec<Operation>,
+}
+
+impl Input
+{
+	/// Creates the ring buffer described by the input, returning it and its capacity.
+	pub(crate) fn ring_buffer(&self) -> (RingBufferConsumer<u64>, Vec<RingBufferProducer<u64>>, usize)
+	{
+		let capacity = self.capacity as usize % MaximumCapacity + 1;
+		let mut maximum_reservations_per_producer: Vec<usize> = self.maximum_reservations_per_producer.iter().take(MaximumProducers).map(|&maximum_reservations| maximum_reservations as usize % MaximumReservationsPerProducer + 1).collect();
+		if maximum_reservations_per_producer.is_empty()
+		{
+			maximum_reservations_per_producer.push(1)
+		}
+
+		let (ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u64>::new_with_maximum_reservations(capacity, &maximum_reservations_per_producer);
+		(ring_buffer_consumer, ring_buffer_producers, capacity)
+	}
+}
+
+const MaximumCapacity: usize = 64;
+
+const MaximumProducers: usize = 4;
+
+const MaximumReservationsPerProducer: usize = 3;
+
+pub(crate) fn message(producer_index: usize, sequence_number: u64) -> u64
+{
+	(producer_index as u64) << 32 | sequence_number
+}
+
+/// Asserts that each of `messages` is the next one expected from its producer.
+pub(crate) fn check_messages(messages: &[u64], expected_sequence_numbers: &mut [u64])
+{
+	for &message in messages.iter()
+	{
+		let producer_index = (message >> 32) as usize;
+		let sequence_number = message & 0xFFFF_FFFF;
+		assert_eq!(sequence_number, expected_sequence_numbers[producer_index], "lost, duplicated or reordered message from producer {}", producer_index);
+		expected_sequence_numbers[producer_index] += 1;
+	}
+}
+
+/// The address range of a slice.
+pub(crate) fn address_range(slice: &[u64]) -> Range<usize>
+{
+	let start = slice.as_ptr() as usize;
+	start .. start + slice.len() * 8
+}
+
+pub(crate) fn overlaps(left: &Range<usize>, right: &Range<usize>) -> bool
+{
+	left.start < right.end && right.start < left.end
+}
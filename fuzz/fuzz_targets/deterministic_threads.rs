@@ -0,0 +1,177 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Drives a ring buffer with arbitrary operations, each producer on its own thread, asserting its invariants after every operation.
+//!
+//! The interleaving is deterministic: the fuzzer's input chooses which thread performs the next operation, and only that thread runs until the operation is complete.
+//!
+//! Run with `cargo fuzz run deterministic_threads`.
+
+
+#![allow(non_upper_case_globals)]
+#![no_main]
+
+
+mod common;
+
+
+use crate::common::Input;
+use crate::common::Operation;
+use crate::common::address_range;
+use crate::common::check_messages;
+use crate::common::message;
+use crate::common::overlaps;
+use ::libfuzzer_sys::fuzz_target;
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+use ::std::cmp::min;
+use ::std::ops::Range;
+use ::std::sync::mpsc::channel;
+use ::std::sync::mpsc::Receiver;
+use ::std::sync::mpsc::Sender;
+use ::std::thread::JoinHandle;
+use ::std::thread::spawn;
+
+
+enum Command
+{
+	/// Replies with the address range of the reservation, if acquired.
+	Acquire(usize),
+
+	/// Replies with an empty range.
+	Produce(usize),
+}
+
+/// Executes one command at a time; it exits when the sender of commands is dropped.
+fn producer_thread(ring_buffer_producer: RingBufferProducer<u64>, producer_index: usize, commands: Receiver<Command>, replies: Sender<Option<Range<usize>>>)
+{
+	let mut held_guards = Vec::new();
+	let mut next_sequence_number = 0;
+
+	while let Ok(command) = commands.recv()
+	{
+		let reply = match command
+		{
+			Command::Acquire(count) => match ring_buffer_producer.acquire(count)
+			{
+				Err(()) => None,
+
+				Ok(mut slice_guard) =>
+				{
+					for slot in slice_guard.iter_mut()
+					{
+						*slot = message(producer_index, next_sequence_number);
+						next_sequence_number += 1;
+					}
+					let address_range = address_range(&slice_guard);
+					held_guards.push(slice_guard);
+					Some(address_range)
+				}
+			},
+
+			Command::Produce(reservation_index) =>
+			{
+				drop(held_guards.remove(reservation_index));
+				Some(0 .. 0)
+			}
+		};
+		replies.send(reply).unwrap();
+	}
+}
+
+struct Producer
+{
+	commands: Sender<Command>,
+	replies: Receiver<Option<Range<usize>>>,
+	thread: JoinHandle<()>,
+	in_flight: Vec<Range<usize>>,
+}
+
+impl Producer
+{
+	fn execute(&self, command: Command) -> Option<Range<usize>>
+	{
+		self.commands.send(command).unwrap();
+		self.replies.recv().unwrap()
+	}
+}
+
+fuzz_target!(|input: Input|
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers, capacity) = input.ring_buffer();
+	let number_of_producers = ring_buffer_producers.len();
+
+	let mut producers: Vec<Producer> = ring_buffer_producers.into_iter().enumerate().map(|(producer_index, ring_buffer_producer)|
+	{
+		let (commands_sender, commands_receiver) = channel();
+		let (replies_sender, replies_receiver) = channel();
+		Producer
+		{
+			commands: commands_sender,
+			replies: replies_receiver,
+			thread: spawn(move || producer_thread(ring_buffer_producer, producer_index, commands_receiver, replies_sender)),
+			in_flight: Vec::new(),
+		}
+	}).collect();
+
+	let mut expected_sequence_numbers = vec![0; number_of_producers];
+	let mut unreleased = 0;
+
+	for operation in input.operations
+	{
+		match operation
+		{
+			Operation::Acquire { producer_index, count } =>
+			{
+				let producer = &mut producers[producer_index as usize % number_of_producers];
+				let count = count as usize % capacity + 1;
+
+				if let Some(address_range) = producer.execute(Command::Acquire(count))
+				{
+					assert_eq!(address_range.len(), count * 8);
+					unreleased += count;
+					producer.in_flight.push(address_range);
+				}
+			}
+
+			Operation::Produce { producer_index, reservation_index } =>
+			{
+				let producer = &mut producers[producer_index as usize % number_of_producers];
+				if !producer.in_flight.is_empty()
+				{
+					let reservation_index = reservation_index as usize % producer.in_flight.len();
+					producer.execute(Command::Produce(reservation_index));
+					producer.in_flight.remove(reservation_index);
+				}
+			}
+
+			Operation::Consume { maximum_to_release } =>
+			{
+				let mut slice_guard = ring_buffer_consumer.consume();
+
+				let consumed = address_range(slice_guard.as_slice());
+				for producer in producers.iter()
+				{
+					for in_flight in producer.in_flight.iter()
+					{
+						assert!(!overlaps(&consumed, in_flight), "consumer slice overlaps an in-flight producer reservation");
+					}
+				}
+
+				let release_count = min(maximum_to_release as usize, slice_guard.len());
+				check_messages(&slice_guard.as_slice()[.. release_count], &mut expected_sequence_numbers);
+
+				slice_guard.release(release_count);
+				unreleased -= release_count;
+			}
+		}
+
+		assert_eq!(ring_buffer_consumer.assert_invariants(), unreleased, "next has caught up with written");
+	}
+
+	for producer in producers
+	{
+		drop(producer.commands);
+		producer.thread.join().unwrap();
+	}
+});
@@ -0,0 +1,93 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Drives a ring buffer with arbitrary operations on one thread, asserting its invariants after every operation.
+//!
+//! Run with `cargo fuzz run single_threaded`.
+
+
+#![allow(non_upper_case_globals)]
+#![no_main]
+
+
+mod common;
+
+
+use crate::common::Input;
+use crate::common::Operation;
+use crate::common::address_range;
+use crate::common::check_messages;
+use crate::common::message;
+use crate::common::overlaps;
+use ::libfuzzer_sys::fuzz_target;
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+use ::std::cmp::min;
+
+
+fuzz_target!(|input: Input|
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers, capacity) = input.ring_buffer();
+	let number_of_producers = ring_buffer_producers.len();
+
+	let mut held_guards: Vec<Vec<RingBufferProducerGuard<u64>>> = (0 .. number_of_producers).map(|_| Vec::new()).collect();
+	let mut next_sequence_numbers = vec![0; number_of_producers];
+	let mut expected_sequence_numbers = vec![0; number_of_producers];
+	let mut unreleased = 0;
+
+	for operation in input.operations
+	{
+		match operation
+		{
+			Operation::Acquire { producer_index, count } =>
+			{
+				let producer_index = producer_index as usize % number_of_producers;
+				let count = count as usize % capacity + 1;
+
+				if let Ok(mut slice_guard) = ring_buffer_producers[producer_index].acquire(count)
+				{
+					assert_eq!(slice_guard.len(), count);
+					for slot in slice_guard.iter_mut()
+					{
+						*slot = message(producer_index, next_sequence_numbers[producer_index]);
+						next_sequence_numbers[producer_index] += 1;
+					}
+					unreleased += count;
+					held_guards[producer_index].push(slice_guard);
+				}
+			}
+
+			Operation::Produce { producer_index, reservation_index } =>
+			{
+				let guards = &mut held_guards[producer_index as usize % number_of_producers];
+				if !guards.is_empty()
+				{
+					let reservation_index = reservation_index as usize % guards.len();
+					drop(guards.remove(reservation_index));
+				}
+			}
+
+			Operation::Consume { maximum_to_release } =>
+			{
+				let mut slice_guard = ring_buffer_consumer.consume();
+
+				let consumed = address_range(slice_guard.as_slice());
+				for guards in held_guards.iter()
+				{
+					for producer_slice_guard in guards.iter()
+					{
+						assert!(!overlaps(&consumed, &address_range(producer_slice_guard)), "consumer slice overlaps an in-flight producer reservation");
+					}
+				}
+
+				let release_count = min(maximum_to_release as usize, slice_guard.len());
+				check_messages(&slice_guard.as_slice()[.. release_count], &mut expected_sequence_numbers);
+
+				slice_guard.release(release_count);
+				unreleased -= release_count;
+			}
+		}
+
+		assert_eq!(ring_buffer_consumer.assert_invariants(), unreleased, "next has caught up with written");
+	}
+});
@@ -0,0 +1,54 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2017 - 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// A hand (offset) shared between the producers and the consumer, and so between threads (and, for a `SharedMemoryRingBuffer`, processes).
+///
+/// Reads are `Acquire` and writes `Release`, so that everything written before a hand is moved is visible to whoever observes it moved; the algorithm's explicit fences order accesses of different hands.
+/// When model checking with `loom`, this is `loom`'s `AtomicUsize` with the same orderings.
+#[derive(Debug)]
+struct AtomicRingBufferOffset(AtomicUsize);
+
+impl AtomicRingBufferOffset
+{
+	#[inline(always)]
+	pub(crate) fn new(value: RingBufferOffset) -> Self
+	{
+		AtomicRingBufferOffset(AtomicUsize::new(value))
+	}
+
+	/// `x = self`.
+	#[inline(always)]
+	pub(crate) fn read(&self) -> RingBufferOffset
+	{
+		self.0.load(Acquire)
+	}
+
+	/// `self = value`.
+	#[inline(always)]
+	pub(crate) fn write(&self, value: RingBufferOffset)
+	{
+		self.0.store(value, Release)
+	}
+
+	/// `self &= and_equals_value`.
+	#[inline(always)]
+	pub(crate) fn and_equals(&self, and_equals_value: RingBufferOffset)
+	{
+		self.0.fetch_and(and_equals_value, Release);
+	}
+
+	#[inline(always)]
+	pub(crate) fn atomic_compare_and_exchange_weak(&self, old: RingBufferOffset, source: RingBufferOffset) -> bool
+	{
+		#[cfg(feature = "fault-injection")]
+		{
+			if FaultInjector::compare_and_exchange_fails()
+			{
+				return false
+			}
+		}
+
+		self.0.compare_exchange_weak(old, source, AcqRel, Acquire).is_ok()
+	}
+}
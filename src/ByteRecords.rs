@@ -0,0 +1,28 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Iterates the unread records of a `ByteRingBufferConsumerGuard` without consuming them.
+#[derive(Debug, Clone)]
+pub struct ByteRecords<'a>
+{
+	units: &'a [u64],
+}
+
+impl<'a> Iterator for ByteRecords<'a>
+{
+	type Item = &'a [u8];
+
+	#[inline(always)]
+	fn next(&mut self) -> Option<Self::Item>
+	{
+		if self.units.is_empty()
+		{
+			return None
+		}
+
+		let (record, record_units) = ByteRingBuffer::record(self.units);
+		self.units = &self.units[record_units ..];
+		Some(record)
+	}
+}
@@ -0,0 +1,95 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// A ring buffer of variable-length records of bytes, like the original C code's `ringbuf`.
+///
+/// Each record is stored as a header, containing its length (and, for a `MessageRingBuffer`, the `RingMessageTag` of its message), followed by its bytes, padded to a multiple of `Self::RecordAlignment`; a record always starts on a multiple of `Self::RecordAlignment`.
+/// A record is never split by a wrap-around: if it does not fit before the end of the buffer, it is acquired at the start instead, and the consumer skips the space left at the end.
+///
+/// There are no values of this type; it only creates consumers and producers.
+#[derive(Debug)]
+pub enum ByteRingBuffer
+{
+}
+
+impl ByteRingBuffer
+{
+	/// The alignment of the header and bytes of every record, and the size of the header.
+	pub const RecordAlignment: usize = size_of::<u64>();
+
+	/// The tag is in the top 16 bits of the header and the length in the rest.
+	const TagShift: u64 = 48;
+
+	const LengthMask: u64 = (1 << Self::TagShift) - 1;
+
+	/// Creates a new ring buffer of at least `capacity` bytes and returns a consumer to it and producers for it.
+	///
+	/// Each producer can only have one reservation (acquisition) at a time.
+	///
+	/// A record of `length` bytes uses `Self::record_size(length)` bytes of the capacity.
+	#[inline(always)]
+	pub fn new(capacity: usize, number_of_producers: usize) -> (ByteRingBufferConsumer, Vec<ByteRingBufferProducer>)
+	{
+		Self::new_with_maximum_reservations(capacity, &vec![1; number_of_producers])
+	}
+
+	/// Creates a new ring buffer of at least `capacity` bytes and returns a consumer to it and producers for it.
+	///
+	/// `maximum_reservations_per_producer` is as for `RingBuffer::new_with_maximum_reservations()`.
+	#[inline(always)]
+	pub fn new_with_maximum_reservations(capacity: usize, maximum_reservations_per_producer: &[usize]) -> (ByteRingBufferConsumer, Vec<ByteRingBufferProducer>)
+	{
+		let (consumer, producers) = RingBuffer::new_with_maximum_reservations(Self::units(capacity), maximum_reservations_per_producer);
+		(ByteRingBufferConsumer(consumer), producers.into_iter().map(ByteRingBufferProducer).collect())
+	}
+
+	/// The count of bytes of the capacity used by a record of `length` bytes, including its header and padding.
+	#[inline(always)]
+	pub fn record_size(length: usize) -> usize
+	{
+		Self::record_units(length) * Self::RecordAlignment
+	}
+
+	/// The count of `u64`s used by a record of `length` bytes.
+	#[inline(always)]
+	fn record_units(length: usize) -> usize
+	{
+		1 + Self::units(length)
+	}
+
+	#[inline(always)]
+	fn units(length: usize) -> usize
+	{
+		length.div_ceil(Self::RecordAlignment)
+	}
+
+	#[inline(always)]
+	fn header(tag: RingMessageTag, length: usize) -> u64
+	{
+		((tag as u64) << Self::TagShift) | (length as u64)
+	}
+
+	#[inline(always)]
+	fn length(header: u64) -> usize
+	{
+		(header & Self::LengthMask) as usize
+	}
+
+	#[inline(always)]
+	fn tag(header: u64) -> RingMessageTag
+	{
+		(header >> Self::TagShift) as RingMessageTag
+	}
+
+	/// `units` must start with a record; returns its bytes and the count of `u64`s it uses.
+	#[inline(always)]
+	fn record(units: &[u64]) -> (&[u8], usize)
+	{
+		let length = Self::length(units[0]);
+		let record_units = Self::record_units(length);
+		debug_assert!(record_units <= units.len(), "record of length '{}' is split", length);
+
+		(unsafe { from_raw_parts(units[1 ..].as_ptr() as *const u8, length) }, record_units)
+	}
+}
@@ -0,0 +1,71 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Use this to consume the records of a `ByteRingBuffer`.
+///
+/// As for `RingBufferConsumerGuard`, when dropped, the records read are "released" (dequeued completely) in a burst; any unread records are returned to the queue and can be read again.
+#[derive(Debug)]
+pub struct ByteRingBufferConsumerGuard<'a>(RingBufferConsumerGuard<'a, u64>);
+
+impl<'a> ByteRingBufferConsumerGuard<'a>
+{
+	/// Are there no unread records?
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool
+	{
+		self.0.is_empty()
+	}
+
+	/// The count of unread records; walks them all.
+	#[inline(always)]
+	pub fn len(&self) -> usize
+	{
+		self.records().count()
+	}
+
+	/// Iterates the unread records without consuming them.
+	#[inline(always)]
+	pub fn records<'b>(&'b self) -> ByteRecords<'b>
+	{
+		ByteRecords
+		{
+			units: self.0.as_slice(),
+		}
+	}
+
+	/// Reads the next unread record; its space is released back to the ring buffer when this guard is dropped (or on the next call to `self.release()`).
+	///
+	/// Returns `None` if there are no unread records.
+	#[inline(always)]
+	pub fn next_record(&mut self) -> Option<&[u8]>
+	{
+		self.next_tagged_record().map(|(_tag, record)| record)
+	}
+
+	/// As `self.next_record()`, but also returns the tag recorded by `ByteRingBufferProducer::acquire_tagged()`.
+	#[inline(always)]
+	pub(crate) fn next_tagged_record(&mut self) -> Option<(RingMessageTag, &[u8])>
+	{
+		if self.is_empty()
+		{
+			return None
+		}
+
+		let tag = ByteRingBuffer::tag(self.0.as_slice()[0]);
+		let (record, record_units) = ByteRingBuffer::record(self.0.as_slice());
+		let (pointer, length) = (record.as_ptr(), record.len());
+		self.0.discard(record_units);
+		Some((tag, unsafe { from_raw_parts(pointer, length) }))
+	}
+
+	/// Reads the next `count` unread records and immediately releases their space (and that of any records already read) back to the ring buffer for producers to use.
+	///
+	/// `count` is clamped to `self.len()`.
+	#[inline(always)]
+	pub fn release(&mut self, count: usize)
+	{
+		let units = self.records().take(count).map(|record| ByteRingBuffer::record_units(record.len())).sum();
+		self.0.release(units)
+	}
+}
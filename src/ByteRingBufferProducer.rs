@@ -0,0 +1,51 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// A producer of records for a `ByteRingBuffer`.
+#[derive(Debug)]
+pub struct ByteRingBufferProducer(RingBufferProducer<u64>);
+
+impl ByteRingBufferProducer
+{
+	/// Reserves a record of `length` bytes, which can be zero.
+	///
+	/// The record is produced when the returned guard is dropped; its bytes are zero'd.
+	///
+	/// Returns an Err if there is not `ByteRingBuffer::record_size(length)` bytes of contiguous space, if that exceeds the capacity of the ring buffer, or if this producer already has its maximum number of reservations.
+	#[inline(always)]
+	pub fn acquire<'a>(&'a self, length: usize) -> Result<ByteRingBufferProducerGuard<'a>, ()>
+	{
+		self.acquire_tagged(0, length)
+	}
+
+	/// As `self.acquire()`, but also records `tag` in the header.
+	#[inline(always)]
+	pub(crate) fn acquire_tagged<'a>(&'a self, tag: RingMessageTag, length: usize) -> Result<ByteRingBufferProducerGuard<'a>, ()>
+	{
+		let record_units = ByteRingBuffer::record_units(length);
+		if record_units > self.0.reference().capacity
+		{
+			return Err(())
+		}
+
+		let mut ring_buffer_producer_guard = self.0.acquire(record_units)?;
+
+		// So that neither the bytes, which are exposed as a safe slice, nor the padding are ever uninitialized or left over from an earlier record.
+		ring_buffer_producer_guard.fill(0);
+
+		ring_buffer_producer_guard[0] = ByteRingBuffer::header(tag, length);
+
+		Ok(ByteRingBufferProducerGuard(ring_buffer_producer_guard))
+	}
+
+	/// Reserves a record of `bytes.len()` bytes, copies `bytes` into it and produces it.
+	///
+	/// Returns an Err as for `self.acquire()`.
+	#[inline(always)]
+	pub fn produce(&self, bytes: &[u8]) -> Result<(), ()>
+	{
+		self.acquire(bytes.len())?.copy_from_slice(bytes);
+		Ok(())
+	}
+}
@@ -0,0 +1,34 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Use this to produce a record of a `ByteRingBuffer`.
+///
+/// When dropped, the record is "produced" (enqueued).
+///
+/// Use `DerefMut` to access the bytes of the record, which start on a multiple of `ByteRingBuffer::RecordAlignment`.
+///
+/// The bytes are zero'd when the record is acquired.
+#[derive(Debug)]
+pub struct ByteRingBufferProducerGuard<'a>(RingBufferProducerGuard<'a, u64>);
+
+impl<'a> Deref for ByteRingBufferProducerGuard<'a>
+{
+	type Target = [u8];
+
+	#[inline(always)]
+	fn deref(&self) -> &Self::Target
+	{
+		ByteRingBuffer::record(&self.0).0
+	}
+}
+
+impl<'a> DerefMut for ByteRingBufferProducerGuard<'a>
+{
+	#[inline(always)]
+	fn deref_mut(&mut self) -> &mut Self::Target
+	{
+		let length = ByteRingBuffer::length(self.0[0]);
+		unsafe { from_raw_parts_mut(self.0[1 ..].as_mut_ptr() as *mut u8, length) }
+	}
+}
@@ -0,0 +1,47 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Why `RingBufferConsumer::consume_detailed()` returned what it did.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ConsumeStatus
+{
+	/// Nothing has been acquired by any producer since the consumer last released.
+	///
+	/// Also returned by the consumer of a `SharedMemoryRingBuffer` if the hands in the memory were impossible (see `SharedMemoryRingBuffer::corrupt_hands()`), or if a producer held the wrap-around lock of the `next` hand for too long.
+	Empty,
+
+	/// Nothing is ready because the reservation at the start of what would be consumed has been acquired but not yet produced; producers might have acquired and produced items after it.
+	///
+	/// See `RingBufferConsumer::stalled_producer()` for how long it has been so.
+	///
+	/// Also returned by the consumer of a `SharedMemoryRingBuffer` if the reservation held its wrap-around lock for too long, eg because its producer process died part way through acquiring it.
+	BlockedByProducer
+	{
+		/// The index of the producer.
+		producer_index: usize,
+
+		/// Which of the producer's reservations it is, counting from zero.
+		reservation_index: usize,
+	},
+
+	/// An `OwnedBatch` is outstanding; nothing can be consumed until it has been dropped.
+	OwnedBatchOutstanding,
+
+	/// This count of items is ready.
+	///
+	/// There might be fewer than have been produced, if a reservation after them has been acquired but not yet produced.
+	Ready(usize),
+
+	/// `count` items are ready up to where the producers wrapped-around to the start of the buffer, and `wrapped_count` more are ready at the start of the buffer.
+	///
+	/// Only `count` items are returned; the others can be consumed once these have been released.
+	WrapPending
+	{
+		/// The count of items returned.
+		count: usize,
+
+		/// The count of items ready at the start of the buffer.
+		wrapped_count: usize,
+	},
+}
@@ -0,0 +1,108 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2017 - 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// A batch of items ready to be consumed, passed to the closure given to `RingBufferConsumer::consume_with()`.
+///
+/// If the ready range wraps-around the end of the ring buffer, then it is made up of two segments; the second segment is only non-empty if the first is.
+///
+/// Items are borrowed in place; the closure reports how many items, counting from the start of the first segment, it handled.
+/// Handled items are dropped; all others are returned to the queue and can be read again.
+#[derive(Debug)]
+pub struct ConsumedBatch<'a, T: 'a + Sized>
+{
+	first: &'a mut [T],
+
+	second: &'a mut [T],
+}
+
+impl<'a, T: 'a + Sized> ConsumedBatch<'a, T>
+{
+	/// Is this batch empty?
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool
+	{
+		self.first.is_empty()
+	}
+
+	/// Number of items in this batch (in both segments).
+	#[inline(always)]
+	pub fn len(&self) -> usize
+	{
+		self.first.len() + self.second.len()
+	}
+
+	/// Borrows the item at `index`, counting from the start of the first segment.
+	#[inline(always)]
+	pub fn get(&self, index: usize) -> Option<&T>
+	{
+		let first_length = self.first.len();
+		if index < first_length
+		{
+			Some(unsafe { self.first.get_unchecked(index) })
+		}
+		else
+		{
+			self.second.get(index - first_length)
+		}
+	}
+
+	/// Mutably borrows the item at `index`, counting from the start of the first segment.
+	#[inline(always)]
+	pub fn get_mut(&mut self, index: usize) -> Option<&mut T>
+	{
+		let first_length = self.first.len();
+		if index < first_length
+		{
+			Some(unsafe { self.first.get_unchecked_mut(index) })
+		}
+		else
+		{
+			self.second.get_mut(index - first_length)
+		}
+	}
+
+	/// Borrows both segments.
+	#[inline(always)]
+	pub fn as_slices(&self) -> (&[T], &[T])
+	{
+		(&self.first[..], &self.second[..])
+	}
+
+	/// Mutably borrows both segments, so that items can be processed in place.
+	#[inline(always)]
+	pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T])
+	{
+		(&mut self.first[..], &mut self.second[..])
+	}
+
+	/// Iterates over the items in both segments, in order.
+	#[inline(always)]
+	pub fn iter<'b>(&'b self) -> Chain<Iter<'b, T>, Iter<'b, T>>
+	{
+		self.first.iter().chain(self.second.iter())
+	}
+
+	/// Mutably iterates over the items in both segments, in order.
+	#[inline(always)]
+	pub fn iter_mut<'b>(&'b mut self) -> Chain<IterMut<'b, T>, IterMut<'b, T>>
+	{
+		self.first.iter_mut().chain(self.second.iter_mut())
+	}
+
+	/// Drops the first `handled` items (which must not exceed `self.len()`) and returns the count of those that came from the first segment.
+	#[inline(always)]
+	fn drop_handled(&mut self, handled: usize) -> usize
+	{
+		let first_handled = min(handled, self.first.len());
+		let second_handled = handled - first_handled;
+
+		unsafe
+		{
+			drop_in_place(&mut self.first[.. first_handled]);
+			drop_in_place(&mut self.second[.. second_handled]);
+		}
+
+		first_handled
+	}
+}
@@ -0,0 +1,144 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Defends a consumer of memory shared with other processes against producer processes which are buggy or hostile, and so might write anything into the memory.
+///
+/// Process-local, so that nothing here can be changed by another process.
+/// Every hand the consumer reads from the memory is bounds-checked; a producer reservation which publishes an impossible `seen` offset has its producer quarantined, and its offsets are ignored from then on.
+#[derive(Debug)]
+struct ConsumerHardening
+{
+	/// The consumer's own copy of the `written` hand; the copy in the memory is only read by producers.
+	written: AtomicUsize,
+
+	/// The index of the producer of each producer reservation; built from the `RingBufferProducerSlot`s when this process's view of the memory is created.
+	producer_index_of_producer_reservation: Box<[usize]>,
+
+	/// Whether each producer is quarantined.
+	quarantined: Box<[AtomicBool]>,
+
+	/// Counts the times the `next`, `end` or `written` hands were impossible; these can not be attributed to a producer.
+	corrupt_hands: AtomicUsize,
+}
+
+impl ConsumerHardening
+{
+	/// How many times the consumer backs off whilst waiting for a producer to release a wrap-around lock before giving up on this consumption (a fraction of a second); the lock is only held for a few instructions unless its producer has been preempted, has died or is hostile.
+	const MaximumBackOffs: usize = 1 << 14;
+
+	/// `first_ring_buffer_producer_slot` must point to `number_of_producers` `RingBufferProducerSlot`s whose reservations have been validated to exactly partition `0 .. number_of_producer_reservations` (see `Self::validate_producer_slots()`).
+	///
+	/// Panics if `number_of_producers` is zero (which `RingBufferFormat::validate()` rejects).
+	#[inline(always)]
+	unsafe fn new(first_ring_buffer_producer_slot: NonNull<RingBufferProducerSlot>, number_of_producers: usize, number_of_producer_reservations: usize, written: RingBufferOffset) -> Self
+	{
+		assert_ne!(number_of_producers, 0, "number_of_producers can not be zero");
+
+		// Bounded by `number_of_producer_reservations` in case another process has changed the slots since they were validated.
+		let mut producer_index_of_producer_reservation = Vec::with_capacity(number_of_producer_reservations);
+		for producer_index in 0 .. number_of_producers
+		{
+			let ring_buffer_producer_slot = &* first_ring_buffer_producer_slot.as_ptr().add(producer_index);
+			let remaining = number_of_producer_reservations - producer_index_of_producer_reservation.len();
+			for _ in 0 .. min(ring_buffer_producer_slot.maximum_reservations, remaining)
+			{
+				producer_index_of_producer_reservation.push(producer_index);
+			}
+		}
+		producer_index_of_producer_reservation.resize(number_of_producer_reservations, number_of_producers - 1);
+
+		Self
+		{
+			written: AtomicUsize::new(written),
+			producer_index_of_producer_reservation: producer_index_of_producer_reservation.into_boxed_slice(),
+			quarantined: (0 .. number_of_producers).map(|_| AtomicBool::new(false)).collect::<Vec<_>>().into_boxed_slice(),
+			corrupt_hands: AtomicUsize::new(0),
+		}
+	}
+
+	/// Checks that the reservations of the `RingBufferProducerSlot`s are contiguous, non-empty and together exactly `0 .. number_of_producer_reservations`; returns the index of the first producer which is not.
+	///
+	/// Panics if `number_of_producers` is zero (which `RingBufferFormat::validate()` rejects).
+	#[inline(always)]
+	fn validate_producer_slots(first_ring_buffer_producer_slot: NonNull<RingBufferProducerSlot>, number_of_producers: usize, number_of_producer_reservations: usize) -> Result<(), usize>
+	{
+		assert_ne!(number_of_producers, 0, "number_of_producers can not be zero");
+
+		let mut first_producer_reservation_index = 0;
+		for producer_index in 0 .. number_of_producers
+		{
+			let ring_buffer_producer_slot = unsafe { &* first_ring_buffer_producer_slot.as_ptr().add(producer_index) };
+			let maximum_reservations = ring_buffer_producer_slot.maximum_reservations;
+			if ring_buffer_producer_slot.first_producer_reservation_index != first_producer_reservation_index || maximum_reservations == 0 || maximum_reservations > number_of_producer_reservations - first_producer_reservation_index
+			{
+				return Err(producer_index)
+			}
+			first_producer_reservation_index += maximum_reservations;
+		}
+
+		if first_producer_reservation_index == number_of_producer_reservations
+		{
+			Ok(())
+		}
+		else
+		{
+			Err(number_of_producers - 1)
+		}
+	}
+
+	#[inline(always)]
+	fn written(&self) -> RingBufferOffset
+	{
+		self.written.load(Relaxed)
+	}
+
+	#[inline(always)]
+	fn set_written(&self, written: RingBufferOffset)
+	{
+		self.written.store(written, Relaxed)
+	}
+
+	#[inline(always)]
+	fn producer_index(&self, producer_reservation_index: usize) -> usize
+	{
+		self.producer_index_of_producer_reservation[producer_reservation_index]
+	}
+
+	#[inline(always)]
+	fn first_producer_reservation_index(&self, producer_index: usize) -> usize
+	{
+		self.producer_index_of_producer_reservation.iter().position(|&index| index == producer_index).unwrap_or(0)
+	}
+
+	#[inline(always)]
+	fn is_quarantined(&self, producer_index: usize) -> bool
+	{
+		self.quarantined[producer_index].load(Relaxed)
+	}
+
+	#[inline(always)]
+	fn quarantine(&self, producer_index: usize)
+	{
+		self.quarantined[producer_index].store(true, Relaxed)
+	}
+
+	#[inline(always)]
+	fn quarantined_producers(&self) -> Vec<usize>
+	{
+		(0 .. self.quarantined.len()).filter(|&producer_index| self.is_quarantined(producer_index)).collect()
+	}
+
+	#[cold]
+	#[inline(never)]
+	fn corrupt_hand(&self)
+	{
+		self.corrupt_hands.fetch_add(1, Relaxed);
+	}
+
+	#[inline(always)]
+	fn corrupt_hands(&self) -> usize
+	{
+		self.corrupt_hands.load(Relaxed)
+	}
+}
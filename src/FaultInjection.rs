@@ -0,0 +1,34 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Controls the `fault-injection` feature, which injects faults (spurious compare-and-exchange failures, yields and sleeps) into a thread's use of ring buffers.
+///
+/// Faults are only injected on threads for which it has been enabled.
+/// Each thread draws whether to inject a fault from its own pseudo-random sequence, so an interleaving found by one set of seeds can be reproduced by re-running with the same seeds.
+#[derive(Debug)]
+pub struct FaultInjection;
+
+impl FaultInjection
+{
+	/// Enables fault injection on the current thread, replacing any previous configuration.
+	#[inline(always)]
+	pub fn enable_for_current_thread(configuration: FaultInjectionConfiguration)
+	{
+		FaultInjector::enable(configuration)
+	}
+
+	/// Disables fault injection on the current thread.
+	#[inline(always)]
+	pub fn disable_for_current_thread()
+	{
+		FaultInjector::disable()
+	}
+
+	/// The number of faults (spurious compare-and-exchange failures, yields and sleeps) injected on the current thread since fault injection was last enabled.
+	#[inline(always)]
+	pub fn number_of_faults_injected_on_current_thread() -> u64
+	{
+		FaultInjector::number_of_faults_injected()
+	}
+}
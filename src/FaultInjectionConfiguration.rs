@@ -0,0 +1,47 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Configures the faults injected into a thread; see `FaultInjection::enable_for_current_thread()`.
+///
+/// Each `_one_in` field is the inverse of the probability of injecting that fault; `0` never injects it.
+#[derive(Default)]
+pub struct FaultInjectionConfiguration
+{
+	/// Seeds the pseudo-random sequence from which whether to inject each fault is drawn.
+	pub seed: u64,
+
+	/// Makes a weak compare-and-exchange fail spuriously (without exchanging).
+	pub compare_and_exchange_failure_one_in: u32,
+
+	/// Yields at a `FaultInjectionPoint`.
+	pub yield_one_in: u32,
+
+	/// Sleeps for up to `maximum_sleep` at a `FaultInjectionPoint`.
+	pub sleep_one_in: u32,
+
+	/// The longest sleep.
+	pub maximum_sleep: Duration,
+
+	/// Called at every `FaultInjectionPoint`, before any yield or sleep.
+	///
+	/// It can block, eg until another thread has made progress, to force a particular interleaving.
+	/// It must not itself use a ring buffer.
+	pub at_point: Option<Box<dyn FnMut(FaultInjectionPoint)>>,
+}
+
+impl Debug for FaultInjectionConfiguration
+{
+	#[inline(always)]
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result
+	{
+		f.debug_struct("FaultInjectionConfiguration")
+			.field("seed", &self.seed)
+			.field("compare_and_exchange_failure_one_in", &self.compare_and_exchange_failure_one_in)
+			.field("yield_one_in", &self.yield_one_in)
+			.field("sleep_one_in", &self.sleep_one_in)
+			.field("maximum_sleep", &self.maximum_sleep)
+			.field("at_point", &self.at_point.is_some())
+			.finish()
+	}
+}
@@ -0,0 +1,32 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// A point in the algorithm at which the `fault-injection` feature can call a hook, yield or sleep.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum FaultInjectionPoint
+{
+	/// In `acquire()`, after the producer has saved the observed `next` offset as its (unstable) `seen` offset, and before it reads the `written` offset.
+	AcquireAfterSeenOffsetWrite,
+
+	/// In `acquire()`, immediately before the compare-and-exchange of the `next` offset.
+	AcquireBeforeCompareAndExchange,
+
+	/// In `acquire()`, after wrapping-around with the `WrapLockBit` set in the `next` offset, and before the `end` offset is set and the lock released.
+	AcquireWhilstWrapLocked,
+
+	/// In `produce()`, before the producer clears its `seen` offset.
+	ProduceBeforeSeenOffsetClear,
+
+	/// In `consume()`, after reading the `next` offset and before observing the `seen` offsets.
+	ConsumeAfterNextRead,
+
+	/// In `consume()`, before observing each producer reservation's `seen` offset.
+	ConsumeBeforeSeenOffsetRead,
+
+	/// In `consume()`, before the consumer wraps-around.
+	ConsumeBeforeWrapAround,
+
+	/// In `release()`, before the `written` offset is updated.
+	ReleaseBeforeWrittenWrite,
+}
@@ -0,0 +1,125 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+thread_local!
+{
+	static CurrentThreadFaultInjector: RefCell<Option<FaultInjector>> = const { RefCell::new(None) };
+}
+
+/// The state of fault injection for a thread.
+struct FaultInjector
+{
+	configuration: FaultInjectionConfiguration,
+	random: XorShift,
+	number_of_faults_injected: u64,
+}
+
+impl FaultInjector
+{
+	#[inline(always)]
+	fn enable(configuration: FaultInjectionConfiguration)
+	{
+		let random = XorShift::new(configuration.seed);
+		CurrentThreadFaultInjector.with(|current| *current.borrow_mut() = Some
+		(
+			Self
+			{
+				configuration,
+				random,
+				number_of_faults_injected: 0,
+			}
+		))
+	}
+
+	#[inline(always)]
+	fn disable()
+	{
+		CurrentThreadFaultInjector.with(|current| *current.borrow_mut() = None)
+	}
+
+	#[inline(always)]
+	fn number_of_faults_injected() -> u64
+	{
+		CurrentThreadFaultInjector.with(|current| current.borrow().as_ref().map(|this| this.number_of_faults_injected).unwrap_or(0))
+	}
+
+	/// Should a weak compare-and-exchange fail spuriously?
+	#[inline(always)]
+	fn compare_and_exchange_fails() -> bool
+	{
+		CurrentThreadFaultInjector.with(|current| match current.borrow_mut().as_mut()
+		{
+			None => false,
+			Some(this) =>
+			{
+				let one_in = this.configuration.compare_and_exchange_failure_one_in;
+				this.inject(one_in)
+			}
+		})
+	}
+
+	/// Calls the hook, if any, then perhaps yields or sleeps.
+	#[inline(always)]
+	fn at(point: FaultInjectionPoint)
+	{
+		// The hook is taken out whilst it is called, so that a `RefCell` borrow is not held whilst it blocks.
+		let at_point = CurrentThreadFaultInjector.with(|current| current.borrow_mut().as_mut().and_then(|this| this.configuration.at_point.take()));
+		if let Some(mut at_point) = at_point
+		{
+			at_point(point);
+			CurrentThreadFaultInjector.with(|current| if let Some(this) = current.borrow_mut().as_mut()
+			{
+				this.configuration.at_point = Some(at_point)
+			});
+		}
+
+		let (yield_now, sleep_for) = CurrentThreadFaultInjector.with(|current| match current.borrow_mut().as_mut()
+		{
+			None => (false, None),
+			Some(this) =>
+			{
+				let yield_one_in = this.configuration.yield_one_in;
+				let yield_now = this.inject(yield_one_in);
+
+				let sleep_one_in = this.configuration.sleep_one_in;
+				let sleep_for = if this.inject(sleep_one_in)
+				{
+					let maximum_sleep_in_nanoseconds = this.configuration.maximum_sleep.as_nanos() as u64;
+					Some(Duration::from_nanos(this.random.next() % (maximum_sleep_in_nanoseconds + 1)))
+				}
+				else
+				{
+					None
+				};
+
+				(yield_now, sleep_for)
+			}
+		});
+
+		if yield_now
+		{
+			::std::thread::yield_now()
+		}
+		if let Some(sleep_for) = sleep_for
+		{
+			sleep(sleep_for)
+		}
+	}
+
+	#[inline(always)]
+	fn inject(&mut self, one_in: u32) -> bool
+	{
+		if one_in == 0
+		{
+			return false
+		}
+
+		let inject = self.random.next().is_multiple_of(one_in as u64);
+		if inject
+		{
+			self.number_of_faults_injected += 1;
+		}
+		inject
+	}
+}
@@ -0,0 +1,45 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// A ring buffer of messages of a `RingMessage` type, usually an enum of several kinds of message, in which each message uses only the space of its own variant rather than that of the largest.
+///
+/// Each message is stored as a record of a `ByteRingBuffer`, whose header contains the `RingMessageTag` of its variant as well as its size.
+/// Messages are moved in and out without any allocation; the consumer gets each back by value, to `match` on.
+///
+/// Messages which are never consumed are never dropped.
+///
+/// There are no values of this type; it only creates consumers and producers.
+#[derive(Debug)]
+pub struct MessageRingBuffer<M: RingMessage>(PhantomData<M>);
+
+impl<M: RingMessage> MessageRingBuffer<M>
+{
+	/// Creates a new ring buffer of at least `capacity` bytes and returns a consumer to it and producers for it.
+	///
+	/// Each producer can only have one reservation (acquisition) at a time.
+	///
+	/// A message uses `Self::message_size(&message)` bytes of the capacity.
+	#[inline(always)]
+	pub fn new(capacity: usize, number_of_producers: usize) -> (MessageRingBufferConsumer<M>, Vec<MessageRingBufferProducer<M>>)
+	{
+		Self::new_with_maximum_reservations(capacity, &vec![1; number_of_producers])
+	}
+
+	/// Creates a new ring buffer of at least `capacity` bytes and returns a consumer to it and producers for it.
+	///
+	/// `maximum_reservations_per_producer` is as for `RingBuffer::new_with_maximum_reservations()`.
+	#[inline(always)]
+	pub fn new_with_maximum_reservations(capacity: usize, maximum_reservations_per_producer: &[usize]) -> (MessageRingBufferConsumer<M>, Vec<MessageRingBufferProducer<M>>)
+	{
+		let (consumer, producers) = ByteRingBuffer::new_with_maximum_reservations(capacity, maximum_reservations_per_producer);
+		(MessageRingBufferConsumer(consumer, PhantomData), producers.into_iter().map(|producer| MessageRingBufferProducer(producer, PhantomData)).collect())
+	}
+
+	/// The count of bytes of the capacity used by `message`, including its header and padding.
+	#[inline(always)]
+	pub fn message_size(message: &M) -> usize
+	{
+		ByteRingBuffer::record_size(message.tag_and_size().1)
+	}
+}
@@ -0,0 +1,28 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// A consumer of a `MessageRingBuffer`.
+#[derive(Debug)]
+pub struct MessageRingBufferConsumer<M: RingMessage>(ByteRingBufferConsumer, PhantomData<M>);
+
+impl<M: RingMessage> MessageRingBufferConsumer<M>
+{
+	/// Gets the messages which are ready to be consumed.
+	///
+	/// Borrows this consumer mutably, as for `RingBufferConsumer::consume()`.
+	///
+	/// Not particularly cheap (as it walks all producers) so try to take as much as possible.
+	#[inline(always)]
+	pub fn consume<'a>(&'a mut self) -> MessageRingBufferConsumerGuard<'a, M>
+	{
+		MessageRingBufferConsumerGuard(self.0.consume(), PhantomData)
+	}
+
+	/// As `ByteRingBufferConsumer::stalled_producer()`.
+	#[inline(always)]
+	pub fn stalled_producer(&self) -> Option<StalledProducer>
+	{
+		self.0.stalled_producer()
+	}
+}
@@ -0,0 +1,30 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Use this to consume the messages of a `MessageRingBuffer`; iterating it moves each message out, by value.
+///
+/// As for `ByteRingBufferConsumerGuard`, when dropped, the space of the messages moved out is "released" in a burst; any others are returned to the queue and can be consumed again.
+#[derive(Debug)]
+pub struct MessageRingBufferConsumerGuard<'a, M: RingMessage>(ByteRingBufferConsumerGuard<'a>, PhantomData<M>);
+
+impl<'a, M: RingMessage> Iterator for MessageRingBufferConsumerGuard<'a, M>
+{
+	type Item = M;
+
+	#[inline(always)]
+	fn next(&mut self) -> Option<Self::Item>
+	{
+		self.0.next_tagged_record().map(|(tag, record)| unsafe { M::read_payload(tag, record.as_ptr()) })
+	}
+}
+
+impl<'a, M: RingMessage> MessageRingBufferConsumerGuard<'a, M>
+{
+	/// Are there no messages left to move out?
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool
+	{
+		self.0.is_empty()
+	}
+}
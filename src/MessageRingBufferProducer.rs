@@ -0,0 +1,29 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// A producer of messages for a `MessageRingBuffer`.
+#[derive(Debug)]
+pub struct MessageRingBufferProducer<M: RingMessage>(ByteRingBufferProducer, PhantomData<M>);
+
+impl<M: RingMessage> MessageRingBufferProducer<M>
+{
+	/// Moves `message` into a record of `MessageRingBuffer::message_size(&message)` bytes and produces it.
+	///
+	/// Returns `message` as an Err if its record can not be acquired, as for `ByteRingBufferProducer::acquire()`.
+	#[inline(always)]
+	pub fn produce(&self, message: M) -> Result<(), M>
+	{
+		let (tag, size) = message.tag_and_size();
+		match self.0.acquire_tagged(tag, size)
+		{
+			Ok(mut byte_ring_buffer_producer_guard) =>
+			{
+				unsafe { message.write_payload(byte_ring_buffer_producer_guard.as_mut_ptr()) };
+				Ok(())
+			}
+
+			Err(()) => Err(message),
+		}
+	}
+}
@@ -0,0 +1,149 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// The layout of the bits of the `next` offset (an offset, a wrap-around counter and a lock bit) and the arithmetic upon it.
+///
+/// `RingBufferInnerHeader` uses the implementation for `RingBufferOffset`, which has the layout for the target's pointer width.
+/// When verifying with Kani there are also implementations for `u16`, `u32` and `u64`, so the arithmetic for every pointer width can be proved on any host (see `offset_arithmetic_proofs.rs`).
+trait OffsetArithmetic: Copy + Ord + Add<Output=Self> + BitAnd<Output=Self> + BitOr<Output=Self>
+{
+	const Zero: Self;
+
+	/// Masks the wrap-around counter; it is incremented on every wrap-around to prevent an ABA problem.
+	const WrapCounter: Self;
+
+	/// The least significant bit of `Self::WrapCounter`.
+	const WrapCounterIncrement: Self;
+
+	/// Set in the `next` offset whilst a producer which has wrapped-around updates the `end` offset, and in a producer's `seen` offset whilst it is unstable.
+	const WrapLockBit: Self;
+
+	const OffsetMask: Self;
+
+	/// Computes the `target` value to compare-and-exchange the `next` offset with to acquire `count` items, given the `seen` (stable) value of the `next` offset.
+	///
+	/// If `Self::WrapLockBit` is set in the `target` then the reservation wrapped-around and starts at `0`.
+	///
+	/// Returns `Err(())` if the producer must wait for the consumer.
+	#[inline(always)]
+	fn acquire_target(seen: Self, count: Self, written: Self, capacity: Self) -> Result<Self, ()>
+	{
+		let next = seen & Self::OffsetMask;
+
+		// Compute the target offset.
+		// Key invariant: we cannot go beyond the `WRITTEN` offset or catch up with it.
+		let target = next + count;
+		if unlikely!(next < written && target >= written)
+		{
+			return Err(())
+		}
+
+		if unlikely!(target >= capacity)
+		{
+			let exceed = target > capacity;
+
+			// Wrap-around and start from the beginning.
+			//
+			// If we would exceed the buffer, then attempt to acquire the `Self::WrapLockBit` and use the space in the beginning.
+			// If we used all space exactly to the end, then reset to 0.
+			//
+			// Check the invariant again.
+			let target = if exceed
+			{
+				Self::WrapLockBit | count
+			}
+			else
+			{
+				Self::Zero
+			};
+			if (target & Self::OffsetMask) >= written
+			{
+				return Err(())
+			}
+
+			// Increment the wrap-around counter.
+			Ok(target | ((seen + Self::WrapCounterIncrement) & Self::WrapCounter))
+		}
+		else
+		{
+			// Preserve the wrap-around counter.
+			Ok(target | (seen & Self::WrapCounter))
+		}
+	}
+
+	/// Computes the `written` offset after releasing `count` items; the consumer wraps-around to `0` if it reached the end of the buffer.
+	#[inline(always)]
+	fn released_written(written: Self, count: Self, capacity: Self) -> Self
+	{
+		let number_written = written + count;
+		if number_written == capacity
+		{
+			Self::Zero
+		}
+		else
+		{
+			number_written
+		}
+	}
+}
+
+#[cfg(target_pointer_width = "64")]
+impl OffsetArithmetic for RingBufferOffset
+{
+	const Zero: Self = 0;
+	const WrapCounter: Self = 0x7FFFFFFF00000000;
+	const WrapCounterIncrement: Self = 0x0000000100000000;
+	const WrapLockBit: Self = 0x8000000000000000;
+	const OffsetMask: Self = 0x00000000FFFFFFFF;
+}
+
+#[cfg(target_pointer_width = "32")]
+impl OffsetArithmetic for RingBufferOffset
+{
+	const Zero: Self = 0;
+	const WrapCounter: Self = 0x7FFF0000;
+	const WrapCounterIncrement: Self = 0x00010000;
+	const WrapLockBit: Self = 0x80000000;
+	const OffsetMask: Self = 0x0000FFFF;
+}
+
+#[cfg(target_pointer_width = "16")]
+impl OffsetArithmetic for RingBufferOffset
+{
+	const Zero: Self = 0;
+	const WrapCounter: Self = 0x7F00;
+	const WrapCounterIncrement: Self = 0x0100;
+	const WrapLockBit: Self = 0x8000;
+	const OffsetMask: Self = 0x00FF;
+}
+
+#[cfg(kani)]
+impl OffsetArithmetic for u64
+{
+	const Zero: Self = 0;
+	const WrapCounter: Self = 0x7FFFFFFF00000000;
+	const WrapCounterIncrement: Self = 0x0000000100000000;
+	const WrapLockBit: Self = 0x8000000000000000;
+	const OffsetMask: Self = 0x00000000FFFFFFFF;
+}
+
+#[cfg(kani)]
+impl OffsetArithmetic for u32
+{
+	const Zero: Self = 0;
+	const WrapCounter: Self = 0x7FFF0000;
+	const WrapCounterIncrement: Self = 0x00010000;
+	const WrapLockBit: Self = 0x80000000;
+	const OffsetMask: Self = 0x0000FFFF;
+}
+
+#[cfg(kani)]
+impl OffsetArithmetic for u16
+{
+	const Zero: Self = 0;
+	const WrapCounter: Self = 0x7F00;
+	const WrapCounterIncrement: Self = 0x0100;
+	const WrapLockBit: Self = 0x8000;
+	const OffsetMask: Self = 0x00FF;
+}
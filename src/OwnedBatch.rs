@@ -0,0 +1,91 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2017 - 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// A consumed batch which, unlike `RingBufferConsumerGuard`, does not borrow the consumer.
+///
+/// It keeps the ring buffer alive, can be sent to another thread and can be held across an `.await`.
+///
+/// When dropped, any unread items are dropped and the entire range is "released" (dequeued completely).
+/// Until then, the consumer refuses further consumption.
+///
+/// Use `DerefMut` to access the unread items in place; use `Iterator::next()` to move them out.
+#[derive(Debug)]
+pub struct OwnedBatch<T: Sized>
+{
+	ring_buffer: RingBuffer<T>,
+
+	/// The first item of the batch; slices of it are only made on demand (shared by `deref()`, mutable by `deref_mut()`), so that a shared borrow never invalidates another.
+	first: NonNull<T>,
+
+	count: usize,
+
+	read_count: usize,
+}
+
+unsafe impl<T: Send> Send for OwnedBatch<T>
+{
+}
+
+impl<T: Sized> Drop for OwnedBatch<T>
+{
+	#[inline(always)]
+	fn drop(&mut self)
+	{
+		unsafe { drop_in_place(self.deref_mut()) };
+
+		let reference = self.ring_buffer.reference();
+		reference.release(self.count);
+		reference.set_owned_batch_outstanding(false)
+	}
+}
+
+impl<T: Sized> Deref for OwnedBatch<T>
+{
+	type Target = [T];
+
+	#[inline(always)]
+	fn deref(&self) -> &Self::Target
+	{
+		unsafe { from_raw_parts(self.unread(), self.count - self.read_count) }
+	}
+}
+
+impl<T: Sized> DerefMut for OwnedBatch<T>
+{
+	#[inline(always)]
+	fn deref_mut(&mut self) -> &mut Self::Target
+	{
+		unsafe { from_raw_parts_mut(self.unread(), self.count - self.read_count) }
+	}
+}
+
+impl<T: Sized> Iterator for OwnedBatch<T>
+{
+	type Item = T;
+
+	#[inline(always)]
+	fn next(&mut self) -> Option<Self::Item>
+	{
+		if self.read_count == self.count
+		{
+			return None
+		}
+
+		let next = unsafe { self.unread().read() };
+
+		self.read_count += 1;
+
+		Some(next)
+	}
+}
+
+impl<T: Sized> OwnedBatch<T>
+{
+	/// The first unread item.
+	#[inline(always)]
+	fn unread(&self) -> *mut T
+	{
+		unsafe { self.first.as_ptr().add(self.read_count) }
+	}
+}
@@ -0,0 +1,71 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2017 - 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// A reservation which, unlike `RingBufferProducerGuard`, does not borrow the producer.
+///
+/// It keeps the ring buffer alive and can be sent to, and committed from, another thread.
+///
+/// When dropped (or explicitly committed), the data owned by this reservation is "produced" (enqueued) in a burst.
+///
+/// Use `DerefMut` to access the data slice owned by this reservation.
+///
+/// This data is considered to be ***uninitialized***.
+#[derive(Debug)]
+pub struct OwnedReservation<T: Sized>
+{
+	ring_buffer: RingBuffer<T>,
+
+	ring_buffer_producer_inner_non_null: NonNull<RingBufferProducerInner>,
+
+	/// The first item of the reservation; slices of it are only made on demand (shared by `deref()`, mutable by `deref_mut()`), so that a shared borrow never invalidates another.
+	first: NonNull<T>,
+
+	count: usize,
+}
+
+unsafe impl<T: Send> Send for OwnedReservation<T>
+{
+}
+
+impl<T: Sized> Deref for OwnedReservation<T>
+{
+	type Target = [T];
+
+	#[inline(always)]
+	fn deref(&self) -> &Self::Target
+	{
+		unsafe { from_raw_parts(self.first.as_ptr(), self.count) }
+	}
+}
+
+impl<T: Sized> DerefMut for OwnedReservation<T>
+{
+	#[inline(always)]
+	fn deref_mut(&mut self) -> &mut Self::Target
+	{
+		unsafe { from_raw_parts_mut(self.first.as_ptr(), self.count) }
+	}
+}
+
+impl<T: Sized> Drop for OwnedReservation<T>
+{
+	#[inline(always)]
+	fn drop(&mut self)
+	{
+		self.ring_buffer.reference().produce(unsafe { self.ring_buffer_producer_inner_non_null.as_ref() }, self.count)
+	}
+}
+
+impl<T: Sized> OwnedReservation<T>
+{
+	/// Produces (enqueues) the data owned by this reservation.
+	///
+	/// All indices ***MUST*** have been populated with valid (initialized) data.
+	///
+	/// Equivalent to dropping this reservation.
+	#[inline(always)]
+	pub fn commit(self)
+	{
+	}
+}
@@ -0,0 +1,68 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// A type whose values can be put in memory shared with other processes, by a `SharedMemoryRingBuffer`.
+///
+/// Implemented for the primitive integers and arrays of them.
+///
+/// # Safety
+///
+/// The type must contain no pointers or references (including inside a `Box`, `Vec`, `String` or the like), as the memory is mapped at a different address in each process, and no interior mutability.
+///
+/// Every bit pattern must be a valid value (so not `bool`, `char`, an enum or a reference), as a buggy or hostile producer process can write anything into the memory and the consumer reads it as is.
+pub unsafe trait PlainOldData: Copy
+{
+}
+
+unsafe impl PlainOldData for u8
+{
+}
+
+unsafe impl PlainOldData for u16
+{
+}
+
+unsafe impl PlainOldData for u32
+{
+}
+
+unsafe impl PlainOldData for u64
+{
+}
+
+unsafe impl PlainOldData for u128
+{
+}
+
+unsafe impl PlainOldData for usize
+{
+}
+
+unsafe impl PlainOldData for i8
+{
+}
+
+unsafe impl PlainOldData for i16
+{
+}
+
+unsafe impl PlainOldData for i32
+{
+}
+
+unsafe impl PlainOldData for i64
+{
+}
+
+unsafe impl PlainOldData for i128
+{
+}
+
+unsafe impl PlainOldData for isize
+{
+}
+
+unsafe impl<T: PlainOldData, const N: usize> PlainOldData for [T; N]
+{
+}
@@ -33,35 +33,111 @@ impl<T: Sized> RingBuffer<T>
 {
 	/// Creates a new ring buffer and returns a consumer to it and producers for it.
 	///
+	/// Each producer can only have one reservation (acquisition) at a time.
+	///
 	/// When the last consumer or producer is dropped, the ring buffer is freed.
 	#[inline(always)]
 	pub fn new(capacity: usize, number_of_producers: usize) -> (RingBufferConsumer<T>, Vec<RingBufferProducer<T>>)
 	{
-		let ring_buffer_inner_non_null = RingBufferInner::allocate(capacity, number_of_producers);
+		Self::new_with_maximum_reservations(capacity, &vec![1; number_of_producers])
+	}
+
+	/// Creates a new ring buffer and returns a consumer to it and producers for it.
+	///
+	/// There is one producer for each entry in `maximum_reservations_per_producer`; each entry is the maximum number of reservations (acquisitions) that producer can have at a time, and should not be zero.
+	/// Reservations can be produced in any order.
+	///
+	/// The consumer has to check every reservation of every producer, so keep the total number of reservations small.
+	///
+	/// When the last consumer or producer is dropped, the ring buffer is freed.
+	#[inline(always)]
+	pub fn new_with_maximum_reservations(capacity: usize, maximum_reservations_per_producer: &[usize]) -> (RingBufferConsumer<T>, Vec<RingBufferProducer<T>>)
+	{
+		Self::new_with_completion(capacity, maximum_reservations_per_producer, false)
+	}
+
+	/// Creates a new ring buffer, as `new_with_maximum_reservations()`, in which a reservation which has been produced (completed) can be consumed before earlier reservations which have not, so that a slow producer does not hold back the items of others.
+	///
+	/// The items of each producer are still consumed in the order it acquired them.
+	/// If nothing is ready in order, `RingBufferConsumer::consume()` returns the items of one completed reservation; releasing them does not release their space back to producers until all the items before it have also been released, so the space used by a slow producer's reservation and everything after it can not be acquired again until it is produced.
+	///
+	/// A reservation counts towards its producer's maximum number of reservations until the consumer has released all of its items.
+	/// A reservation completed out of order is reported by `RingBufferConsumer::consume_detailed()` as `ConsumeStatus::Ready`.
+	#[inline(always)]
+	pub fn new_with_out_of_order_completion(capacity: usize, maximum_reservations_per_producer: &[usize]) -> (RingBufferConsumer<T>, Vec<RingBufferProducer<T>>)
+	{
+		Self::new_with_completion(capacity, maximum_reservations_per_producer, true)
+	}
+
+	#[inline(always)]
+	fn new_with_completion(capacity: usize, maximum_reservations_per_producer: &[usize], out_of_order: bool) -> (RingBufferConsumer<T>, Vec<RingBufferProducer<T>>)
+	{
+		assert!(maximum_reservations_per_producer.iter().all(|&maximum_reservations| maximum_reservations != 0), "maximum_reservations_per_producer can not contain zero");
+
+		let ring_buffer = Self::from_inner(RingBufferInner::allocate(capacity, maximum_reservations_per_producer, out_of_order));
 
-		let ring_buffer = Self
+		let consumer = ring_buffer.claim_consumer().unwrap();
+		let producers = (0 .. maximum_reservations_per_producer.len()).map(|producer_index| ring_buffer.claim_producer(producer_index).unwrap()).collect();
+		(consumer, producers)
+	}
+
+	/// Takes ownership of this process's view of the memory of a ring buffer.
+	#[inline(always)]
+	pub(crate) fn from_inner(ring_buffer_inner_non_null: NonNull<RingBufferInner<T>>) -> Self
+	{
+		Self
 		{
 			ring_buffer_inner_non_null,
 			inner_drop_handle: Arc::new(RingBufferInnerDropHandler(ring_buffer_inner_non_null)),
 			marker: PhantomData,
-		};
+		}
+	}
+
+	/// Returns `None` if there is already a consumer.
+	#[inline(always)]
+	pub(crate) fn claim_consumer(&self) -> Option<RingBufferConsumer<T>>
+	{
+		if self.reference().shared().claim_consumer()
+		{
+			Some(RingBufferConsumer(self.clone()))
+		}
+		else
+		{
+			None
+		}
+	}
+
+	/// Returns `None` if there is already a producer for `producer_index`, or if it has been quarantined or its `RingBufferProducerSlot` is impossible.
+	#[inline(always)]
+	pub(crate) fn claim_producer(&self, producer_index: usize) -> Option<RingBufferProducer<T>>
+	{
+		let reference = self.reference();
+		let ring_buffer_producer_slot_non_null = reference.ring_buffer_producer_slot_non_null(producer_index);
+		let ring_buffer_producer_slot = unsafe { ring_buffer_producer_slot_non_null.as_ref() };
+		if !ring_buffer_producer_slot.claim()
+		{
+			return None
+		}
 
-		let mut ring_buffer_producer_inner_non_null = ring_buffer.reference().first_ring_buffer_producer_inner_non_null();
-		let mut producers = Vec::with_capacity(number_of_producers);
-		for _ in 0 .. number_of_producers
+		// The slot might have been changed by another process.
+		let first_producer_reservation_index = ring_buffer_producer_slot.first_producer_reservation_index;
+		let maximum_reservations = ring_buffer_producer_slot.maximum_reservations;
+		if maximum_reservations == 0 || first_producer_reservation_index.checked_add(maximum_reservations).is_none_or(|end| end > reference.number_of_producer_reservations)
 		{
-			producers.push
-			(
-				RingBufferProducer
-				{
-					ring_buffer: ring_buffer.clone(),
-					ring_buffer_producer_inner_non_null,
-				}
-			);
-			ring_buffer_producer_inner_non_null = RingBufferInner::<T>::next_ring_buffer_producer_inner_non_null(ring_buffer_producer_inner_non_null);
+			ring_buffer_producer_slot.unclaim();
+			return None
 		}
 
-		(RingBufferConsumer(ring_buffer), producers)
+		Some
+		(
+			RingBufferProducer
+			{
+				ring_buffer: self.clone(),
+				ring_buffer_producer_slot_non_null,
+				ring_buffer_producer_inner_non_null: reference.ring_buffer_producer_inner_non_null(first_producer_reservation_index),
+				maximum_reservations,
+			}
+		)
 	}
 	
 	#[inline(always)]
@@ -8,26 +8,231 @@
 #[derive(Debug, Ord, PartialOrd, Eq, PartialEq)]
 pub struct RingBufferConsumer<T: Sized>(RingBuffer<T>);
 
+// There is only ever one consumer; the state only it uses (eg the stall watchdog) is not `Sync`, but this is not `Sync` either, so that state is only ever used on one thread at a time, whichever that is.
+// Items are moved out on the consumer's thread, so must be `Send`.
+unsafe impl<T: Send> Send for RingBufferConsumer<T>
+{
+}
+
+impl<T: Sized> Drop for RingBufferConsumer<T>
+{
+	#[inline(always)]
+	fn drop(&mut self)
+	{
+		self.reference().shared().unclaim_consumer()
+	}
+}
+
 impl<T: Sized> RingBufferConsumer<T>
 {
 	/// Get a contiguous range which is ready to be consumed.
 	///
-	/// Only call this on one thread at a time.
+	/// Borrows this consumer mutably so that no other consumption (which would be given the same items) can happen whilst the guard exists.
 	///
 	/// Not particularly cheap (as it walks all producers) so try to take as much as possible.
+	///
+	/// Returns an empty range whilst an `OwnedBatch` is outstanding.
+	#[inline(always)]
+	pub fn consume<'a>(&'a mut self) -> RingBufferConsumerGuard<'a, T>
+	{
+		self.consume_guard()
+	}
+
+	/// As `consume()`; the caller must ensure that no other guard, batch or consumption exists at the same time (eg `RingBufferConsumerReader` drops its guard before it calls this again).
 	#[inline(always)]
-	pub fn consume<'a>(&'a self) -> RingBufferConsumerGuard<'a, T>
+	pub(crate) fn consume_guard<'a>(&'a self) -> RingBufferConsumerGuard<'a, T>
 	{
-		let (count, offset) = self.reference().consume();
+		let (count, offset) = if self.reference().owned_batch_outstanding()
+		{
+			(0, 0)
+		}
+		else
+		{
+			self.reference().consume()
+		};
 		
 		RingBufferConsumerGuard
 		{
-			buffer_slice: self.reference().buffer_consumer_slice_reference(count, offset),
-			release_count: count,
+			buffer_slice: self.reference().buffer_consumer_slice_mutable(count, offset),
+			release_count: 0,
 			consumer: self,
 		}
 	}
 	
+	/// As `consume()`, but additionally returns why the range is what it is; in particular, why it is empty.
+	#[inline(always)]
+	pub fn consume_detailed<'a>(&'a mut self) -> (RingBufferConsumerGuard<'a, T>, ConsumeStatus)
+	{
+		let (count, offset, consume_status) = if self.reference().owned_batch_outstanding()
+		{
+			(0, 0, ConsumeStatus::OwnedBatchOutstanding)
+		}
+		else
+		{
+			self.reference().consume_detailed()
+		};
+
+		let ring_buffer_consumer_guard = RingBufferConsumerGuard
+		{
+			buffer_slice: self.reference().buffer_consumer_slice_mutable(count, offset),
+			release_count: 0,
+			consumer: self,
+		};
+		(ring_buffer_consumer_guard, consume_status)
+	}
+
+	/// Get a contiguous range which is ready to be consumed as an `OwnedBatch`, which does not borrow this consumer.
+	///
+	/// Returns `None` if there is nothing ready to be consumed or if a previous `OwnedBatch` is still outstanding; no further consumption is possible until that batch has been dropped.
+	///
+	/// Borrows this consumer mutably so that it can not be called whilst a guard or another consumption holds the same items.
+	#[inline(always)]
+	pub fn consume_owned(&mut self) -> Option<OwnedBatch<T>>
+	{
+		let reference = self.reference();
+		if reference.owned_batch_outstanding()
+		{
+			return None
+		}
+
+		let (count, offset) = reference.consume();
+		if count == 0
+		{
+			return None
+		}
+
+		reference.set_owned_batch_outstanding(true);
+		Some
+		(
+			OwnedBatch
+			{
+				ring_buffer: self.0.clone(),
+				first: unsafe { NonNull::new_unchecked(reference.buffer_pointer(offset)) },
+				count,
+				read_count: 0,
+			}
+		)
+	}
+
+	/// Passes the range which is ready to be consumed, including any range at the start of the ring buffer after a wrap-around, to `handler`.
+	///
+	/// `handler` returns the count of items it handled, counting from the start of the batch; these items are dropped and released.
+	/// All other items are returned to the queue and can be read again.
+	///
+	/// `handler` is not called if there is nothing ready to be consumed or whilst an `OwnedBatch` is outstanding.
+	///
+	/// Returns the count of items handled (clamped to the batch's length).
+	///
+	/// Borrows this consumer mutably so that `handler` can not consume again (and so be given the same items) whilst it is handling them:-
+	///
+	/// ```compile_fail,E0499
+	/// # extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+	/// # use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+	/// let (mut ring_buffer_consumer, _ring_buffer_producers) = RingBuffer::<u8>::new(16, 1);
+	/// ring_buffer_consumer.consume_with(|_batch| ring_buffer_consumer.consume_with(|nested_batch| nested_batch.len()));
+	/// ```
+	#[inline(always)]
+	pub fn consume_with<F: FnOnce(&mut ConsumedBatch<T>) -> usize>(&mut self, handler: F) -> usize
+	{
+		if self.reference().owned_batch_outstanding()
+		{
+			return 0
+		}
+
+		let (count, offset, wrapped_count) = self.reference().consume_segments();
+		if count == 0
+		{
+			return 0
+		}
+
+		let mut batch = ConsumedBatch
+		{
+			first: self.reference().buffer_consumer_slice_mutable(count, offset),
+			second: self.reference().buffer_consumer_slice_mutable(wrapped_count, 0),
+		};
+
+		let handled = min(handler(&mut batch), batch.len());
+		let first_handled = batch.drop_handled(handled);
+
+		self.release(first_handled);
+		if first_handled == count && handled != first_handled
+		{
+			self.reference().release_after_wrap_around(handled - first_handled)
+		}
+
+		handled
+	}
+
+	/// Repeatedly calls `self.consume_with()` until either there is nothing ready to be consumed or `handler` does not handle all the items it is passed.
+	///
+	/// Returns the total count of items handled.
+	///
+	/// Borrows this consumer mutably for the same reason as `consume_with()`.
+	#[inline(always)]
+	pub fn drain_with<F: FnMut(&mut ConsumedBatch<T>) -> usize>(&mut self, mut handler: F) -> usize
+	{
+		let mut total_handled = 0;
+		loop
+		{
+			let mut batch_length = 0;
+			let handled = self.consume_with(|batch|
+			{
+				batch_length = batch.len();
+				handler(batch)
+			});
+
+			total_handled += handled;
+
+			if handled == 0 || handled != batch_length
+			{
+				return total_handled
+			}
+		}
+	}
+
+	/// Finds the reservation, if any, which has been acquired but not yet produced and which is holding back the items after it from being consumed (the one nearest the `written` offset).
+	///
+	/// A producer which is slow to produce, or which has stopped, holds back every item acquired after it by every other producer; `consume()` then returns less than is ready, or nothing.
+	///
+	/// Each call observes the reservation, so that `StalledProducer::stalled_for` measures from the first call which found it.
+	///
+	/// Not particularly cheap (as it walks all producers). Only call this on one thread at a time.
+	#[inline(always)]
+	pub fn stalled_producer(&self) -> Option<StalledProducer>
+	{
+		self.reference().stalled_producer()
+	}
+
+	/// Calls `callback` once for each reservation found to have been holding back items for at least `threshold`; replaces any previous watchdog.
+	///
+	/// The watchdog is checked every time this consumer consumes (by any method), which then costs about twice as much, and every time `stalled_producer()` is called; a stall is only detected whilst the consumer is being used.
+	///
+	/// Only call this on one thread at a time.
+	#[inline(always)]
+	pub fn set_stall_watchdog<F: FnMut(&StalledProducer) + Send + 'static>(&self, threshold: Duration, callback: F)
+	{
+		self.reference().set_stall_watchdog(threshold, Box::new(callback))
+	}
+
+	/// Removes the watchdog set by `set_stall_watchdog()`, if any.
+	///
+	/// Only call this on one thread at a time.
+	#[inline(always)]
+	pub fn clear_stall_watchdog(&self)
+	{
+		self.reference().clear_stall_watchdog()
+	}
+
+	/// Asserts the invariants of the ring buffer's offsets, returning the count of items acquired but not yet released.
+	///
+	/// Only meaningful whilst no producer is part way through an operation on another thread; used by the fuzz targets.
+	#[cfg(fuzzing)]
+	#[doc(hidden)]
+	pub fn assert_invariants(&self) -> usize
+	{
+		self.reference().assert_invariants()
+	}
+
 	#[inline(always)]
 	pub(crate) fn release(&self, count: usize)
 	{
@@ -40,3 +245,56 @@ impl<T: Sized> RingBufferConsumer<T>
 		self.0.reference()
 	}
 }
+
+impl RingBufferConsumer<u8>
+{
+	/// Reads from the ring buffer with `io::Read` and `io::BufRead`; see `RingBufferConsumerReader`.
+	///
+	/// Borrows this consumer mutably, as the reader holds a `RingBufferConsumerGuard`.
+	#[inline(always)]
+	pub fn reader<'a>(&'a mut self) -> RingBufferConsumerReader<'a>
+	{
+		RingBufferConsumerReader
+		{
+			consumer: self,
+			slice_guard: None,
+		}
+	}
+
+	/// Writes the bytes which are ready to be consumed, including any at the start of the ring buffer after a wrap-around, to `writer` with one `Write::write_vectored()` and releases exactly the bytes written.
+	///
+	/// Returns the count of bytes written, which is `0` if there is nothing ready to be consumed or whilst an `OwnedBatch` is outstanding.
+	/// If `writer` fails, nothing is released.
+	///
+	/// Borrows this consumer mutably for the same reason as `consume_with()`.
+	#[inline(always)]
+	pub fn write_to<W: Write>(&mut self, writer: &mut W) -> io::Result<usize>
+	{
+		if self.reference().owned_batch_outstanding()
+		{
+			return Ok(0)
+		}
+
+		let (count, offset, wrapped_count) = self.reference().consume_segments();
+		if count == 0
+		{
+			return Ok(0)
+		}
+
+		let written =
+		{
+			let first = self.reference().buffer_consumer_slice_mutable(count, offset);
+			let second = self.reference().buffer_consumer_slice_mutable(wrapped_count, 0);
+			min(writer.write_vectored(&[IoSlice::new(first), IoSlice::new(second)])?, count + wrapped_count)
+		};
+
+		let first_written = min(written, count);
+		self.release(first_written);
+		if first_written == count && written != first_written
+		{
+			self.reference().release_after_wrap_around(written - first_written)
+		}
+
+		Ok(written)
+	}
+}
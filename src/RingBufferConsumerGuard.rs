@@ -9,7 +9,7 @@
 #[derive(Debug)]
 pub struct RingBufferConsumerGuard<'a, T: 'a + Sized>
 {
-	buffer_slice: &'a [T],
+	buffer_slice: &'a mut [T],
 
 	release_count: usize,
 
@@ -37,7 +37,7 @@ impl<'a, T: 'a + Sized> Iterator for RingBufferConsumerGuard<'a, T>
 			return None
 		}
 
-		let next = unsafe { transmute_copy(self.buffer_slice.get_unchecked(self.release_count)) };
+		let next = unsafe { self.buffer_slice.as_ptr().add(self.release_count).read() };
 
 		self.release_count += 1;
 
@@ -49,7 +49,7 @@ impl<'a, T: 'a + Sized> RingBufferConsumerGuard<'a, T>
 {
 	/// Is the inner slice empty?
 	///
-	/// This property is not constant; it can change after calls to `Iterator::next()`, `self.move_into_slice()` and `self.move_into_slice_unsafe()`.
+	/// This property is not constant; it can change after calls to `Iterator::next()`, `self.move_into_slice()`, `self.move_into_slice_unsafe()`, `self.release()` and `self.discard()`.
 	#[inline(always)]
 	pub fn is_empty(&self) -> bool
 	{
@@ -58,19 +58,89 @@ impl<'a, T: 'a + Sized> RingBufferConsumerGuard<'a, T>
 
 	/// Data in this slice.
 	///
-	/// This property is not constant; it can change after calls to `Iterator::next()`, `self.move_into_slice()` and `self.move_into_slice_unsafe()`.
+	/// This property is not constant; it can change after calls to `Iterator::next()`, `self.move_into_slice()`, `self.move_into_slice_unsafe()`, `self.release()` and `self.discard()`.
 	#[inline(always)]
 	pub fn len(&self) -> usize
 	{
 		self.buffer_slice.len() - self.release_count
 	}
 
+	/// Borrows the next unread item without consuming it.
+	///
+	/// Returns `None` if there are no unread items.
+	#[inline(always)]
+	pub fn peek(&self) -> Option<&T>
+	{
+		self.current_buffer_slice().first()
+	}
+
+	/// Borrows the unread items without consuming them.
+	#[inline(always)]
+	pub fn as_slice(&self) -> &[T]
+	{
+		self.current_buffer_slice()
+	}
+
+	/// Mutably borrows the unread items without consuming them, so that they can be processed in place.
+	///
+	/// Use `self.release()` or `self.discard()` afterwards to consume the items processed.
+	#[inline(always)]
+	pub fn as_mut_slice(&mut self) -> &mut [T]
+	{
+		self.current_buffer_slice_mutable()
+	}
+
+	/// Consumes (and drops) the next `count` unread items and immediately releases their space (and that of any items already moved out) back to the ring buffer for producers to use.
+	///
+	/// Any remaining unread items are still available from this guard, and, if not consumed, will be returned to the queue when this guard is dropped.
+	///
+	/// `count` is clamped to `self.len()`.
+	#[inline(always)]
+	pub fn release(&mut self, count: usize)
+	{
+		self.discard(count);
+
+		let release_count = self.release_count;
+		if release_count == 0
+		{
+			return
+		}
+
+		let buffer_slice = take(&mut self.buffer_slice);
+		self.buffer_slice = &mut buffer_slice[release_count ..];
+		self.release_count = 0;
+		self.consumer.release(release_count)
+	}
+
+	/// Consumes (and drops) the next `count` unread items.
+	///
+	/// This is not called `skip()` as that would be shadowed by `Iterator::skip()`.
+	///
+	/// Their space is released back to the ring buffer when this guard is dropped (or on the next call to `self.release()`).
+	///
+	/// `count` is clamped to `self.len()`.
+	#[inline(always)]
+	pub fn discard(&mut self, count: usize)
+	{
+		let count = min(count, self.len());
+
+		unsafe { drop_in_place(&mut self.current_buffer_slice_mutable()[.. count]) };
+
+		self.release_count += count;
+	}
+
 	#[inline(always)]
 	fn current_buffer_slice(&self) -> &[T]
 	{
 		&self.buffer_slice[self.release_count ..]
 	}
 
+	#[inline(always)]
+	fn current_buffer_slice_mutable(&mut self) -> &mut [T]
+	{
+		&mut self.buffer_slice[self.release_count ..]
+	}
+
 	/// This moves the data in the buffer to a destination slice using an iteration.
 	///
 	/// If the destination slice is smaller than this one, then no harm can happen; the excess elements are dropped.
@@ -78,12 +148,13 @@ impl<'a, T: 'a + Sized> RingBufferConsumerGuard<'a, T>
 	pub fn move_into_slice(&mut self, slice: &mut [T])
 	{
 		let count = slice.len();
+		let first = self.current_buffer_slice().as_ptr();
 		for index in 0 .. count
 		{
 			unsafe
 			{
 				let slot = slice.get_unchecked_mut(index);
-				*slot = transmute_copy(self.current_buffer_slice().get_unchecked(index));
+				*slot = first.add(index).read();
 			}
 		}
 
@@ -93,6 +164,10 @@ impl<'a, T: 'a + Sized> RingBufferConsumerGuard<'a, T>
 	/// This moves the data in the buffer to a destination slice using a `memcpy`; as a consequence, ***any existing data in the slice is NOT dropped***.
 	///
 	/// If the destination slice is smaller than this one, then no harm can happen; the underlying slice's length is adjusted.
+	///
+	/// # Safety
+	///
+	/// Any items in `slice` are overwritten without being dropped, so they must not need dropping (or must be dropped elsewhere).
 	#[inline(always)]
 	pub unsafe fn move_into_slice_unsafe(&mut self, slice: &mut [T])
 	{
@@ -113,18 +188,17 @@ impl<'a, T: 'a + Sized> RingBufferConsumerGuard<'a, T>
 	{
 		let count = min(maximum_to_move_out, self.len());
 
-		let mut boxed_slice =
-		{
-			let mut vec = Vec::with_capacity(count);
-			unsafe { vec.set_len(count) };
-			vec.into_boxed_slice()
-		};
+		let mut vec = Vec::with_capacity(count);
 
 		let first = self.current_buffer_slice().as_ptr();
-		unsafe { first.copy_to_nonoverlapping(boxed_slice.as_mut_ptr(), count) };
+		unsafe
+		{
+			first.copy_to_nonoverlapping(vec.as_mut_ptr(), count);
+			vec.set_len(count)
+		};
 
 		self.release_count += count;
 
-		boxed_slice
+		vec.into_boxed_slice()
 	}
 }
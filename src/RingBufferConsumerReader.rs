@@ -0,0 +1,76 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Reads bytes from a ring buffer with `io::Read` and `io::BufRead`; created by `RingBufferConsumer::reader()`.
+///
+/// `fill_buf()` returns the range returned by `RingBufferConsumer::consume()`, without copying, and `consume(amt)` releases exactly `amt` bytes of it back to the ring buffer.
+///
+/// If nothing is ready, reads fail with an `io::Error` of kind `io::ErrorKind::WouldBlock`, unless every producer, and every reservation (including any `OwnedReservation`), has been dropped, in which case they return end-of-file.
+#[derive(Debug)]
+pub struct RingBufferConsumerReader<'a>
+{
+	consumer: &'a RingBufferConsumer<u8>,
+
+	/// The range last returned by `fill_buf()`, less what has been released.
+	slice_guard: Option<RingBufferConsumerGuard<'a, u8>>,
+}
+
+impl<'a> Read for RingBufferConsumerReader<'a>
+{
+	#[inline(always)]
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>
+	{
+		let count =
+		{
+			let available = self.fill_buf()?;
+			let count = min(available.len(), buf.len());
+			buf[.. count].copy_from_slice(&available[.. count]);
+			count
+		};
+		self.consume(count);
+		Ok(count)
+	}
+}
+
+impl<'a> BufRead for RingBufferConsumerReader<'a>
+{
+	#[inline(always)]
+	fn fill_buf(&mut self) -> io::Result<&[u8]>
+	{
+		if self.slice_guard.as_ref().is_none_or(|slice_guard| slice_guard.is_empty())
+		{
+			self.slice_guard = None;
+
+			// Checked before consuming, as a producer produces before it is dropped.
+			// Reservations are checked after producers, as a producer acquires a reservation (such as an `OwnedReservation`, which can outlive it) before it is dropped.
+			let reference = self.consumer.reference();
+			let end_of_file = !reference.has_claimed_producers() && !reference.has_reservations_in_progress();
+
+			let slice_guard = self.consumer.consume_guard();
+			if slice_guard.is_empty()
+			{
+				return if end_of_file
+				{
+					Ok(&[])
+				}
+				else
+				{
+					Err(io::Error::from(io::ErrorKind::WouldBlock))
+				}
+			}
+			self.slice_guard = Some(slice_guard);
+		}
+
+		Ok(self.slice_guard.as_ref().unwrap().as_slice())
+	}
+
+	#[inline(always)]
+	fn consume(&mut self, amt: usize)
+	{
+		if let Some(ref mut slice_guard) = self.slice_guard
+		{
+			slice_guard.release(amt)
+		}
+	}
+}
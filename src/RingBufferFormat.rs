@@ -0,0 +1,250 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Describes the memory of a ring buffer; the first part of the `RingBufferSharedHeader`.
+///
+/// Only fixed-width fields, without padding, so that this part has the same layout whatever the pointer width of the process reading it, and can always be read to discover what follows it.
+/// The magic number and format version come first so that they remain readable if later versions change the rest.
+///
+/// Memory shared with other processes can be changed by them at any time, so a process attaching to it takes one snapshot (see `Self::snapshot()`), validates it, and computes everything from the snapshot.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+struct RingBufferFormat
+{
+	/// `Self::Magic`; byte-swapped if written by a process of the other endianness.
+	magic: u64,
+
+	/// `Self::Version`.
+	version: u32,
+
+	/// `size_of::<RingBufferOffset>()`, ie the pointer width in bytes.
+	offset_width: u32,
+
+	/// `RingBufferOffset::WrapCounter`.
+	wrap_counter: u64,
+
+	/// `RingBufferOffset::WrapLockBit`.
+	wrap_lock_bit: u64,
+
+	/// `RingBufferOffset::OffsetMask`.
+	offset_mask: u64,
+
+	/// `size_of::<T>()`.
+	item_size: u64,
+
+	/// `align_of::<T>()`.
+	item_alignment: u64,
+
+	capacity: u64,
+
+	number_of_producers: u64,
+
+	/// Each producer has one or more reservations, each of which has a `RingBufferProducerInner`.
+	number_of_producer_reservations: u64,
+
+	/// See `Self::compute_layout_checksum()`.
+	layout_checksum: u64,
+}
+
+impl RingBufferFormat
+{
+	/// `"RINGBUF\0"` as a big-endian integer.
+	const Magic: u64 = 0x52494E4742554600;
+
+	/// Incremented whenever the layout of the memory changes.
+	const Version: u32 = 6;
+
+	#[inline(always)]
+	fn new<T>(capacity: usize, number_of_producers: usize, number_of_producer_reservations: usize) -> Self
+	{
+		let mut format = Self
+		{
+			magic: Self::Magic,
+			version: Self::Version,
+			offset_width: size_of::<RingBufferOffset>() as u32,
+			wrap_counter: RingBufferOffset::WrapCounter as u64,
+			wrap_lock_bit: RingBufferOffset::WrapLockBit as u64,
+			offset_mask: RingBufferOffset::OffsetMask as u64,
+			item_size: size_of::<T>() as u64,
+			item_alignment: align_of::<T>() as u64,
+			capacity: capacity as u64,
+			number_of_producers: number_of_producers as u64,
+			number_of_producer_reservations: number_of_producer_reservations as u64,
+			layout_checksum: 0,
+		};
+		format.layout_checksum = format.compute_layout_checksum();
+		format
+	}
+
+	#[inline(always)]
+	fn item_size(&self) -> usize
+	{
+		self.item_size as usize
+	}
+
+	#[inline(always)]
+	fn item_alignment(&self) -> usize
+	{
+		self.item_alignment as usize
+	}
+
+	#[inline(always)]
+	fn capacity(&self) -> usize
+	{
+		self.capacity as usize
+	}
+
+	#[inline(always)]
+	fn number_of_producers(&self) -> usize
+	{
+		self.number_of_producers as usize
+	}
+
+	#[inline(always)]
+	fn number_of_producer_reservations(&self) -> usize
+	{
+		self.number_of_producer_reservations as usize
+	}
+
+	/// The `RingBufferProducerInner`s immediately follow the `RingBufferProducerSlot`s.
+	#[inline(always)]
+	fn producers_offset(&self) -> usize
+	{
+		RingBufferSharedHeader::producers_offset_for(self.number_of_producers())
+	}
+
+	/// The buffer follows the `RingBufferProducerInner`s, rounded up to the alignment of an item.
+	#[inline(always)]
+	fn buffer_offset(&self) -> usize
+	{
+		RingBufferSharedHeader::buffer_offset_for(self.producers_offset(), self.number_of_producer_reservations(), self.item_alignment())
+	}
+
+	/// The size of the memory of the ring buffer.
+	#[inline(always)]
+	fn size(&self) -> usize
+	{
+		let space = round_up_to_alignment(self.item_size() * self.capacity(), self.alignment());
+		self.buffer_offset() + space
+	}
+
+	#[inline(always)]
+	fn alignment(&self) -> usize
+	{
+		max(align_of::<RingBufferSharedHeader>(), max(align_of::<RingBufferProducerSlot>(), max(align_of::<RingBufferProducerInner>(), self.item_alignment())))
+	}
+
+	#[inline(always)]
+	fn layout(&self) -> Layout
+	{
+		Layout::from_size_align(self.size(), self.alignment()).unwrap()
+	}
+
+	/// Reads the format at the start of `memory` exactly once, so that it can not change between being validated and being used.
+	///
+	/// `memory` must point to at least `size_of::<RingBufferSharedHeader>()` bytes.
+	#[cfg(feature = "shared-memory")]
+	#[inline(always)]
+	unsafe fn snapshot(memory: NonNull<u8>) -> Self
+	{
+		read_volatile(memory.cast::<Self>().as_ptr())
+	}
+
+	/// Checks that the memory, of `length` bytes, is a ring buffer of `T` in the format this process uses, so that every offset computed from it is within the memory.
+	///
+	/// Only the fields before `capacity` are read before the offset width is known to match, and the derived layout is only computed once the counts are known not to overflow it.
+	#[cfg(feature = "shared-memory")]
+	#[inline(always)]
+	fn validate<T>(&self, length: usize) -> Result<(), RingBufferFormatError>
+	{
+		use self::RingBufferFormatError::*;
+
+		if self.magic != Self::Magic
+		{
+			return Err(if self.magic == Self::Magic.swap_bytes() { DifferentEndianness } else { NotARingBuffer { magic: self.magic } })
+		}
+
+		if self.version != Self::Version
+		{
+			return Err(UnsupportedVersion { version: self.version, supported_version: Self::Version })
+		}
+
+		if self.offset_width != size_of::<RingBufferOffset>() as u32
+		{
+			return Err(DifferentOffsetWidth { offset_width: self.offset_width, expected_offset_width: size_of::<RingBufferOffset>() as u32 })
+		}
+
+		if self.wrap_counter != RingBufferOffset::WrapCounter as u64 || self.wrap_lock_bit != RingBufferOffset::WrapLockBit as u64 || self.offset_mask != RingBufferOffset::OffsetMask as u64
+		{
+			return Err(DifferentOffsetLayout)
+		}
+
+		if self.item_size != size_of::<T>() as u64 || self.item_alignment != align_of::<T>() as u64
+		{
+			return Err(DifferentItemType { item_size: self.item_size, item_alignment: self.item_alignment, expected_item_size: size_of::<T>(), expected_item_alignment: align_of::<T>() })
+		}
+
+		let maximum_capacity = RingBufferOffset::OffsetMask - 1;
+		if self.capacity == 0 || self.capacity > maximum_capacity as u64 || self.capacity.checked_mul(self.item_size).is_none_or(|space| space > length as u64)
+		{
+			return Err(ImpossibleCapacity { capacity: self.capacity, maximum_capacity })
+		}
+
+		let maximum_count = (length / size_of::<RingBufferOffset>()) as u64;
+		if self.number_of_producers == 0 || self.number_of_producers > maximum_count || self.number_of_producer_reservations < self.number_of_producers || self.number_of_producer_reservations > maximum_count
+		{
+			return Err(ImpossibleNumberOfProducers { number_of_producers: self.number_of_producers, number_of_producer_reservations: self.number_of_producer_reservations })
+		}
+
+		let expected_layout_checksum = self.compute_layout_checksum();
+		if self.layout_checksum != expected_layout_checksum
+		{
+			return Err(LayoutChecksumMismatch { layout_checksum: self.layout_checksum, expected_layout_checksum })
+		}
+
+		Ok(())
+	}
+
+	/// A FNV-1a hash of every field before it and of the sizes and offsets of the parts of the memory they imply.
+	///
+	/// Detects a process whose build lays out the memory differently (eg a different layout of `RingBufferProducerInner`) although the format version is the same, as well as corruption of the fields.
+	#[inline(always)]
+	fn compute_layout_checksum(&self) -> u64
+	{
+		const OffsetBasis: u64 = 0xCBF29CE484222325;
+		const Prime: u64 = 0x00000100000001B3;
+
+		let producers_offset = self.producers_offset();
+		let buffer_offset = self.buffer_offset();
+
+		let words =
+		[
+			self.magic,
+			((self.version as u64) << 32) | (self.offset_width as u64),
+			self.wrap_counter,
+			self.wrap_lock_bit,
+			self.offset_mask,
+			self.item_size,
+			self.item_alignment,
+			self.capacity,
+			self.number_of_producers,
+			self.number_of_producer_reservations,
+			size_of::<RingBufferSharedHeader>() as u64,
+			size_of::<RingBufferProducerSlot>() as u64,
+			size_of::<RingBufferProducerInner>() as u64,
+			producers_offset as u64,
+			buffer_offset as u64,
+		];
+
+		let mut hash = OffsetBasis;
+		for word in words.iter()
+		{
+			for byte in word.to_le_bytes().iter()
+			{
+				hash = (hash ^ (*byte as u64)).wrapping_mul(Prime);
+			}
+		}
+		hash
+	}
+}
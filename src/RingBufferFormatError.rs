@@ -0,0 +1,154 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Why shared memory could not be attached to as a ring buffer.
+///
+/// Returned by `SharedMemoryRingBuffer::attach_to_file_descriptor()` and `SharedMemoryRingBuffer::attach_to_path()` inside an `io::Error` of kind `io::ErrorKind::InvalidData`; use `io::Error::get_ref()` and `downcast_ref()` to get it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RingBufferFormatError
+{
+	/// The shared memory is smaller than the header of a ring buffer.
+	TooSmall
+	{
+		/// The size of the shared memory.
+		length: usize,
+
+		/// The size of the header of a ring buffer.
+		minimum_length: usize,
+	},
+
+	/// The shared memory does not start with the magic number of a ring buffer.
+	NotARingBuffer
+	{
+		/// What the shared memory starts with.
+		magic: u64,
+	},
+
+	/// The ring buffer was created by a process of the other endianness.
+	DifferentEndianness,
+
+	/// The ring buffer was created in a format version this process does not support.
+	UnsupportedVersion
+	{
+		/// The format version of the ring buffer.
+		version: u32,
+
+		/// The format version this process supports.
+		supported_version: u32,
+	},
+
+	/// The ring buffer was created by a process with a different pointer width, which uses offsets of a different width.
+	DifferentOffsetWidth
+	{
+		/// The width of the offsets of the ring buffer in bytes.
+		offset_width: u32,
+
+		/// The width of the offsets of this process in bytes.
+		expected_offset_width: u32,
+	},
+
+	/// The ring buffer packs its offsets, wrap-around counter and lock bit differently to this process.
+	DifferentOffsetLayout,
+
+	/// The ring buffer contains items of a different size or alignment to the one attached with.
+	DifferentItemType
+	{
+		/// The size of the items of the ring buffer.
+		item_size: u64,
+
+		/// The alignment of the items of the ring buffer.
+		item_alignment: u64,
+
+		/// The size of the type attached with.
+		expected_item_size: usize,
+
+		/// The alignment of the type attached with.
+		expected_item_alignment: usize,
+	},
+
+	/// The capacity of the ring buffer is zero, too large or does not fit in the shared memory.
+	ImpossibleCapacity
+	{
+		/// The capacity of the ring buffer.
+		capacity: u64,
+
+		/// The largest possible capacity.
+		maximum_capacity: usize,
+	},
+
+	/// The ring buffer has no producers, fewer producer reservations than producers or more of either than fit in the shared memory.
+	ImpossibleNumberOfProducers
+	{
+		/// The number of producers of the ring buffer.
+		number_of_producers: u64,
+
+		/// The number of producer reservations of the ring buffer.
+		number_of_producer_reservations: u64,
+	},
+
+	/// The description of the ring buffer is corrupt, or it was created by a build which lays out the memory differently.
+	LayoutChecksumMismatch
+	{
+		/// The layout checksum in the ring buffer.
+		layout_checksum: u64,
+
+		/// The layout checksum this process computes from the description of the ring buffer.
+		expected_layout_checksum: u64,
+	},
+
+	/// The reservations of a producer are empty or overlap or are not within those of the ring buffer.
+	ImpossibleProducerSlot
+	{
+		/// The index of the first producer whose reservations are impossible.
+		producer_index: usize,
+	},
+
+	/// The shared memory is not the size of the ring buffer it describes.
+	DifferentLength
+	{
+		/// The size of the shared memory.
+		length: usize,
+
+		/// The size of the ring buffer.
+		expected_length: usize,
+	},
+}
+
+impl Display for RingBufferFormatError
+{
+	#[inline(always)]
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result
+	{
+		use self::RingBufferFormatError::*;
+
+		match *self
+		{
+			TooSmall { length, minimum_length } => write!(f, "shared memory of {} bytes is smaller than the {} bytes of the header of a ring buffer", length, minimum_length),
+			NotARingBuffer { magic } => write!(f, "shared memory starts with 0x{:016X}, not the magic number of a ring buffer", magic),
+			DifferentEndianness => write!(f, "ring buffer was created by a process of the other endianness"),
+			UnsupportedVersion { version, supported_version } => write!(f, "ring buffer is in format version {} but only version {} is supported", version, supported_version),
+			DifferentOffsetWidth { offset_width, expected_offset_width } => write!(f, "ring buffer has {}-byte offsets but this process uses {}-byte offsets", offset_width, expected_offset_width),
+			DifferentOffsetLayout => write!(f, "ring buffer packs its offsets, wrap-around counter and lock bit differently to this process"),
+			DifferentItemType { item_size, item_alignment, expected_item_size, expected_item_alignment } => write!(f, "ring buffer has items of {} bytes aligned to {} bytes but attached with items of {} bytes aligned to {} bytes", item_size, item_alignment, expected_item_size, expected_item_alignment),
+			ImpossibleCapacity { capacity, maximum_capacity } => write!(f, "ring buffer has a capacity of {} which is zero, exceeds {} or does not fit in the shared memory", capacity, maximum_capacity),
+			ImpossibleNumberOfProducers { number_of_producers, number_of_producer_reservations } => write!(f, "ring buffer has an impossible {} producers with {} producer reservations", number_of_producers, number_of_producer_reservations),
+			LayoutChecksumMismatch { layout_checksum, expected_layout_checksum } => write!(f, "ring buffer has layout checksum 0x{:016X} but 0x{:016X} was expected; it is corrupt or was created by a build with a different layout", layout_checksum, expected_layout_checksum),
+			ImpossibleProducerSlot { producer_index } => write!(f, "producer {} of the ring buffer has reservations which are empty, overlap those of another producer or are not within those of the ring buffer", producer_index),
+			DifferentLength { length, expected_length } => write!(f, "shared memory of {} bytes is not the {} bytes of the ring buffer it contains", length, expected_length),
+		}
+	}
+}
+
+impl Error for RingBufferFormatError
+{
+}
+
+impl From<RingBufferFormatError> for io::Error
+{
+	#[inline(always)]
+	fn from(error: RingBufferFormatError) -> Self
+	{
+		io::Error::new(io::ErrorKind::InvalidData, error)
+	}
+}
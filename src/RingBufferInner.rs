@@ -2,18 +2,18 @@
 // Copyright © 2017 - 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
 
 
+/// A process's view of the memory of a ring buffer, and how to free that memory.
+///
+/// Boxed and shared by all the `RingBuffer`s of a process; freed when the last is dropped.
 #[derive(Debug)]
-#[repr(C)]
 struct RingBufferInner<T: Sized>
 {
-	/// Fixed size field.
 	header: RingBufferInnerHeader<T>,
 
-	/// Variable sized field.
-	ring_buffer_producer_inners: PhantomData<RingBufferProducerInner>,
+	memory: RingBufferMemory,
 
-	/// Variable sized field.
-	buffer: PhantomData<T>,
+	/// Only used by the consumer.
+	stall_watchdog: StallWatchdog,
 }
 
 impl<T: Sized> Drop for RingBufferInner<T>
@@ -21,9 +21,15 @@ impl<T: Sized> Drop for RingBufferInner<T>
 	#[inline(always)]
 	fn drop(&mut self)
 	{
-		self.drop_remaining_data(self);
+		// Items in memory shared with other processes are `PlainOldData`, and this process might not be the consumer.
+		if self.memory.is_process_local()
+		{
+			self.drop_remaining_data();
+		}
+
+		#[cfg(feature = "paranoid")] unsafe { self.slot_states.free() };
 
-		unsafe { Global.dealloc(NonNull::new_unchecked(self as *mut _ as *mut _), self.layout()) }
+		unsafe { self.memory.free(self.shared.cast()) }
 	}
 }
 
@@ -38,75 +44,311 @@ impl<T: Sized> Deref for RingBufferInner<T>
 	}
 }
 
-impl<T: Sized> DerefMut for RingBufferInner<T>
-{
-	#[inline(always)]
-	fn deref_mut(&mut self) -> &mut Self::Target
-	{
-		&mut self.header
-	}
-}
-
 impl<T: Sized> RingBufferInner<T>
 {
+	/// Allocates and initializes the memory of a ring buffer on the heap.
+	///
+	/// If `out_of_order`, reservations are completed rather than produced; see `RingBuffer::new_with_out_of_order_completion()`.
 	#[inline(always)]
-	pub(crate) fn allocate(capacity: usize, number_of_producers: usize) -> NonNull<Self>
+	pub(crate) fn allocate(capacity: usize, maximum_reservations_per_producer: &[usize], out_of_order: bool) -> NonNull<Self>
 	{
-		let header = RingBufferInnerHeader::new(capacity, number_of_producers);
+		let shared_header = Self::shared_header(capacity, maximum_reservations_per_producer);
 
-		let layout = header.layout();
-		let mut this = unsafe
+		let layout = shared_header.format.layout();
+		let memory = match NonNull::new(unsafe { alloc(layout) })
 		{
-			let raw_this_pointer = Global.alloc(layout).expect("Out of memory");
-			NonNull::new_unchecked(raw_this_pointer.as_ptr() as *mut Self)
+			Some(memory) => memory,
+			None => handle_alloc_error(layout),
 		};
 
+		unsafe
 		{
-			let this_mut: &mut Self = unsafe { this.as_mut() };
-			this_mut.initialize_header(header);
-			let after_last_ring_buffer_producer_inner_non_null = this_mut.initialize_ring_buffer_producer_inners();
-			this_mut.initialize_buffer(after_last_ring_buffer_producer_inner_non_null);
+			let format = shared_header.format;
+			Self::initialize(memory, shared_header, maximum_reservations_per_producer);
+			Self::view(memory, format, RingBufferMemory::Heap(layout), out_of_order)
 		}
-		
-		fence_stores();
-		
-		this
 	}
 
 	#[inline(always)]
-	pub(crate) fn initialize_header(&mut self, header: RingBufferInnerHeader<T>)
+	fn shared_header(capacity: usize, maximum_reservations_per_producer: &[usize]) -> RingBufferSharedHeader
 	{
-		unsafe { write(self.deref_mut(), header) };
+		let number_of_producer_reservations = maximum_reservations_per_producer.iter().sum();
+		RingBufferInnerHeader::<T>::shared_header(capacity, maximum_reservations_per_producer.len(), number_of_producer_reservations)
 	}
 
+	/// Initializes the `RingBufferSharedHeader`, `RingBufferProducerSlot`s and `RingBufferProducerInner`s of the memory of a ring buffer; the buffer is left uninitialized.
+	///
+	/// All pointers into the memory are derived from `memory`, never from references to the `RingBufferSharedHeader`; a reference only permits access to the memory of its referent.
 	#[inline(always)]
-	pub(crate) fn initialize_ring_buffer_producer_inners(&mut self) -> NonNull<RingBufferProducerInner>
+	unsafe fn initialize(memory: NonNull<u8>, shared_header: RingBufferSharedHeader, maximum_reservations_per_producer: &[usize])
 	{
-		let mut ring_buffer_producer_inner_non_null = self.first_ring_buffer_producer_inner_non_null();
-		for _ in 0 .. self.number_of_producers
+		let mut ring_buffer_producer_slot = NonNull::new_unchecked(memory.as_ptr().add(RingBufferSharedHeader::producer_slots_offset()) as *mut RingBufferProducerSlot);
+		let mut first_producer_reservation_index = 0;
+		for &maximum_reservations in maximum_reservations_per_producer
+		{
+			RingBufferProducerSlot::initialize(ring_buffer_producer_slot, first_producer_reservation_index, maximum_reservations);
+			ring_buffer_producer_slot = NonNull::new_unchecked(ring_buffer_producer_slot.as_ptr().add(1));
+			first_producer_reservation_index += maximum_reservations;
+		}
+
+		let mut ring_buffer_producer_inner_non_null = NonNull::new_unchecked(memory.as_ptr().add(shared_header.format.producers_offset()) as *mut RingBufferProducerInner);
+		for _ in 0 .. shared_header.format.number_of_producer_reservations()
 		{
 			RingBufferProducerInner::initialize(ring_buffer_producer_inner_non_null);
 			ring_buffer_producer_inner_non_null = Self::next_ring_buffer_producer_inner_non_null(ring_buffer_producer_inner_non_null);
 		}
-		ring_buffer_producer_inner_non_null
+
+		write(memory.as_ptr() as *mut RingBufferSharedHeader, shared_header);
+
+		fence_stores();
+	}
+
+	/// Creates this process's view of `memory`, initialized with `format` (see `RingBufferInnerHeader::new()`).
+	///
+	/// `out_of_order` must only be `true` if `ring_buffer_memory` is process-local.
+	#[inline(always)]
+	unsafe fn view(memory: NonNull<u8>, format: RingBufferFormat, ring_buffer_memory: RingBufferMemory, out_of_order: bool) -> NonNull<Self>
+	{
+		let this = Box::new
+		(
+			Self
+			{
+				header: RingBufferInnerHeader::new(memory, format, ring_buffer_memory.is_process_local(), out_of_order),
+				memory: ring_buffer_memory,
+				stall_watchdog: StallWatchdog::new(),
+			}
+		);
+		NonNull::new_unchecked(Box::into_raw(this))
 	}
 
+	/// `this` must have been returned by `allocate()` or `view()`, and must not be used again.
 	#[inline(always)]
-	pub(crate) fn initialize_buffer(&mut self, after_last_ring_buffer_producer_inner_non_null: NonNull<RingBufferProducerInner>)
+	pub(crate) unsafe fn free(this: NonNull<Self>)
 	{
-		self.header.initialize_buffer(after_last_ring_buffer_producer_inner_non_null);
+		drop(Box::from_raw(this.as_ptr()))
 	}
 
 	#[inline(always)]
 	pub(crate) fn consume(&self) -> (usize, usize)
 	{
-		self.header.consume(self)
+		let consumed = self.header.consume();
+		self.check_stall_watchdog();
+		consumed
+	}
+
+	#[inline(always)]
+	pub(crate) fn consume_segments(&self) -> (usize, usize, usize)
+	{
+		let (count, offset, wrapped_count) = self.header.consume_segments();
+
+		#[cfg(feature = "paranoid")]
+		{
+			self.header.paranoid_consumed(offset, count);
+			self.header.paranoid_consumed(0, wrapped_count);
+		}
+
+		self.check_stall_watchdog();
+
+		(count, offset, wrapped_count)
+	}
+
+	/// As `consume()`, but additionally returns why it returned what it did.
+	#[inline(always)]
+	pub(crate) fn consume_detailed(&self) -> (usize, usize, ConsumeStatus)
+	{
+		let (count, offset, wrapped_count, holding_back) = self.header.consume_segments_detailed();
+
+		#[cfg(feature = "paranoid")] self.header.paranoid_consumed(offset, count);
+
+		self.check_stall_watchdog();
+
+		let consume_status = if count != 0
+		{
+			if wrapped_count != 0
+			{
+				ConsumeStatus::WrapPending { count, wrapped_count }
+			}
+			else
+			{
+				ConsumeStatus::Ready(count)
+			}
+		}
+		else
+		{
+			match holding_back
+			{
+				None => ConsumeStatus::Empty,
+
+				Some(producer_reservation_index) =>
+				{
+					let (producer_index, reservation_index) = self.producer_index_and_reservation_index(producer_reservation_index);
+					ConsumeStatus::BlockedByProducer { producer_index, reservation_index }
+				}
+			}
+		};
+
+		(count, offset, consume_status)
+	}
+
+	/// Finds the producer reservation which is holding back items from being consumed, if any, and observes it with the stall watchdog (calling its callback if the reservation has been observed for too long).
+	///
+	/// Must only be called by the consumer.
+	#[inline(always)]
+	pub(crate) fn stalled_producer(&self) -> Option<StalledProducer>
+	{
+		match self.header.producer_reservation_holding_back_items()
+		{
+			None =>
+			{
+				self.stall_watchdog.forget();
+				None
+			}
+
+			Some((producer_reservation_index, offset, items_held_back)) =>
+			{
+				let (producer_index, reservation_index) = self.producer_index_and_reservation_index(producer_reservation_index);
+				let stalled_producer = StalledProducer
+				{
+					producer_index,
+					reservation_index,
+					offset,
+					items_held_back,
+					stalled_for: self.stall_watchdog.observe(producer_reservation_index, offset),
+				};
+				self.stall_watchdog.fire_if_exceeded(&stalled_producer);
+				Some(stalled_producer)
+			}
+		}
+	}
+
+	/// Must only be called by the consumer.
+	#[inline(always)]
+	pub(crate) fn set_stall_watchdog(&self, threshold: Duration, callback: StallWatchdogCallback)
+	{
+		self.stall_watchdog.set(threshold, callback)
+	}
+
+	/// Must only be called by the consumer.
+	#[inline(always)]
+	pub(crate) fn clear_stall_watchdog(&self)
+	{
+		self.stall_watchdog.clear()
+	}
+
+	#[inline(always)]
+	fn check_stall_watchdog(&self)
+	{
+		if unlikely!(self.stall_watchdog.is_set())
+		{
+			self.stalled_producer();
+		}
+	}
+
+	/// The producer of `producer_reservation_index`, and which of its reservations it is.
+	#[inline(always)]
+	fn producer_index_and_reservation_index(&self, producer_reservation_index: usize) -> (usize, usize)
+	{
+		// The slots might have been changed by another process since they were validated.
+		#[cfg(feature = "shared-memory")]
+		{
+			if let Some(ref hardening) = self.hardening
+			{
+				let producer_index = hardening.producer_index(producer_reservation_index);
+				return (producer_index, producer_reservation_index - hardening.first_producer_reservation_index(producer_index))
+			}
+		}
+
+		for producer_index in 0 .. self.number_of_producers
+		{
+			let ring_buffer_producer_slot = unsafe { self.ring_buffer_producer_slot_non_null(producer_index).as_ref() };
+			let reservation_index = producer_reservation_index.wrapping_sub(ring_buffer_producer_slot.first_producer_reservation_index);
+			if reservation_index < ring_buffer_producer_slot.maximum_reservations
+			{
+				return (producer_index, reservation_index)
+			}
+		}
+		unreachable!("producer_reservation_index '{}' is not a reservation of any producer", producer_reservation_index)
+	}
+
+	/// Frees the slot of each producer claimed by a process for which `is_dead(process_identifier, process_start_time)` is true, returning their producer indices.
+	///
+	/// The range of each of their reservations in progress is poisoned, so that the consumer releases it without consuming it, and the reservation is then cleared, so that the consumer can advance past it.
+	/// A producer which died part way through acquiring a reservation, before recording its range, has its reservation cleared without being poisoned, so it is consumed (as whatever it contains).
+	///
+	/// Must only be called by the consumer, as only it knows the poisoned ranges.
+	#[cfg(feature = "shared-memory")]
+	fn reap_dead_producers(&self, is_dead: impl Fn(usize, usize) -> bool) -> Vec<usize>
+	{
+		let mut reaped_producer_indices = Vec::new();
+		for producer_index in 0 .. self.number_of_producers
+		{
+			let ring_buffer_producer_slot = unsafe { self.ring_buffer_producer_slot_non_null(producer_index).as_ref() };
+			match ring_buffer_producer_slot.owner_process()
+			{
+				Some((owner_process_identifier, owner_process_start_time)) if is_dead(owner_process_identifier, owner_process_start_time) => (),
+				_ => continue,
+			}
+
+			// The slot might have been changed by another process.
+			let first_producer_reservation_index = min(ring_buffer_producer_slot.first_producer_reservation_index, self.number_of_producer_reservations);
+			let maximum_reservations = min(ring_buffer_producer_slot.maximum_reservations, self.number_of_producer_reservations - first_producer_reservation_index);
+
+			for producer_reservation_index in first_producer_reservation_index .. first_producer_reservation_index + maximum_reservations
+			{
+				let producer = unsafe { self.ring_buffer_producer_inner_non_null(producer_reservation_index).as_ref() };
+				if producer.seen_offset.read() == RingBufferInnerHeader::<T>::MaximumOffset
+				{
+					continue
+				}
+
+				// Ignored if impossible (the memory might have been changed by a hostile process); the range will then be consumed.
+				let reserved_offset = producer.reserved_offset.read();
+				let reserved_count = producer.reserved_count.read();
+				if reserved_offset < self.capacity && reserved_count != 0 && reserved_count <= self.capacity - reserved_offset
+				{
+					self.skipped_ranges.skip(reserved_offset, reserved_count);
+				}
+
+				// The range must be poisoned before the consumer can advance past it.
+				fence_stores();
+				producer.seen_offset.write(RingBufferInnerHeader::<T>::MaximumOffset);
+				producer.reserved_count.write(0);
+			}
+
+			ring_buffer_producer_slot.unclaim();
+			reaped_producer_indices.push(producer_index);
+		}
+		reaped_producer_indices
+	}
+
+	/// Whether any producer is currently claimed; once none are, and none can be claimed again, nothing more can be produced.
+	#[inline(always)]
+	pub(crate) fn has_claimed_producers(&self) -> bool
+	{
+		(0 .. self.number_of_producers).any(|producer_index| unsafe { self.ring_buffer_producer_slot_non_null(producer_index).as_ref() }.is_claimed())
+	}
+
+	/// Whether any reservation is in progress (acquired, but not yet produced, or not yet finished with by the consumer); an `OwnedReservation` can outlive the producer which acquired it.
+	#[inline(always)]
+	pub(crate) fn has_reservations_in_progress(&self) -> bool
+	{
+		(0 .. self.number_of_producer_reservations).any(|producer_reservation_index| unsafe { self.ring_buffer_producer_inner_non_null(producer_reservation_index).as_ref() }.seen_offset.read() != RingBufferInnerHeader::<T>::MaximumOffset)
 	}
 
 	#[inline(always)]
-	fn first_ring_buffer_producer_inner_non_null(&self) -> NonNull<RingBufferProducerInner>
+	fn ring_buffer_producer_slot_non_null(&self, producer_index: usize) -> NonNull<RingBufferProducerSlot>
 	{
-		unsafe { NonNull::new_unchecked(&self.ring_buffer_producer_inners as *const PhantomData<RingBufferProducerInner> as *const RingBufferProducerInner as *mut RingBufferProducerInner) }
+		debug_assert!(producer_index < self.number_of_producers, "producer_index '{}' equals or exceeds number_of_producers '{}'", producer_index, self.number_of_producers);
+
+		unsafe { NonNull::new_unchecked(self.first_ring_buffer_producer_slot.as_ptr().add(producer_index)) }
+	}
+
+	#[inline(always)]
+	fn ring_buffer_producer_inner_non_null(&self, producer_reservation_index: usize) -> NonNull<RingBufferProducerInner>
+	{
+		debug_assert!(producer_reservation_index < self.number_of_producer_reservations, "producer_reservation_index '{}' equals or exceeds number_of_producer_reservations '{}'", producer_reservation_index, self.number_of_producer_reservations);
+
+		unsafe { NonNull::new_unchecked(self.first_ring_buffer_producer_inner.as_ptr().add(producer_reservation_index)) }
 	}
 
 	#[inline(always)]
@@ -9,6 +9,6 @@ impl<T: Sized> Drop for RingBufferInnerDropHandler<T>
 {
 	fn drop(&mut self)
 	{
-		unsafe { self.0.as_ptr().drop_in_place() }
+		unsafe { RingBufferInner::free(self.0) }
 	}
 }
@@ -2,56 +2,84 @@
 // Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
 
 
+/// A process's view of the memory of a ring buffer.
+///
+/// The hands are in the `RingBufferSharedHeader` at the start of the memory; the pointers here are derived from the address at which this process has the memory (they are not in the memory, so that it can be shared between processes).
 #[derive(Debug)]
-#[repr(C)]
 struct RingBufferInnerHeader<T: Sized>
 {
-	/// Ring buffer space (in bytes).
-	space: usize,
+	/// The start of the memory.
+	shared: NonNull<RingBufferSharedHeader>,
+
+	/// Copied from `shared` (it never changes).
 	capacity: usize,
 
-	/// pointer to allocated buffer (within the parent struct); for convenience.
+	/// Copied from `shared` (it never changes).
+	number_of_producers: usize,
+
+	/// Copied from `shared` (it never changes).
+	number_of_producer_reservations: usize,
+
+	/// pointer to allocated buffer (within the memory); for convenience.
+	///
+	/// Derived from the pointer to the memory, not from a reference to the `RingBufferSharedHeader`, so that it can access the whole buffer.
 	buffer: NonNull<T>,
 
-	/// The `NEXT` hand is atomically updated by the producer.
-	/// `Self::WrapLockBit` is set in case of wrap-around; in such a case the producer can update the `end` offset.
-	next: UnsafeCell<VolatileRingBufferOffset>,
-	end: Cell<RingBufferOffset>,
+	/// pointer to the first `RingBufferProducerSlot` (within the memory); derived as for `buffer`.
+	first_ring_buffer_producer_slot: NonNull<RingBufferProducerSlot>,
 
-	/// Updated by consumer.
-	written: Cell<RingBufferOffset>,
+	/// pointer to the first producer reservation's `RingBufferProducerInner` (within the memory); derived as for `buffer`.
+	first_ring_buffer_producer_inner: NonNull<RingBufferProducerInner>,
 
-	number_of_producers: usize,
+	/// Only tracked if the memory is not shared with other processes, which would have their own (and so inconsistent) slot states.
+	#[cfg(feature = "paranoid")] slot_states: SlotStates,
+
+	/// Only if the memory is shared with other processes.
+	#[cfg(feature = "shared-memory")] hardening: Option<ConsumerHardening>,
+
+	/// Whether producers complete reservations, rather than produce them, so that the consumer can consume them out of order; see `RingBuffer::new_with_out_of_order_completion()`.
+	///
+	/// Only if the memory is not shared with other processes.
+	out_of_order: bool,
+
+	/// Only used by the consumer.
+	skipped_ranges: SkippedRanges,
+
+	/// The index of the producer reservation whose items the consumer has been given out of order, and the offset of the first of them not yet released; `None` if the consumer has been given items in order.
+	///
+	/// Only used by the consumer if `out_of_order`; a `Mutex` as the items might be in an `OwnedBatch` released on another thread.
+	out_of_order_consumption: Mutex<Option<(usize, RingBufferOffset)>>,
+
+	/// Set by the consumer when it creates an `OwnedBatch`; cleared when that batch is dropped (potentially on another thread).
+	///
+	/// Only used by the consumer, so kept out of the memory shared with other processes.
+	owned_batch_outstanding: AtomicBool,
 }
 
 impl<T: Sized> RingBufferInnerHeader<T>
 {
-	#[cfg(target_pointer_width = "64")] const WrapCounter: usize = 0x7FFFFFFF00000000;
-	#[cfg(target_pointer_width = "32")] const WrapCounter: usize = 0x7FFF0000;
-	#[cfg(target_pointer_width = "16")] const WrapCounter: usize = 0x7F00;
+	#[cfg(feature = "paranoid")] const WrapCounter: RingBufferOffset = <RingBufferOffset as OffsetArithmetic>::WrapCounter;
 
-	#[cfg(target_pointer_width = "64")] const WrapLockBit: RingBufferOffset = 0x8000000000000000;
-	#[cfg(target_pointer_width = "32")] const WrapLockBit: RingBufferOffset = 0x80000000;
-	#[cfg(target_pointer_width = "16")] const WrapLockBit: RingBufferOffset = 0x8000;
+	const WrapLockBit: RingBufferOffset = <RingBufferOffset as OffsetArithmetic>::WrapLockBit;
 
 	const WrapLockMask: RingBufferOffset = !Self::WrapLockBit;
 
-	const MaximumOffset: RingBufferOffset = ::std::usize::MAX & Self::WrapLockMask;
+	const MaximumOffset: RingBufferOffset = usize::MAX & Self::WrapLockMask;
 
-	#[cfg(target_pointer_width = "64")] const OffsetMask: RingBufferOffset = 0x00000000FFFFFFFF;
-	#[cfg(target_pointer_width = "32")] const OffsetMask: RingBufferOffset = 0x0000FFFF;
-	#[cfg(target_pointer_width = "16")] const OffsetMask: RingBufferOffset = 0x00FF;
+	const OffsetMask: RingBufferOffset = <RingBufferOffset as OffsetArithmetic>::OffsetMask;
 
 	#[inline(always)]
-	pub(crate) fn acquire(&self, producer: &mut RingBufferProducerInner, count: usize) -> Result<usize, ()>
+	pub(crate) fn acquire(&self, producer: &RingBufferProducerInner, count: usize) -> Result<usize, ()>
 	{
-		debug_assert_ne!(count, 0, "length can not be zero");
-		debug_assert!(count <= self.capacity, "count '{}' exceeds self.capacity '{}'", count, self.capacity);
-		debug_assert!(producer.seen_offset.read() == Self::MaximumOffset);
+		paranoid_assert!(self, count != 0, "length can not be zero");
+		paranoid_assert!(self, count <= self.capacity, "count '{}' exceeds self.capacity '{}'", count, self.capacity);
+		paranoid_assert!(self, producer.seen_offset.read() == Self::MaximumOffset);
 
 		let mut target;
 		let mut next;
 
+		#[cfg(feature = "shared-memory")] producer.reserved_count.write(0);
+
 		// This is a do-while loop.
 		while
 		{
@@ -61,128 +89,310 @@ impl<T: Sized> RingBufferInnerHeader<T>
 			// Note: CAS will issue a `memory_order_release` for us and thus ensures that it reaches global visibility together with new `next`.
 			let seen = self.stable_next_offset();
 			next = seen & Self::OffsetMask;
-			debug_assert!(next < self.capacity, "next equals or exceeds space");
+			paranoid_assert!(self, next < self.capacity, "next equals or exceeds space");
 			producer.seen_offset.write(next | Self::WrapLockBit);
 
-			// Compute the target offset.
-			// Key invariant: we cannot go beyond the `WRITTEN` offset or catch up with it.
-			target = next + count;
-			let written = self.written();
-			// The producer must wait.
-			if unlikely!(next < written && target >= written)
-			{
-				producer.seen_offset.write(Self::MaximumOffset);
-				return Err(())
-			}
+			#[cfg(feature = "fault-injection")] FaultInjector::at(FaultInjectionPoint::AcquireAfterSeenOffsetWrite);
 
-			if unlikely!(target >= self.capacity)
+			target = match RingBufferOffset::acquire_target(seen, count, self.written(), self.capacity)
 			{
-				let exceed = target > self.capacity;
-
-				// Wrap-around and start from the beginning.
-				//
-				// If we would exceed the buffer, then attempt to acquire the `Self::WrapLockBit` and use the space in the beginning.
-				// If we used all space exactly to the end, then reset to 0.
-				//
-				// Check the invariant again.
-				target = if exceed
-				{
-					Self::WrapLockBit | count
-				}
-				else
-				{
-					0
-				};
-				if (target & Self::OffsetMask) >= written
+				// The producer must wait.
+				Err(()) =>
 				{
 					producer.seen_offset.write(Self::MaximumOffset);
 					return Err(())
 				}
 
-				// Increment the wrap-around counter.
-				target |= (seen + 0x100000000) & Self::WrapCounter;
-			}
-			else
-			{
-				// Preserve the wrap-around counter.
-				target |= seen & Self::WrapCounter;
-			}
+				Ok(target) => target,
+			};
+
+			#[cfg(feature = "fault-injection")] FaultInjector::at(FaultInjectionPoint::AcquireBeforeCompareAndExchange);
 
-			!self.next_mutable().atomic_compare_and_exchange_weak(seen, target)
+			!self.next().atomic_compare_and_exchange_weak(seen, target)
 		}
 		{
 		}
 
 		// Acquired the range.
+		// Record it whilst the `seen` value is unstable, so that once it is stable the range is always known.
+		#[cfg(feature = "shared-memory")]
+		{
+			producer.reserved_offset.write(if target & Self::WrapLockBit != 0 { 0 } else { next });
+			fence_stores();
+			producer.reserved_count.write(count);
+		}
+
 		// Clear `Self::WrapLockBit` in the `seen` value thus indicating that it is now stable.
 		producer.seen_offset.and_equals(Self::WrapLockMask);
 
 		// If we set the `Self::WrapLockBit` in the `next` (because we exceed the remaining space and need to wrap-around), then save the `end` offset and release the lock.
 		if unlikely!(target & Self::WrapLockBit != 0)
 		{
+			#[cfg(feature = "fault-injection")] FaultInjector::at(FaultInjectionPoint::AcquireWhilstWrapLocked);
+
 			// Cannot wrap-around again if consumer did not catch-up.
-			debug_assert!(self.written() <= next);
-			debug_assert_eq!(self.end(), Self::MaximumOffset);
+			paranoid_assert!(self, self.written() <= next);
+			paranoid_assert!(self, self.end() == Self::MaximumOffset);
 			self.set_end(next);
 			next = 0;
 
 			// Unlock: ensure the `end` offset reaches global visibility before the lock is released.
 			fence_stores();
-			self.next_mutable().write(target & Self::WrapLockMask)
+			self.next().write(target & Self::WrapLockMask)
 		}
-		debug_assert!((target & Self::OffsetMask) <= self.capacity);
+		paranoid_assert!(self, (target & Self::OffsetMask) <= self.capacity);
+
+		#[cfg(feature = "paranoid")] self.paranoid_transition(next, count, |slot_state| slot_state == SlotState::Free, SlotState::Reserved(self.producer_reservation_index(producer)), "overlapping reservation");
+
 		Ok(next)
 	}
 
+	/// The largest `count` which `acquire()` could currently succeed with, which is `0` if there is no free space.
+	///
+	/// Only an estimate, as other producers and the consumer can move the `next` and `written` offsets at any time.
+	#[inline(always)]
+	pub(crate) fn acquirable_count(&self) -> usize
+	{
+		let next = self.stable_next_offset() & Self::OffsetMask;
+		let written = self.written();
+
+		// As `RingBufferOffset::acquire_target()`, the `target` offset can not catch up with the `written` offset.
+		if next < written
+		{
+			written - next - 1
+		}
+		else
+		{
+			// Either up to the end (which wraps-around the `next` offset to `0`, so needs `written` to have moved from `0`), or from the beginning, wasting the space at the end.
+			let up_to_end = if written == 0
+			{
+				self.capacity.saturating_sub(next + 1)
+			}
+			else
+			{
+				self.capacity.saturating_sub(next)
+			};
+			max(up_to_end, written.saturating_sub(1))
+		}
+	}
+
+	#[inline(always)]
+	fn consume(&self) -> (usize, usize)
+	{
+		let (to_write, offset, _wrapped_to_write) = self.consume_segments();
+
+		#[cfg(feature = "paranoid")] self.paranoid_consumed(offset, to_write);
+
+		(to_write, offset)
+	}
+
+	/// As `consume()`, but additionally returns the count of items ready at the start of the buffer (ie from offset `0`) if the consumer can wrap-around once it has released the range returned.
 	#[inline(always)]
-	fn consume(&self, parent: &RingBufferInner<T>) -> (usize, usize)
+	fn consume_segments(&self) -> (usize, usize, usize)
 	{
-		let mut written = self.written();
+		let (to_write, offset, wrapped_to_write, _holding_back) = self.consume_segments_detailed();
+		(to_write, offset, wrapped_to_write)
+	}
+
+	/// As `consume_segments()`, but additionally returns, if nothing is ready, the index of the producer reservation which has been acquired but not yet produced at the `written` offset (if that is why).
+	///
+	/// If reservations are completed out of order and nothing is ready in order, returns the items of a completed reservation instead; see `Self::completed_out_of_order()`.
+	#[inline(always)]
+	fn consume_segments_detailed(&self) -> (usize, usize, usize, Option<usize>)
+	{
+		if self.out_of_order
+		{
+			self.set_out_of_order_consumption(None)
+		}
+
+		let segments = self.consume_segments_in_order();
+
+		if self.out_of_order && segments.0 == 0
+		{
+			if let Some((producer_reservation_index, offset, count)) = self.completed_out_of_order()
+			{
+				self.set_out_of_order_consumption(Some((producer_reservation_index, offset)));
+				return (count, offset, 0, None)
+			}
+		}
+
+		segments
+	}
+
+	/// Skipped ranges (see `SkippedRanges`) which are ready are released without being consumed.
+	#[inline(always)]
+	fn consume_segments_in_order(&self) -> (usize, usize, usize, Option<usize>)
+	{
+		loop
+		{
+			let (to_write, offset, wrapped_to_write, holding_back) = self.consume_segments_including_skipped();
+
+			// Checked after the scan, as it may skip items which were not produced.
+			if likely!(self.skipped_ranges.is_empty())
+			{
+				return (to_write, offset, wrapped_to_write, holding_back)
+			}
+
+			match self.skipped_ranges.cut_short(to_write, offset, wrapped_to_write)
+			{
+				Ok((to_write, offset, wrapped_to_write)) => return (to_write, offset, wrapped_to_write, if to_write == 0 { holding_back } else { None }),
+				Err(skipped_count) => self.release_in_order(skipped_count),
+			}
+		}
+	}
+
+	#[inline(always)]
+	fn consume_segments_including_skipped(&self) -> (usize, usize, usize, Option<usize>)
+	{
+		let mut written = self.consumer_written();
 		let mut next;
 		let mut ready;
+		let mut wrapped_ready;
+		let mut ready_producer_reservation_index;
 
 		'retry: loop
 		{
 			// Get the stable `next` offset.
 			// Note: `self.stable_next_offset()` issued a load memory barrier.
 			// The area between the `written` offset and the `next` offset will be the *preliminary* target buffer area to be consumed.
-			next = self.stable_next_offset() & Self::OffsetMask;
+			next = match self.consumer_stable_next_offset()
+			{
+				None => return (0, 0, 0, None),
+				Some(next) => next & Self::OffsetMask,
+			};
+
+			#[cfg(feature = "fault-injection")] FaultInjector::at(FaultInjectionPoint::ConsumeAfterNextRead);
+
+			#[cfg(feature = "shared-memory")]
+			{
+				if let Some(ref hardening) = self.hardening
+				{
+					if unlikely!(next >= self.capacity)
+					{
+						hardening.corrupt_hand();
+						return (0, 0, 0, None)
+					}
+				}
+			}
+
 			// If producers did not advance, then nothing to do.
 			if written == next
 			{
-				return (0, 0)
+				return (0, 0, 0, None)
 			}
 
-			// Observe the `ready` offset of each producer.
+			// Observe the `ready` offset of each producer reservation.
 			//
 			// At this point, some producer might have already triggered the wrap-around and some (or all) seen `ready` values might be in the range between 0 and `written`.
 			// We have to skip them.
 			ready = Self::MaximumOffset;
+			wrapped_ready = Self::MaximumOffset;
+			ready_producer_reservation_index = None;
 
-			let mut producer = parent.first_ring_buffer_producer_inner_non_null();
-			'workers: for _ in 0 .. self.number_of_producers
+			let mut producer = self.first_ring_buffer_producer_inner;
+			#[cfg_attr(not(feature = "shared-memory"), allow(unused_labels))]
+			'workers: for producer_reservation_index in 0 .. self.number_of_producer_reservations
 			{
+				#[cfg(feature = "shared-memory")]
+				{
+					if let Some(ref hardening) = self.hardening
+					{
+						if hardening.is_quarantined(hardening.producer_index(producer_reservation_index))
+						{
+							producer = RingBufferInner::<T>::next_ring_buffer_producer_inner_non_null(producer);
+							continue 'workers
+						}
+					}
+				}
+
 				let mut seen_offset;
 
+				#[cfg(feature = "fault-injection")] FaultInjector::at(FaultInjectionPoint::ConsumeBeforeSeenOffsetRead);
+
 				// Get a stable `seen` value.
 				// This is necessary since we want to discard the stale `seen` values.
 				let mut spin_lock_back_off = SpinLockBackOff::Initial;
+				#[cfg(feature = "shared-memory")] let mut back_offs = 0;
 				while
 				{
 					seen_offset = unsafe { producer.as_ref() }.seen_offset.read();
 					seen_offset & Self::WrapLockBit != 0
 				}
 				{
+					// A producer process which died (or is hostile) whilst holding the lock would otherwise hang a hardened consumer forever; instead, its reservation is reported as holding back the items after it, until the lock is released or its producer is reaped.
+					#[cfg(feature = "shared-memory")]
+					{
+						if self.hardening.is_some()
+						{
+							back_offs += 1;
+							if unlikely!(back_offs == ConsumerHardening::MaximumBackOffs)
+							{
+								return (0, 0, 0, Some(producer_reservation_index))
+							}
+						}
+					}
+
 					spin_lock_back_off.back_off();
 				}
 
+				// A producer reservation is either not in progress or has an offset within the buffer.
+				#[cfg(feature = "shared-memory")]
+				{
+					if let Some(ref hardening) = self.hardening
+					{
+						if unlikely!(seen_offset != Self::MaximumOffset && seen_offset >= self.capacity)
+						{
+							self.quarantine(hardening, hardening.producer_index(producer_reservation_index));
+							producer = RingBufferInner::<T>::next_ring_buffer_producer_inner_non_null(producer);
+							continue 'workers
+						}
+					}
+				}
+
+				// A reservation only some of whose items were produced (see `RingBufferProducerGuard::produce_first()`) is produced by the consumer once it has skipped the others.
+				if seen_offset != Self::MaximumOffset && !self.out_of_order
+				{
+					let producer_reference = unsafe { producer.as_ref() };
+					let unused_count = producer_reference.unused_count.read();
+					if unlikely!(unused_count != 0)
+					{
+						Self::fence_loads();
+						let unused_offset = producer_reference.unused_offset.read();
+
+						#[cfg(feature = "shared-memory")]
+						{
+							if let Some(ref hardening) = self.hardening
+							{
+								if unlikely!(unused_offset >= self.capacity || unused_count > self.capacity - unused_offset)
+								{
+									self.quarantine(hardening, hardening.producer_index(producer_reservation_index));
+									producer = RingBufferInner::<T>::next_ring_buffer_producer_inner_non_null(producer);
+									continue 'workers
+								}
+							}
+						}
+
+						self.skip_unused(producer_reference, unused_offset, unused_count);
+						producer_reference.produce();
+						seen_offset = Self::MaximumOffset;
+					}
+				}
+
 				// Ignore the offsets after the possible wrap-around.
 				// We are interested in the smallest seen offset that is not behind the `written` offset.
 				if seen_offset >= written
 				{
-					ready = min(seen_offset, ready);
+					if seen_offset < ready
+					{
+						ready = seen_offset;
+						ready_producer_reservation_index = Some(producer_reservation_index);
+					}
+				}
+				else
+				{
+					wrapped_ready = min(seen_offset, wrapped_ready);
 				}
-				debug_assert!(ready >= written);
+				paranoid_assert!(self, ready >= written);
 
 				producer = RingBufferInner::<T>::next_ring_buffer_producer_inner_non_null(producer);
 			}
@@ -192,6 +402,19 @@ impl<T: Sized> RingBufferInnerHeader<T>
 			{
 				let end = min(self.capacity, self.end());
 
+				// A producer which wrapped-around set the `end` offset to its `next` offset, which was not before the `written` offset.
+				#[cfg(feature = "shared-memory")]
+				{
+					if let Some(ref hardening) = self.hardening
+					{
+						if unlikely!(end < written)
+						{
+							hardening.corrupt_hand();
+							return (0, 0, 0, None)
+						}
+					}
+				}
+
 				// Wrap-around case.
 				// Check for the cut off first.
 				//
@@ -199,6 +422,8 @@ impl<T: Sized> RingBufferInnerHeader<T>
 				// However, we must check that the producer is actually done (the observed `ready` offsets are clear).
 				if ready == Self::MaximumOffset && written == end
 				{
+					#[cfg(feature = "fault-injection")] FaultInjector::at(FaultInjectionPoint::ConsumeBeforeWrapAround);
+
 					// Clear the 'end' offset if was set.
 					if self.end() != Self::MaximumOffset
 					{
@@ -215,9 +440,23 @@ impl<T: Sized> RingBufferInnerHeader<T>
 				// We cannot wrap-around yet; there is data to consume at the end.
 				// The ready range is smallest of the observed `ready` or the `end` offset.
 				// If neither is set, then the actual end of the buffer.
-				debug_assert!(ready > next);
+				paranoid_assert!(self, ready > next);
+
+				// If no producer is still writing at the end, then, once the range up to the `end` offset has been released, the consumer can wrap-around.
+				// The data at the start of the buffer is then ready up to the smallest observed `ready` before the `written` offset, or the `next` offset.
+				let wrapped_to_write = if ready == Self::MaximumOffset
+				{
+					min(wrapped_ready, next)
+				}
+				else
+				{
+					0
+				};
+
 				ready = min(ready, end);
-				debug_assert!(ready >= written);
+				paranoid_assert!(self, ready >= written);
+
+				return (ready - written, written, wrapped_to_write, Self::holding_back(ready, written, ready_producer_reservation_index))
 			}
 			else
 			{
@@ -230,43 +469,392 @@ impl<T: Sized> RingBufferInnerHeader<T>
 			let to_write = ready - written;
 			let offset = written;
 
-			debug_assert!(ready >= written);
-			debug_assert!(to_write <= self.capacity);
+			paranoid_assert!(self, ready >= written);
+			paranoid_assert!(self, to_write <= self.capacity);
 
-			return (to_write, offset)
+			return (to_write, offset, 0, Self::holding_back(ready, written, ready_producer_reservation_index))
 		}
 	}
 
+	/// The producer reservation whose `seen` offset is `ready`, if nothing is ready because of it.
 	#[inline(always)]
-	pub(crate) fn release(&self, count: usize)
+	fn holding_back(ready: RingBufferOffset, written: RingBufferOffset, ready_producer_reservation_index: Option<usize>) -> Option<usize>
 	{
-		debug_assert!(self.written() <= self.capacity);
-		debug_assert!(self.written() <= self.end());
+		if ready == written
+		{
+			ready_producer_reservation_index
+		}
+		else
+		{
+			None
+		}
+	}
 
-		let number_written = self.written() + count;
-		debug_assert!(number_written <= self.capacity);
+	/// Finds the producer reservation which is holding back the items after it from being consumed: of those in progress, the one with the smallest `seen` offset from the `written` offset onwards or, if there is none and the producers have wrapped-around, the one with the smallest `seen` offset before it.
+	///
+	/// Returns its index, its `seen` offset and the count of items from that offset up to the `next` offset.
+	///
+	/// A reservation part way through being acquired is ignored, as are those a hardened consumer ignores.
+	#[inline(always)]
+	fn producer_reservation_holding_back_items(&self) -> Option<(usize, RingBufferOffset, usize)>
+	{
+		let written = self.consumer_written();
+		let next = self.stable_next_offset() & Self::OffsetMask;
+		if written == next || next >= self.capacity
+		{
+			return None
+		}
 
-		let value = if number_written == self.capacity
+		let mut holding_back = None;
+		let mut wrapped_holding_back = None;
+		for producer_reservation_index in 0 .. self.number_of_producer_reservations
 		{
-			0
+			#[cfg(feature = "shared-memory")]
+			{
+				if let Some(ref hardening) = self.hardening
+				{
+					if hardening.is_quarantined(hardening.producer_index(producer_reservation_index))
+					{
+						continue
+					}
+				}
+			}
+
+			let producer = self.producer(producer_reservation_index);
+			let seen_offset = producer.seen_offset.read();
+
+			// Not in progress (`Self::MaximumOffset`), part way through being acquired or impossible.
+			if seen_offset & Self::WrapLockBit != 0 || seen_offset >= self.capacity
+			{
+				continue
+			}
+
+			// Completed, so only held back by the consumer.
+			if self.out_of_order && producer.completed_count.read() != 0
+			{
+				continue
+			}
+
+			let candidate = if seen_offset >= written
+			{
+				&mut holding_back
+			}
+			else
+			{
+				&mut wrapped_holding_back
+			};
+			if candidate.is_none_or(|(_, offset)| seen_offset < offset)
+			{
+				*candidate = Some((producer_reservation_index, seen_offset))
+			}
+		}
+
+		if next > written
+		{
+			holding_back.map(|(producer_reservation_index, seen_offset)| (producer_reservation_index, seen_offset, next - seen_offset))
 		}
 		else
 		{
-			number_written
+			match holding_back
+			{
+				Some((producer_reservation_index, seen_offset)) =>
+				{
+					let end = min(self.capacity, self.end());
+					Some((producer_reservation_index, seen_offset, end.saturating_sub(seen_offset) + next))
+				}
+
+				None => wrapped_holding_back.map(|(producer_reservation_index, seen_offset)| (producer_reservation_index, seen_offset, next.saturating_sub(seen_offset))),
+			}
+		}
+	}
+
+	/// Finds the completed reservation nearest the `written` offset of those whose producer has no earlier reservation which is not completed, so that the items of each producer are consumed in the order it acquired them.
+	///
+	/// Returns its index and the offset and count of its items which have not already been released.
+	///
+	/// A completed reservation none of whose items were produced is skipped and cleared.
+	#[inline(always)]
+	fn completed_out_of_order(&self) -> Option<(usize, RingBufferOffset, usize)>
+	{
+		loop
+		{
+			let (producer_reservation_index, seen_offset, completed_count) = self.nearest_completed()?;
+
+			// The items must not be read before the `completed_count` is.
+			Self::fence_loads();
+
+			let producer = self.producer(producer_reservation_index);
+			let produced_count = completed_count - producer.unused_count.read();
+			if produced_count == 0
+			{
+				self.finish_out_of_order(producer);
+				continue
+			}
+
+			let offset = self.completed_offset(seen_offset, completed_count);
+			let released = min(self.skipped_ranges.skipped_from(offset), produced_count);
+			return if released == produced_count
+			{
+				None
+			}
+			else
+			{
+				Some((producer_reservation_index, offset + released, produced_count - released))
+			}
+		}
+	}
+
+	/// Returns the index, `seen_offset` and `completed_count` of the reservation for `Self::completed_out_of_order()`.
+	#[inline(always)]
+	fn nearest_completed(&self) -> Option<(usize, RingBufferOffset, usize)>
+	{
+		let written = self.consumer_written();
+		let distance_from_written = |offset: RingBufferOffset| if offset >= written
+		{
+			offset - written
+		}
+		else
+		{
+			offset + self.capacity - written
 		};
-		self.set_written
-		(
-			value
-		);
+
+		let mut nearest: Option<(usize, usize, RingBufferOffset, usize)> = None;
+		for producer_index in 0 .. self.number_of_producers
+		{
+			let ring_buffer_producer_slot = unsafe { &* self.first_ring_buffer_producer_slot.as_ptr().add(producer_index) };
+
+			// A reservation part way through being acquired is ignored, as it will be after all the others of its producer.
+			let mut earliest: Option<(usize, usize, RingBufferOffset)> = None;
+			for producer_reservation_index in ring_buffer_producer_slot.first_producer_reservation_index .. ring_buffer_producer_slot.first_producer_reservation_index + ring_buffer_producer_slot.maximum_reservations
+			{
+				let seen_offset = self.producer(producer_reservation_index).seen_offset.read();
+				if seen_offset & Self::WrapLockBit != 0 || seen_offset >= self.capacity
+				{
+					continue
+				}
+
+				let distance = distance_from_written(seen_offset);
+				if earliest.is_none_or(|(earliest_distance, _, _)| distance < earliest_distance)
+				{
+					earliest = Some((distance, producer_reservation_index, seen_offset))
+				}
+			}
+
+			if let Some((distance, producer_reservation_index, seen_offset)) = earliest
+			{
+				let completed_count = self.producer(producer_reservation_index).completed_count.read();
+				if completed_count != 0 && nearest.is_none_or(|(nearest_distance, _, _, _)| distance < nearest_distance)
+				{
+					nearest = Some((distance, producer_reservation_index, seen_offset, completed_count))
+				}
+			}
+		}
+
+		nearest.map(|(_, producer_reservation_index, seen_offset, completed_count)| (producer_reservation_index, seen_offset, completed_count))
+	}
+
+	/// A reservation which did not fit before the end of the buffer was acquired at its start instead; its `seen_offset` is still where it did not fit.
+	#[inline(always)]
+	fn completed_offset(&self, seen_offset: RingBufferOffset, completed_count: usize) -> RingBufferOffset
+	{
+		if seen_offset + completed_count > self.capacity
+		{
+			0
+		}
+		else
+		{
+			seen_offset
+		}
+	}
+
+	#[inline(always)]
+	fn out_of_order_consumption(&self) -> Option<(usize, RingBufferOffset)>
+	{
+		if self.out_of_order
+		{
+			*self.out_of_order_consumption.lock().unwrap()
+		}
+		else
+		{
+			None
+		}
+	}
+
+	#[inline(always)]
+	fn set_out_of_order_consumption(&self, out_of_order_consumption: Option<(usize, RingBufferOffset)>)
+	{
+		*self.out_of_order_consumption.lock().unwrap() = out_of_order_consumption
+	}
+
+	/// Once everything before a skipped range (see `SkippedRanges`) has been released, it is released too.
+	#[inline(always)]
+	pub(crate) fn release(&self, count: usize)
+	{
+		if let Some((producer_reservation_index, offset)) = self.out_of_order_consumption()
+		{
+			self.release_out_of_order(producer_reservation_index, offset, count)
+		}
+		else
+		{
+			self.release_in_order(count)
+		}
+
+		if unlikely!(!self.skipped_ranges.is_empty())
+		{
+			self.release_skipped_ranges()
+		}
+	}
+
+	#[inline(always)]
+	fn release_skipped_ranges(&self)
+	{
+		while !self.skipped_ranges.is_empty()
+		{
+			let (to_write, offset, wrapped_to_write, _) = self.consume_segments_including_skipped();
+			match self.skipped_ranges.cut_short(to_write, offset, wrapped_to_write)
+			{
+				Ok(_) => return,
+				Err(skipped_count) => self.release_in_order(skipped_count),
+			}
+		}
+	}
+
+	#[inline(always)]
+	fn release_in_order(&self, count: usize)
+	{
+		paranoid_assert!(self, self.consumer_written() <= self.capacity);
+		paranoid_assert!(self, self.is_hardened() || self.consumer_written() <= self.end());
+
+		paranoid_assert!(self, self.consumer_written() + count <= self.capacity, "releasing '{}' exceeds capacity '{}'", count, self.capacity);
+
+		// Must be done before producers can acquire the space.
+		#[cfg(feature = "paranoid")] self.paranoid_transition(self.consumer_written(), count, |slot_state| slot_state == SlotState::Consumed, SlotState::Free, "double release (or release of data not consumed)");
+
+		#[cfg(feature = "fault-injection")] FaultInjector::at(FaultInjectionPoint::ReleaseBeforeWrittenWrite);
+
+		self.set_written(RingBufferOffset::released_written(self.consumer_written(), count, self.capacity))
 	}
 
+	/// Releases `count` items, from `offset`, of the completed reservation `producer_reservation_index` which have been consumed out of order.
+	///
+	/// Their space is only released back to producers once everything before it has been released (see `SkippedRanges`); once all the items of the reservation have been released, it is cleared so that its producer can use it again.
 	#[inline(always)]
-	pub(crate) fn buffer_consumer_slice_reference(&self, count: usize, offset: usize) -> &[T]
+	fn release_out_of_order(&self, producer_reservation_index: usize, offset: RingBufferOffset, count: usize)
 	{
-		let pointer = self.buffer_pointer(offset) as *const T;
-		unsafe { from_raw_parts(pointer, count) }
+		if count == 0
+		{
+			return
+		}
+
+		self.skipped_ranges.skip(offset, count);
+		self.set_out_of_order_consumption(Some((producer_reservation_index, offset + count)));
+
+		let producer = self.producer(producer_reservation_index);
+		let completed_count = producer.completed_count.read();
+		let produced_count = completed_count - producer.unused_count.read();
+		if offset + count == self.completed_offset(producer.seen_offset.read(), completed_count) + produced_count
+		{
+			self.finish_out_of_order(producer)
+		}
 	}
 
+	/// Skips the items of a completed reservation which were not produced, if any, and clears it so that its producer can use it again.
+	#[inline(always)]
+	fn finish_out_of_order(&self, producer: &RingBufferProducerInner)
+	{
+		let unused_count = producer.unused_count.read();
+		if unused_count != 0
+		{
+			self.skip_unused(producer, producer.unused_offset.read(), unused_count)
+		}
+
+		producer.completed_count.write(0);
+		producer.produce()
+	}
+
+	/// Skips the `unused_count` items from `unused_offset` at the end of the reservation of `producer` which were not produced (see `RingBufferProducerGuard::produce_first()`).
+	#[inline(always)]
+	fn skip_unused(&self, producer: &RingBufferProducerInner, unused_offset: RingBufferOffset, unused_count: usize)
+	{
+		#[cfg(feature = "paranoid")] self.paranoid_transition(unused_offset, unused_count, |slot_state| slot_state == SlotState::Ready, SlotState::Consumed, "skipped items which were not produced");
+
+		self.skipped_ranges.skip(unused_offset, unused_count);
+		producer.unused_count.write(0)
+	}
+
+	/// Wraps-around the consumer after `consume_segments()` returned a non-zero count of items at the start of the buffer and the range before it has been released; then releases `count` items from the start of the buffer.
+	#[inline(always)]
+	pub(crate) fn release_after_wrap_around(&self, count: usize)
+	{
+		// Clear the 'end' offset if was set.
+		if self.end() != Self::MaximumOffset
+		{
+			self.set_end(Self::MaximumOffset);
+			fence_stores();
+		}
+
+		self.set_written(0);
+		self.release(count)
+	}
+
+	/// Produces (enqueues) the reservation of `producer`, of `count` items, or, if reservations are completed out of order, completes it.
+	#[inline(always)]
+	pub(crate) fn produce(&self, producer: &RingBufferProducerInner, count: usize)
+	{
+		paranoid_assert!(self, producer.seen_offset.read() != Self::MaximumOffset, "produced a reservation which was not acquired");
+
+		// Must be done before the consumer can observe the data.
+		#[cfg(feature = "paranoid")] self.paranoid_produced(producer);
+
+		#[cfg(feature = "fault-injection")] FaultInjector::at(FaultInjectionPoint::ProduceBeforeSeenOffsetClear);
+
+		if self.out_of_order
+		{
+			producer.complete(count)
+		}
+		else
+		{
+			producer.produce()
+		}
+	}
+
+	/// As `produce()`, but only the first `produced_count` of the `count` items, from `offset`, of the reservation of `producer` have been written; the consumer skips the others.
+	#[inline(always)]
+	pub(crate) fn produce_first(&self, producer: &RingBufferProducerInner, offset: RingBufferOffset, count: usize, produced_count: usize)
+	{
+		if produced_count >= count
+		{
+			return self.produce(producer, count)
+		}
+
+		paranoid_assert!(self, producer.seen_offset.read() != Self::MaximumOffset, "produced a reservation which was not acquired");
+
+		#[cfg(feature = "paranoid")] self.paranoid_produced(producer);
+
+		#[cfg(feature = "fault-injection")] FaultInjector::at(FaultInjectionPoint::ProduceBeforeSeenOffsetClear);
+
+		producer.leave_unused(offset + produced_count, count - produced_count);
+
+		// Out of order, the reservation is only cleared once its items have been released; until then, `completed_count` includes those which were not produced.
+		if self.out_of_order
+		{
+			producer.complete(count)
+		}
+	}
+
+	#[cfg(feature = "paranoid")]
+	#[inline(always)]
+	fn paranoid_produced(&self, producer: &RingBufferProducerInner)
+	{
+		let producer_reservation_index = self.producer_reservation_index(producer);
+		if self.slot_states.is_tracking() && self.slot_states.transition_all(SlotState::Reserved(producer_reservation_index), SlotState::Ready) == 0
+		{
+			self.paranoid_violation(format_args!("produced producer reservation {} which has no reserved slots", producer_reservation_index))
+		}
+	}
+
+	/// The buffer is only ever accessed through raw pointers; the consumer must not have another slice of the same items.
+	#[allow(clippy::mut_from_ref)]
 	#[inline(always)]
 	pub(crate) fn buffer_consumer_slice_mutable(&self, count: usize, offset: usize) -> &mut [T]
 	{
@@ -274,77 +862,216 @@ impl<T: Sized> RingBufferInnerHeader<T>
 		unsafe { from_raw_parts_mut(pointer, count) }
 	}
 
-	pub(crate) fn drop_remaining_data(&self, parent: &RingBufferInner<T>)
+	/// Asserts the invariants of the offsets, returning the count of items acquired but not yet released (which excludes any space wasted at the end of the buffer by a wrap-around).
+	///
+	/// Only meaningful whilst no other thread is part way through an operation.
+	#[cfg(fuzzing)]
+	fn assert_invariants(&self) -> usize
+	{
+		let next = self.stable_next_offset() & Self::OffsetMask;
+		let end = self.end();
+		let written = self.written();
+
+		assert!(written <= self.capacity, "written '{}' exceeds capacity '{}'", written, self.capacity);
+		assert!(next < self.capacity, "next '{}' equals or exceeds capacity '{}'", next, self.capacity);
+
+		if end != Self::MaximumOffset
+		{
+			assert!(end <= self.capacity, "end '{}' exceeds capacity '{}'", end, self.capacity);
+			assert!(written <= end, "written '{}' is after end '{}'", written, end);
+
+			// After a wrap-around, `next` can never catch up with `written`.
+			assert!(next < written, "next '{}' has caught up with written '{}' after wrap-around", next, written);
+		}
+
+		if next >= written
+		{
+			next - written
+		}
+		else
+		{
+			min(self.capacity, end) - written + next
+		}
+	}
+
+	pub(crate) fn drop_remaining_data(&self)
 	{
 		// Done twice in case of wrap-around.
-		for _ in 0 .. self.number_of_producers * 2
+		for _ in 0 .. self.number_of_producer_reservations * 2
 		{
-			let (count, offset) = self.consume(parent);
+			let (count, offset) = self.consume();
 			let buffer_slice = self.buffer_consumer_slice_mutable(count, offset);
 			for datum_pointer in buffer_slice.iter_mut()
 			{
 				unsafe { drop_in_place(datum_pointer) }
 			}
+
+			// Otherwise the next iteration would consume, and drop, the same data again.
+			self.release(count)
 		}
 	}
 
+	#[cfg(feature = "paranoid")]
 	#[inline(always)]
-	fn buffer_pointer(&self, offset: usize) -> *mut T
+	fn paranoid_consumed(&self, offset: usize, count: usize)
 	{
-		unsafe { self.buffer.as_ptr().add(offset) }
+		self.paranoid_transition(offset, count, |slot_state| slot_state == SlotState::Ready || slot_state == SlotState::Consumed, SlotState::Consumed, "consumer slice overlaps data not produced")
 	}
 
+	#[cfg(feature = "paranoid")]
 	#[inline(always)]
-	pub(crate) fn new(capacity: usize, number_of_producers: usize) -> Self
+	fn paranoid_transition<F: Fn(SlotState) -> bool>(&self, offset: usize, count: usize, from: F, to: SlotState, violation: &str)
 	{
-		let length_in_bytes = size_of::<T>() * capacity;
-		assert!(length_in_bytes < ::std::usize::MAX, "length_in_bytes `{}` exceeds ::std::usize::MAX `{}`", length_in_bytes, ::std::usize::MAX);
+		if !self.slot_states.is_tracking()
+		{
+			return
+		}
 
-		let space =
+		if let Err((index, slot_state)) = self.slot_states.transition(offset, count, from, to)
 		{
-			let alignment = Self::alignment();
-			let round_up_length_to_alignment = ((length_in_bytes + alignment - 1) / alignment) * alignment;
-			round_up_length_to_alignment
-		};
-		assert!(space < ::std::usize::MAX, "space '{}' exceeds ::std::usize::MAX `{}`", space, ::std::usize::MAX);
-		assert!(space < Self::OffsetMask, "space '{}' equals or exceeds Self::OffsetMask `{}`", space, Self::OffsetMask);
+			self.paranoid_violation(format_args!("{} at slot {} which is {} (whilst changing slots {} .. {} to {})", violation, index, slot_state, offset, offset + count, to))
+		}
+	}
 
-		Self
+	/// Panics with a dump of all the hands and slots.
+	#[cfg(feature = "paranoid")]
+	#[cold]
+	#[inline(never)]
+	fn paranoid_violation(&self, message: Arguments) -> !
+	{
+		panic!("ring buffer invariant violated: {}\n{}", message, self.dump())
+	}
+
+	#[cfg(feature = "paranoid")]
+	fn dump(&self) -> String
+	{
+		#[inline(always)]
+		fn offset(offset: RingBufferOffset) -> String
 		{
-			space,
-			capacity,
-			buffer: unsafe { uninitialized() },
-			next: UnsafeCell::new(VolatileRingBufferOffset(UnsafeCell::new(0))),
-			end: Cell::new(Self::MaximumOffset),
-			written: Cell::new(0),
-			number_of_producers,
+			if offset == RingBufferInnerHeader::<()>::MaximumOffset
+			{
+				"unset".to_string()
+			}
+			else
+			{
+				format!("{}", offset)
+			}
+		}
+
+		let next = self.next().read();
+
+		let mut seen_offsets = Vec::with_capacity(self.number_of_producer_reservations);
+		let mut producer = self.first_ring_buffer_producer_inner;
+		for _ in 0 .. self.number_of_producer_reservations
+		{
+			let seen_offset = unsafe { producer.as_ref() }.seen_offset.read();
+			let locked = if seen_offset & Self::WrapLockBit != 0
+			{
+				" (locked)"
+			}
+			else
+			{
+				""
+			};
+			seen_offsets.push(format!("{}{}", offset(seen_offset & Self::WrapLockMask), locked));
+			producer = RingBufferInner::<T>::next_ring_buffer_producer_inner_non_null(producer);
 		}
+
+		format!
+		(
+			"capacity: {}\nnext: {} (wrap lock bit: {}, wrap counter: {:#x})\nend: {}\nwritten: {}\nseen offsets by producer reservation: [{}]\nslots: {}",
+			self.capacity,
+			next & Self::OffsetMask,
+			next & Self::WrapLockBit != 0,
+			next & Self::WrapCounter,
+			offset(self.end()),
+			offset(self.written()),
+			seen_offsets.join(", "),
+			self.slot_states.describe(),
+		)
 	}
 
+	#[cfg(feature = "paranoid")]
 	#[inline(always)]
-	fn initialize_buffer(&mut self, after_last_ring_buffer_producer_inner_non_null: NonNull<RingBufferProducerInner>)
+	fn producer_reservation_index(&self, producer: &RingBufferProducerInner) -> usize
 	{
-		unsafe { write(&mut self.buffer, NonNull::new_unchecked(after_last_ring_buffer_producer_inner_non_null.as_ptr() as *mut T)) }
+		let first = self.first_ring_buffer_producer_inner.as_ptr() as usize;
+		(producer as *const RingBufferProducerInner as usize - first) / size_of::<RingBufferProducerInner>()
 	}
 
 	#[inline(always)]
-	fn layout(&self) -> Layout
+	fn producer(&self, producer_reservation_index: usize) -> &RingBufferProducerInner
 	{
-		let size =
-		{
-			let header_size = size_of::<Self>();
-			let producers_size = self.number_of_producers * size_of::<RingBufferProducerInner>();
-			let buffer_size = self.space;
+		debug_assert!(producer_reservation_index < self.number_of_producer_reservations, "producer_reservation_index '{}' equals or exceeds number_of_producer_reservations '{}'", producer_reservation_index, self.number_of_producer_reservations);
 
-			header_size + producers_size + buffer_size
-		};
-		Layout::from_size_align(size, Self::alignment()).unwrap()
+		unsafe { &* self.first_ring_buffer_producer_inner.as_ptr().add(producer_reservation_index) }
+	}
+
+	#[inline(always)]
+	fn buffer_pointer(&self, offset: usize) -> *mut T
+	{
+		unsafe { self.buffer.as_ptr().add(offset) }
 	}
 
+	/// Creates the `RingBufferSharedHeader` of a new ring buffer.
 	#[inline(always)]
-	fn alignment() -> usize
+	fn shared_header(capacity: usize, number_of_producers: usize, number_of_producer_reservations: usize) -> RingBufferSharedHeader
 	{
-		max(align_of::<Self>(), max(align_of::<RingBufferProducerInner>(), align_of::<T>()))
+		let length_in_bytes = size_of::<T>().checked_mul(capacity).expect("length_in_bytes exceeds ::std::usize::MAX");
+		assert!(length_in_bytes < Self::OffsetMask, "length_in_bytes '{}' equals or exceeds Self::OffsetMask `{}`", length_in_bytes, Self::OffsetMask);
+		assert!(capacity < Self::OffsetMask, "capacity '{}' equals or exceeds Self::OffsetMask `{}`", capacity, Self::OffsetMask);
+
+		RingBufferSharedHeader::new::<T>(capacity, number_of_producers, number_of_producer_reservations)
+	}
+
+	/// `memory` must point to the start of the memory of a ring buffer, which has been initialized with `format`, and must have the provenance of the whole memory.
+	///
+	/// If `memory` is not `process_local`, `format` must be the snapshot which was validated, and its `RingBufferProducerSlot`s must have been validated (see `ConsumerHardening::validate_producer_slots()`); the `format` in the memory is never read, as another process might have changed it since.
+	///
+	/// `out_of_order` must only be `true` if `memory` is `process_local`.
+	#[inline(always)]
+	unsafe fn new(memory: NonNull<u8>, format: RingBufferFormat, process_local: bool, out_of_order: bool) -> Self
+	{
+		let shared = memory.cast::<RingBufferSharedHeader>();
+		let capacity = format.capacity();
+
+		#[cfg(not(any(feature = "paranoid", feature = "shared-memory")))] let _ = process_local;
+
+		let number_of_producers = format.number_of_producers();
+		let number_of_producer_reservations = format.number_of_producer_reservations();
+		let first_ring_buffer_producer_slot = NonNull::new_unchecked(memory.as_ptr().add(RingBufferSharedHeader::producer_slots_offset()) as *mut RingBufferProducerSlot);
+
+		Self
+		{
+			shared,
+			capacity,
+			number_of_producers,
+			number_of_producer_reservations,
+			buffer: NonNull::new_unchecked(memory.as_ptr().add(format.buffer_offset()) as *mut T),
+			first_ring_buffer_producer_slot,
+			first_ring_buffer_producer_inner: NonNull::new_unchecked(memory.as_ptr().add(format.producers_offset()) as *mut RingBufferProducerInner),
+			#[cfg(feature = "paranoid")] slot_states: SlotStates::new(if process_local { capacity } else { 0 }),
+			#[cfg(feature = "shared-memory")] hardening: if process_local
+			{
+				None
+			}
+			else
+			{
+				// A `written` offset which is impossible can only be that of an earlier consumer which crashed part way through a release, or of a hostile process; either way, the items are lost.
+				let written = shared.as_ref().written.read();
+				Some(ConsumerHardening::new(first_ring_buffer_producer_slot, number_of_producers, number_of_producer_reservations, if written <= capacity { written } else { 0 }))
+			},
+			out_of_order,
+			skipped_ranges: SkippedRanges::new(),
+			out_of_order_consumption: Mutex::new(None),
+			owned_batch_outstanding: AtomicBool::new(false),
+		}
+	}
+
+	#[inline(always)]
+	fn shared(&self) -> &RingBufferSharedHeader
+	{
+		unsafe { self.shared.as_ref() }
 	}
 
 	#[inline(always)]
@@ -364,44 +1091,124 @@ impl<T: Sized> RingBufferInnerHeader<T>
 
 		Self::fence_loads();
 
-		debug_assert!((next & Self::MaximumOffset) < self.capacity);
+		paranoid_assert!(self, self.is_hardened() || (next & Self::OffsetMask) < self.capacity);
 		next
 	}
 
+	/// As `stable_next_offset()`, but a hardened consumer gives up after `ConsumerHardening::MaximumBackOffs` back-offs and returns `None`, as a producer process which died (or is hostile) whilst holding the wrap-around lock would otherwise hang it forever.
 	#[inline(always)]
-	fn next(&self) -> &VolatileRingBufferOffset
+	fn consumer_stable_next_offset(&self) -> Option<RingBufferOffset>
 	{
-		unsafe { & * self.next.get() }
+		#[cfg(feature = "shared-memory")]
+		{
+			if self.hardening.is_some()
+			{
+				let mut spin_lock_back_off = SpinLockBackOff::Initial;
+				for _ in 0 .. ConsumerHardening::MaximumBackOffs
+				{
+					let next = self.next().read();
+					if next & Self::WrapLockBit == 0
+					{
+						Self::fence_loads();
+						return Some(next)
+					}
+					spin_lock_back_off.back_off();
+				}
+				return None
+			}
+		}
+
+		Some(self.stable_next_offset())
 	}
 
 	#[inline(always)]
-	fn next_mutable(&self) -> &mut VolatileRingBufferOffset
+	fn next(&self) -> &AtomicRingBufferOffset
 	{
-		unsafe { &mut * self.next.get() }
+		&self.shared().next
 	}
 
 	#[inline(always)]
 	fn written(&self) -> RingBufferOffset
 	{
-		self.written.get()
+		self.shared().written.read()
 	}
 
 	#[inline(always)]
 	fn set_written(&self, written: RingBufferOffset)
 	{
-		self.written.set(written);
+		#[cfg(feature = "shared-memory")]
+		{
+			if let Some(ref hardening) = self.hardening
+			{
+				hardening.set_written(written)
+			}
+		}
+
+		self.shared().written.write(written);
+	}
+
+	/// The `written` offset, as the consumer knows it; a hardened consumer does not trust the copy in the memory.
+	#[inline(always)]
+	fn consumer_written(&self) -> RingBufferOffset
+	{
+		#[cfg(feature = "shared-memory")]
+		{
+			if let Some(ref hardening) = self.hardening
+			{
+				return hardening.written()
+			}
+		}
+
+		self.written()
+	}
+
+	/// Is this the view of memory shared with other processes, which might write anything into it?
+	#[inline(always)]
+	fn is_hardened(&self) -> bool
+	{
+		#[cfg(feature = "shared-memory")]
+		{
+			self.hardening.is_some()
+		}
+
+		#[cfg(not(feature = "shared-memory"))]
+		{
+			false
+		}
+	}
+
+	/// Quarantines `producer_index`, both for this consumer and, advisedly, in the memory, so that it can not be claimed again.
+	#[cfg(feature = "shared-memory")]
+	#[cold]
+	#[inline(never)]
+	fn quarantine(&self, hardening: &ConsumerHardening, producer_index: usize)
+	{
+		hardening.quarantine(producer_index);
+		unsafe { &* self.first_ring_buffer_producer_slot.as_ptr().add(producer_index) }.quarantine()
+	}
+
+	#[inline(always)]
+	fn owned_batch_outstanding(&self) -> bool
+	{
+		self.owned_batch_outstanding.load(Acquire)
+	}
+
+	#[inline(always)]
+	fn set_owned_batch_outstanding(&self, owned_batch_outstanding: bool)
+	{
+		self.owned_batch_outstanding.store(owned_batch_outstanding, Release)
 	}
 
 	#[inline(always)]
 	fn end(&self) -> RingBufferOffset
 	{
-		self.end.get()
+		self.shared().end.read()
 	}
 
 	#[inline(always)]
 	fn set_end(&self, end: RingBufferOffset)
 	{
-		self.end.set(end);
+		self.shared().end.write(end);
 	}
 
 	#[inline(always)]
@@ -0,0 +1,49 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Where the memory of a ring buffer is, and so how to free it.
+#[derive(Debug)]
+enum RingBufferMemory
+{
+	/// Allocated by this process with `alloc()`.
+	Heap(Layout),
+
+	/// A shared mapping of `length` bytes; other processes might have it mapped too.
+	#[cfg(feature = "shared-memory")]
+	SharedMemory
+	{
+		length: usize,
+	},
+}
+
+impl RingBufferMemory
+{
+	/// Is this memory only used by this process?
+	#[inline(always)]
+	fn is_process_local(&self) -> bool
+	{
+		match *self
+		{
+			RingBufferMemory::Heap(_) => true,
+
+			#[cfg(feature = "shared-memory")] RingBufferMemory::SharedMemory { .. } => false,
+		}
+	}
+
+	/// `memory` must be the start of this memory, which must not be used again.
+	#[inline(always)]
+	unsafe fn free(&self, memory: NonNull<u8>)
+	{
+		match *self
+		{
+			RingBufferMemory::Heap(layout) => dealloc(memory.as_ptr(), layout),
+
+			#[cfg(feature = "shared-memory")] RingBufferMemory::SharedMemory { length } =>
+			{
+				let result = munmap(memory.as_ptr() as *mut c_void, length);
+				debug_assert_eq!(result, 0, "munmap failed");
+			}
+		}
+	}
+}
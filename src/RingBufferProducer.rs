@@ -7,7 +7,28 @@
 pub struct RingBufferProducer<T: Sized>
 {
 	ring_buffer: RingBuffer<T>,
+
+	/// Claimed by this producer; unclaimed when it is dropped.
+	ring_buffer_producer_slot_non_null: NonNull<RingBufferProducerSlot>,
+
+	/// First of `maximum_reservations` contiguous producer inners, one for each reservation.
 	ring_buffer_producer_inner_non_null: NonNull<RingBufferProducerInner>,
+	maximum_reservations: usize,
+}
+
+// The producer slot and producer inners pointed to are claimed by this producer alone; everything it shares with the other producers and the consumer is atomic, and the ring buffer is kept alive by an `Arc`.
+// Items are moved in on the producer's thread and out on the consumer's, so must be `Send`.
+unsafe impl<T: Send> Send for RingBufferProducer<T>
+{
+}
+
+impl<T: Sized> Drop for RingBufferProducer<T>
+{
+	#[inline(always)]
+	fn drop(&mut self)
+	{
+		unsafe { self.ring_buffer_producer_slot_non_null.as_ref() }.unclaim()
+	}
 }
 
 impl<T: Sized> RingBufferProducer<T>
@@ -23,11 +44,13 @@ impl<T: Sized> RingBufferProducer<T>
 	/// * `count` should not be zero.
 	/// * `count` should not exceed the buffer size.
 	///
-	/// Returns an Err if the producer could acquire a slice of the desired `count`.
+	/// Returns an Err if the producer could acquire a slice of the desired `count`, or if it already has its maximum number of reservations.
 	#[inline(always)]
 	pub fn acquire<'a>(&'a self, count: usize) -> Result<RingBufferProducerGuard<'a, T>, ()>
 	{
-		match self.reference().acquire(self.producer(), count)
+		let ring_buffer_producer_inner_non_null = self.free_reservation()?;
+
+		match self.reference().acquire(unsafe { ring_buffer_producer_inner_non_null.as_ref() }, count)
 		{
 			Err(()) => Err(()),
 
@@ -36,7 +59,38 @@ impl<T: Sized> RingBufferProducer<T>
 				RingBufferProducerGuard
 				{
 					buffer_slice: self.reference().buffer_consumer_slice_mutable(count, offset),
-					producer: self,
+					offset,
+					ring_buffer_producer_inner_non_null,
+					ring_buffer_producer: self,
+				}
+			)
+		}
+	}
+
+	/// As `acquire()`, but returns a reservation which does not borrow this producer.
+	///
+	/// The reservation keeps the ring buffer alive and can be sent to, and committed from, another thread (eg when an I/O operation filling it completes).
+	///
+	/// Counts towards this producer's maximum number of reservations until it is committed (dropped).
+	///
+	/// Returns an Err if the producer could acquire a slice of the desired `count`, or if it already has its maximum number of reservations.
+	#[inline(always)]
+	pub fn acquire_owned(&self, count: usize) -> Result<OwnedReservation<T>, ()>
+	{
+		let ring_buffer_producer_inner_non_null = self.free_reservation()?;
+
+		match self.reference().acquire(unsafe { ring_buffer_producer_inner_non_null.as_ref() }, count)
+		{
+			Err(()) => Err(()),
+
+			Ok(offset) => Ok
+			(
+				OwnedReservation
+				{
+					ring_buffer: self.ring_buffer.clone(),
+					ring_buffer_producer_inner_non_null,
+					first: unsafe { NonNull::new_unchecked(self.reference().buffer_pointer(offset)) },
+					count,
 				}
 			)
 		}
@@ -54,14 +108,14 @@ impl<T: Sized> RingBufferProducer<T>
 	{
 		let mut try_to_acquire_count = populate_with.len();
 
-		while populate_with.len() != 0
+		while !populate_with.is_empty()
 		{
 			match self.acquire(try_to_acquire_count)
 			{
 				Ok(mut slice_guard) => unsafe
 				{
 					let from = populate_with.len() - try_to_acquire_count;
-					slice_guard.as_mut_ptr().copy_from_nonoverlapping(populate_with.get_unchecked(from), try_to_acquire_count);
+					slice_guard.as_mut_ptr().copy_from_nonoverlapping(populate_with.as_ptr().add(from), try_to_acquire_count);
 					populate_with.set_len(from);
 				},
 
@@ -79,15 +133,34 @@ impl<T: Sized> RingBufferProducer<T>
 		}
 	}
 	
+	/// As `acquire()`, but acquires as much of the free space, up to `maximum`, as is contiguous.
 	#[inline(always)]
-	pub(crate) fn produce(&self)
+	fn acquire_up_to<'a>(&'a self, maximum: usize) -> io::Result<RingBufferProducerGuard<'a, T>>
 	{
-		let producer = self.producer();
-		debug_assert_ne!(producer.seen_offset.read(), RingBufferInnerHeader::<T>::MaximumOffset);
+		if self.free_reservation().is_err()
+		{
+			return Err(io::Error::from(io::ErrorKind::WouldBlock))
+		}
+
+		let mut try_to_acquire_count = min(maximum, self.reference().acquirable_count());
+		while try_to_acquire_count != 0
+		{
+			if let Ok(slice_guard) = self.acquire(try_to_acquire_count)
+			{
+				return Ok(slice_guard)
+			}
 
-		fence_stores();
+			// Another producer acquired some of the free space first (or the consumer has since released more); always trying less ensures this gives up.
+			try_to_acquire_count = min(try_to_acquire_count - 1, self.reference().acquirable_count());
+		}
+		Err(io::Error::from(io::ErrorKind::WouldBlock))
+	}
 
-		producer.seen_offset.write(RingBufferInnerHeader::<T>::MaximumOffset);
+	/// The maximum number of reservations (acquisitions) this producer can have at a time.
+	#[inline(always)]
+	pub fn maximum_reservations(&self) -> usize
+	{
+		self.maximum_reservations
 	}
 	
 	#[inline(always)]
@@ -96,9 +169,67 @@ impl<T: Sized> RingBufferProducer<T>
 		self.ring_buffer.reference()
 	}
 	
+	/// Finds a producer inner which does not currently have a reservation.
+	#[inline(always)]
+	fn free_reservation(&self) -> Result<NonNull<RingBufferProducerInner>, ()>
+	{
+		let mut ring_buffer_producer_inner_non_null = self.ring_buffer_producer_inner_non_null;
+		for _ in 0 .. self.maximum_reservations
+		{
+			if unsafe { ring_buffer_producer_inner_non_null.as_ref() }.seen_offset.read() == RingBufferInnerHeader::<T>::MaximumOffset
+			{
+				return Ok(ring_buffer_producer_inner_non_null)
+			}
+			ring_buffer_producer_inner_non_null = RingBufferInner::<T>::next_ring_buffer_producer_inner_non_null(ring_buffer_producer_inner_non_null);
+		}
+		Err(())
+	}
+}
+
+impl RingBufferProducer<u8>
+{
+	/// Writes into the ring buffer with `io::Write`; see `RingBufferProducerWriter`.
+	#[inline(always)]
+	pub fn writer<'a>(&'a self) -> RingBufferProducerWriter<'a>
+	{
+		RingBufferProducerWriter
+		{
+			producer: self,
+			pending: Vec::with_capacity(self.maximum_reservations),
+		}
+	}
+
+	/// Acquires as much contiguous space as it can, up to `maximum` bytes, reads into it from `reader` with one `Read::read()` and produces only the bytes read (see `RingBufferProducerGuard::produce_first()`).
+	///
+	/// Returns the count of bytes read, which is `0` if `maximum` is `0` or `reader` is at end-of-file.
+	/// A short read holds its reservation until the consumer's next consume, as for `RingBufferProducerGuard::produce_first()`.
+	/// Fails with an `io::Error` of kind `io::ErrorKind::WouldBlock` if not even one byte can be acquired, or with the error of `reader`, in which case nothing is produced.
 	#[inline(always)]
-	fn producer(&self) -> &mut RingBufferProducerInner
+	pub fn read_from<R: Read>(&self, reader: &mut R, maximum: usize) -> io::Result<usize>
 	{
-		unsafe { &mut * self.ring_buffer_producer_inner_non_null.as_ptr() }
+		if maximum == 0
+		{
+			return Ok(0)
+		}
+
+		let mut slice_guard = self.acquire_up_to(maximum)?;
+
+		// `Read::read()` is allowed to read from the slice it is passed, so it must not be passed uninitialized memory.
+		slice_guard.fill(0);
+
+		match reader.read(&mut slice_guard)
+		{
+			Ok(count) =>
+			{
+				slice_guard.produce_first(count);
+				Ok(count)
+			}
+
+			Err(error) =>
+			{
+				slice_guard.produce_first(0);
+				Err(error)
+			}
+		}
 	}
 }
@@ -16,8 +16,13 @@ pub struct RingBufferProducerGuard<'a, T: 'a + Sized>
 	///
 	/// All indices ***MUST*** be populated with valid (initialized) data.
 	buffer_slice: &'a mut [T],
-	
-	producer: &'a RingBufferProducer<T>,
+
+	offset: usize,
+
+	/// The producer inner of the reservation.
+	ring_buffer_producer_inner_non_null: NonNull<RingBufferProducerInner>,
+
+	ring_buffer_producer: &'a RingBufferProducer<T>,
 }
 
 impl<'a, T: 'a + Sized> Deref for RingBufferProducerGuard<'a, T>
@@ -45,6 +50,21 @@ impl<'a, T: 'a + Sized> Drop for RingBufferProducerGuard<'a, T>
 	#[inline(always)]
 	fn drop(&mut self)
 	{
-		self.producer.produce()
+		self.ring_buffer_producer.reference().produce(unsafe { self.ring_buffer_producer_inner_non_null.as_ref() }, self.buffer_slice.len())
+	}
+}
+
+impl<'a, T: 'a + Sized> RingBufferProducerGuard<'a, T>
+{
+	/// Produces (enqueues) only the first `count` items, which must have been populated; the others are never consumed (or dropped), and their space is released back to the ring buffer once the consumer reaches it.
+	///
+	/// Unless reservations are completed out of order, the reservation itself is only freed by the consumer's next consume, so, until then, it counts towards the producer's maximum number of reservations.
+	///
+	/// `count` is clamped to `self.len()`.
+	#[inline(always)]
+	pub fn produce_first(self, count: usize)
+	{
+		self.ring_buffer_producer.reference().produce_first(unsafe { self.ring_buffer_producer_inner_non_null.as_ref() }, self.offset, self.buffer_slice.len(), count);
+		forget(self)
 	}
 }
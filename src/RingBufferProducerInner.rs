@@ -5,19 +5,77 @@
 #[derive(Debug)]
 struct RingBufferProducerInner
 {
-	seen_offset: VolatileRingBufferOffset,
+	seen_offset: AtomicRingBufferOffset,
+
+	/// The offset of the range of the reservation in progress, recorded before `seen_offset` is stable, so that the range can be poisoned if the producer's process dies; only meaningful if `reserved_count` is not zero.
+	#[cfg(feature = "shared-memory")] reserved_offset: AtomicRingBufferOffset,
+
+	/// The count of the range of the reservation in progress, recorded after `reserved_offset`; `0` if not yet recorded.
+	#[cfg(feature = "shared-memory")] reserved_count: AtomicRingBufferOffset,
+
+	/// The count of items of the reservation in progress once it has been completed out of order; `0` if not completed.
+	completed_count: AtomicRingBufferOffset,
+
+	/// The offset of the items at the end of the reservation in progress which were not produced (see `RingBufferProducerGuard::produce_first()`); only meaningful if `unused_count` is not zero.
+	unused_offset: AtomicRingBufferOffset,
+
+	/// The count of items at the end of the reservation in progress which were not produced, recorded after `unused_offset`; `0` if all of them were, or once the consumer has skipped them.
+	unused_count: AtomicRingBufferOffset,
 }
 
 impl RingBufferProducerInner
 {
-	const Default: Self = Self
+	#[inline(always)]
+	fn initialize(this: NonNull<Self>)
 	{
-		seen_offset: VolatileRingBufferOffset(UnsafeCell::new(RingBufferInnerHeader::<()>::MaximumOffset)),
-	};
+		let default = Self
+		{
+			seen_offset: AtomicRingBufferOffset::new(RingBufferInnerHeader::<()>::MaximumOffset),
+			#[cfg(feature = "shared-memory")] reserved_offset: AtomicRingBufferOffset::new(0),
+			#[cfg(feature = "shared-memory")] reserved_count: AtomicRingBufferOffset::new(0),
+			completed_count: AtomicRingBufferOffset::new(0),
+			unused_offset: AtomicRingBufferOffset::new(0),
+			unused_count: AtomicRingBufferOffset::new(0),
+		};
+
+		unsafe { write(this.as_ptr(), default) }
+	}
 
 	#[inline(always)]
-	fn initialize(this: NonNull<Self>)
+	fn produce(&self)
+	{
+		debug_assert_ne!(self.seen_offset.read(), RingBufferInnerHeader::<()>::MaximumOffset);
+
+		fence_stores();
+
+		self.seen_offset.write(RingBufferInnerHeader::<()>::MaximumOffset);
+	}
+
+	/// Records that the `count` items from `offset` at the end of the reservation in progress were not produced; the consumer skips them, then produces the reservation.
+	#[inline(always)]
+	fn leave_unused(&self, offset: RingBufferOffset, count: usize)
 	{
-		unsafe { write(this.as_ptr(), RingBufferProducerInner::Default) }
+		debug_assert_ne!(self.seen_offset.read(), RingBufferInnerHeader::<()>::MaximumOffset);
+		debug_assert_ne!(count, 0);
+
+		fence_stores();
+
+		self.unused_offset.write(offset);
+
+		fence_stores();
+
+		self.unused_count.write(count);
+	}
+
+	/// Completes the reservation in progress, of `count` items (including any which were not produced; see `Self::leave_unused()`), so that the consumer can consume it out of order; the consumer produces it once it has released all of them.
+	#[inline(always)]
+	fn complete(&self, count: usize)
+	{
+		debug_assert_ne!(self.seen_offset.read(), RingBufferInnerHeader::<()>::MaximumOffset);
+		debug_assert_ne!(count, 0);
+
+		fence_stores();
+
+		self.completed_count.write(count);
 	}
 }
@@ -0,0 +1,107 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Describes which `RingBufferProducerInner`s a producer uses, and whether a `RingBufferProducer` currently has them.
+///
+/// Part of the position-independent memory of a ring buffer, so that a producer in another process can claim a slot.
+#[derive(Debug)]
+#[repr(C)]
+struct RingBufferProducerSlot
+{
+	claimed: AtomicBool,
+
+	/// Set by a hardened consumer (see `ConsumerHardening`) once the producer has published an impossible offset; it can then never be claimed again.
+	///
+	/// Only advisory, as a hostile process can clear it; the consumer's own record is authoritative.
+	quarantined: AtomicBool,
+
+	/// The process identifier of the process which has claimed this slot, so that a consumer can detect that it has died (see `RingBufferInner::reap_dead_producers()`); `0` if unclaimed.
+	#[cfg(feature = "shared-memory")] owner_process_identifier: AtomicUsize,
+
+	/// When the process which has claimed this slot started (see `process_start_time()`), so that a consumer can detect that its process identifier has since been reused by another process; `0` if unknown.
+	#[cfg(feature = "shared-memory")] owner_process_start_time: AtomicUsize,
+
+	/// Index of the first of `maximum_reservations` contiguous `RingBufferProducerInner`s.
+	first_producer_reservation_index: usize,
+
+	maximum_reservations: usize,
+}
+
+impl RingBufferProducerSlot
+{
+	#[inline(always)]
+	fn initialize(this: NonNull<Self>, first_producer_reservation_index: usize, maximum_reservations: usize)
+	{
+		let default = Self
+		{
+			claimed: AtomicBool::new(false),
+			quarantined: AtomicBool::new(false),
+			#[cfg(feature = "shared-memory")] owner_process_identifier: AtomicUsize::new(0),
+			#[cfg(feature = "shared-memory")] owner_process_start_time: AtomicUsize::new(0),
+			first_producer_reservation_index,
+			maximum_reservations,
+		};
+
+		unsafe { write(this.as_ptr(), default) }
+	}
+
+	/// Tries to become the producer using this slot.
+	#[inline(always)]
+	fn claim(&self) -> bool
+	{
+		let claimed = !self.quarantined.load(Acquire) && !self.claimed.swap(true, AcqRel);
+
+		#[cfg(feature = "shared-memory")]
+		{
+			if claimed
+			{
+				let owner_process_identifier = process_identifier() as usize;
+				self.owner_process_start_time.store(process_start_time(owner_process_identifier), Relaxed);
+				self.owner_process_identifier.store(owner_process_identifier, Release)
+			}
+		}
+
+		claimed
+	}
+
+	#[inline(always)]
+	fn unclaim(&self)
+	{
+		#[cfg(feature = "shared-memory")] self.owner_process_identifier.store(0, Release);
+
+		self.claimed.store(false, Release)
+	}
+
+	#[inline(always)]
+	fn is_claimed(&self) -> bool
+	{
+		self.claimed.load(Acquire)
+	}
+
+	/// The process identifier of the process which has claimed this slot, if any, and when that process started (`0` if unknown).
+	#[cfg(feature = "shared-memory")]
+	#[inline(always)]
+	fn owner_process(&self) -> Option<(usize, usize)>
+	{
+		if self.claimed.load(Acquire)
+		{
+			match self.owner_process_identifier.load(Acquire)
+			{
+				0 => None,
+				owner_process_identifier => Some((owner_process_identifier, self.owner_process_start_time.load(Relaxed))),
+			}
+		}
+		else
+		{
+			None
+		}
+	}
+
+	#[cfg(feature = "shared-memory")]
+	#[inline(always)]
+	fn quarantine(&self)
+	{
+		self.quarantined.store(true, Release)
+	}
+}
@@ -0,0 +1,48 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Writes bytes into a ring buffer with `io::Write`; created by `RingBufferProducer::writer()`.
+///
+/// Each write acquires as much contiguous space as it can, up to the length written, and copies into it.
+/// What has been written is only produced (published) by `flush()`, when this is dropped, or, to free a reservation, when a write would otherwise exceed the producer's maximum number of reservations; until then, it holds back everything acquired after it by other producers.
+///
+/// A write which can not acquire even one byte fails with an `io::Error` of kind `io::ErrorKind::WouldBlock`.
+#[derive(Debug)]
+pub struct RingBufferProducerWriter<'a>
+{
+	producer: &'a RingBufferProducer<u8>,
+
+	/// Written but not yet produced.
+	pending: Vec<RingBufferProducerGuard<'a, u8>>,
+}
+
+impl<'a> Write for RingBufferProducerWriter<'a>
+{
+	#[inline(always)]
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize>
+	{
+		if buf.is_empty()
+		{
+			return Ok(0)
+		}
+
+		if self.pending.len() == self.producer.maximum_reservations()
+		{
+			self.pending.clear()
+		}
+
+		let mut slice_guard = self.producer.acquire_up_to(buf.len())?;
+		let count = slice_guard.len();
+		slice_guard.copy_from_slice(&buf[.. count]);
+		self.pending.push(slice_guard);
+		Ok(count)
+	}
+
+	#[inline(always)]
+	fn flush(&mut self) -> io::Result<()>
+	{
+		self.pending.clear();
+		Ok(())
+	}
+}
@@ -0,0 +1,81 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// The start of the memory of a ring buffer, followed by its `RingBufferProducerSlot`s, its `RingBufferProducerInner`s and then its buffer.
+///
+/// Contains no pointers, so that the memory is position-independent and can be mapped at a different address in each process sharing it; every offset is computed from the counts here.
+#[derive(Debug)]
+#[repr(C)]
+struct RingBufferSharedHeader
+{
+	/// Always first, so that attaching code can check that it understands the rest of the memory before reading it.
+	format: RingBufferFormat,
+
+	/// The `NEXT` hand is atomically updated by the producer.
+	/// `WrapLockBit` is set in case of wrap-around; in such a case the producer can update the `end` offset.
+	next: AtomicRingBufferOffset,
+	end: AtomicRingBufferOffset,
+
+	/// Updated by consumer.
+	written: AtomicRingBufferOffset,
+
+	/// Set whilst a `RingBufferConsumer` exists.
+	consumer_claimed: AtomicBool,
+}
+
+impl RingBufferSharedHeader
+{
+	#[inline(always)]
+	fn new<T>(capacity: usize, number_of_producers: usize, number_of_producer_reservations: usize) -> Self
+	{
+		Self
+		{
+			format: RingBufferFormat::new::<T>(capacity, number_of_producers, number_of_producer_reservations),
+			next: AtomicRingBufferOffset::new(0),
+			end: AtomicRingBufferOffset::new(RingBufferInnerHeader::<()>::MaximumOffset),
+			written: AtomicRingBufferOffset::new(0),
+			consumer_claimed: AtomicBool::new(false),
+		}
+	}
+
+	/// The `RingBufferProducerSlot`s immediately follow the header.
+	#[inline(always)]
+	fn producer_slots_offset() -> usize
+	{
+		size_of::<Self>()
+	}
+
+	/// The `RingBufferProducerInner`s immediately follow the `RingBufferProducerSlot`s.
+	#[inline(always)]
+	fn producers_offset_for(number_of_producers: usize) -> usize
+	{
+		Self::producer_slots_offset() + number_of_producers * size_of::<RingBufferProducerSlot>()
+	}
+
+	/// The buffer follows the `RingBufferProducerInner`s, rounded up to the alignment of an item.
+	#[inline(always)]
+	fn buffer_offset_for(producers_offset: usize, number_of_producer_reservations: usize, item_alignment: usize) -> usize
+	{
+		round_up_to_alignment(producers_offset + number_of_producer_reservations * size_of::<RingBufferProducerInner>(), item_alignment)
+	}
+
+	/// Tries to become the one consumer.
+	#[inline(always)]
+	fn claim_consumer(&self) -> bool
+	{
+		!self.consumer_claimed.swap(true, AcqRel)
+	}
+
+	#[inline(always)]
+	fn unclaim_consumer(&self)
+	{
+		self.consumer_claimed.store(false, Release)
+	}
+}
+
+#[inline(always)]
+fn round_up_to_alignment(value: usize, alignment: usize) -> usize
+{
+	value.div_ceil(alignment) * alignment
+}
@@ -0,0 +1,30 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// A type, usually an enum of several kinds of message, whose values are sent through a `MessageRingBuffer` using only the space of their own variant.
+///
+/// Derive it with `#[derive(RingMessage)]` (with the `derive` feature) on an enum: each variant is given a `RingMessageTag` in order of declaration, starting at `0`, and its fields are moved in and out as a tuple.
+///
+/// # Safety
+///
+/// `Self::read_payload()` must move out exactly what `Self::write_payload()` moved in for the same tag.
+pub unsafe trait RingMessage: Sized
+{
+	/// The tag of the variant of `self` and the size of its payload in bytes.
+	fn tag_and_size(&self) -> (RingMessageTag, usize);
+
+	/// Moves the payload of `self` into `payload`.
+	///
+	/// # Safety
+	///
+	/// `payload` must be valid for writes of the size returned by `self.tag_and_size()`; it need only be aligned to `ByteRingBuffer::RecordAlignment`.
+	unsafe fn write_payload(self, payload: *mut u8);
+
+	/// Moves a value out of `payload`.
+	///
+	/// # Safety
+	///
+	/// `payload` must have been written by `Self::write_payload()` for a value whose tag is `tag`, and not moved out of since.
+	unsafe fn read_payload(tag: RingMessageTag, payload: *const u8) -> Self;
+}
@@ -0,0 +1,318 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// A ring buffer in memory which can be shared between processes, for plain-old-data items (see `PlainOldData`).
+///
+/// One process creates the ring buffer, either in an anonymous `memfd` (whose file descriptor is then passed to other processes, eg by inheritance across `fork()` or over a Unix domain socket) or in a named POSIX shared memory object.
+/// Other processes attach to it, and each process claims producers and, in one process, the consumer.
+///
+/// The memory contains no pointers (see `RingBufferSharedHeader`), so it can be mapped at a different address in each process.
+///
+/// A buggy or hostile producer process can write anything into the memory; the consumer is hardened (see `Self::quarantined_producers()` and `Self::corrupt_hands()`) so that it never accesses memory outside the buffer, but the items themselves can still be garbage (which `PlainOldData` requires to be valid values of `T`).
+///
+/// Unmapped when this and every consumer, producer, reservation and batch claimed from it have been dropped.
+#[derive(Debug)]
+pub struct SharedMemoryRingBuffer<T: PlainOldData>
+{
+	ring_buffer: RingBuffer<T>,
+	file_descriptor: RawFd,
+}
+
+impl<T: PlainOldData> Drop for SharedMemoryRingBuffer<T>
+{
+	#[inline(always)]
+	fn drop(&mut self)
+	{
+		unsafe { close(self.file_descriptor) };
+	}
+}
+
+impl<T: PlainOldData> SharedMemoryRingBuffer<T>
+{
+	/// Creates a ring buffer in an anonymous `memfd`; `name` is only used for debugging (eg it is shown in `/proc/self/fd`).
+	///
+	/// The file descriptor is close-on-exec; to pass it to a program executed by a child process, clear `FD_CLOEXEC` on a duplicate of it.
+	///
+	/// `maximum_reservations_per_producer` is as for `RingBuffer::new_with_maximum_reservations()`.
+	#[cfg(target_os = "linux")]
+	#[inline(always)]
+	pub fn create_anonymous(name: &CStr, capacity: usize, maximum_reservations_per_producer: &[usize]) -> io::Result<Self>
+	{
+		let file_descriptor = unsafe { memfd_create(name.as_ptr(), MFD_CLOEXEC) };
+		Self::create(file_descriptor, capacity, maximum_reservations_per_producer)
+	}
+
+	/// Creates a ring buffer in a new named POSIX shared memory object (with `shm_open()`); `path` should start with a `/` and contain no other `/`.
+	///
+	/// Fails if the object already exists; use `Self::unlink()` to remove it once every process has attached.
+	#[inline(always)]
+	pub fn create_named(path: &CStr, capacity: usize, maximum_reservations_per_producer: &[usize]) -> io::Result<Self>
+	{
+		let file_descriptor = unsafe { shm_open(path.as_ptr(), O_RDWR | O_CREAT | O_EXCL | O_CLOEXEC, 0o600) };
+		Self::create(file_descriptor, capacity, maximum_reservations_per_producer)
+	}
+
+	/// Attaches to a ring buffer created by `Self::create_anonymous()` or `Self::create_named()`, usually in another process.
+	///
+	/// `file_descriptor` is duplicated, so the caller remains responsible for closing it.
+	///
+	/// Fails with an `io::Error` of kind `io::ErrorKind::InvalidData` containing a `RingBufferFormatError` if the memory is not a ring buffer of `T` in the format, and with the layout, this process uses.
+	#[inline(always)]
+	pub fn attach_to_file_descriptor(file_descriptor: RawFd) -> io::Result<Self>
+	{
+		let file_descriptor = unsafe { fcntl(file_descriptor, F_DUPFD_CLOEXEC, 0) };
+		Self::attach(file_descriptor)
+	}
+
+	/// Attaches to a ring buffer created by `Self::create_named()`, usually in another process.
+	///
+	/// Fails as for `Self::attach_to_file_descriptor()`.
+	#[inline(always)]
+	pub fn attach_to_path(path: &CStr) -> io::Result<Self>
+	{
+		let file_descriptor = unsafe { shm_open(path.as_ptr(), O_RDWR | O_CLOEXEC, 0) };
+		Self::attach(file_descriptor)
+	}
+
+	/// Removes a named POSIX shared memory object; processes which have attached to it are unaffected.
+	#[inline(always)]
+	pub fn unlink(path: &CStr) -> io::Result<()>
+	{
+		if unsafe { shm_unlink(path.as_ptr()) } == 0
+		{
+			Ok(())
+		}
+		else
+		{
+			Err(io::Error::last_os_error())
+		}
+	}
+
+	/// The file descriptor of the shared memory, which can be passed to other processes.
+	#[inline(always)]
+	pub fn file_descriptor(&self) -> RawFd
+	{
+		self.file_descriptor
+	}
+
+	/// The number of producers (whether claimed or not).
+	#[inline(always)]
+	pub fn number_of_producers(&self) -> usize
+	{
+		self.ring_buffer.reference().number_of_producers
+	}
+
+	/// Claims the consumer; there can be only one, in any process.
+	///
+	/// Returns `None` if the consumer is already claimed; it is unclaimed when the consumer is dropped.
+	#[inline(always)]
+	pub fn claim_consumer(&self) -> Option<RingBufferConsumer<T>>
+	{
+		self.ring_buffer.claim_consumer()
+	}
+
+	/// Claims the first producer which is not already claimed, in any process.
+	///
+	/// Returns `None` if every producer is already claimed; a producer is unclaimed when it is dropped.
+	#[inline(always)]
+	pub fn claim_producer(&self) -> Option<RingBufferProducer<T>>
+	{
+		(0 .. self.number_of_producers()).filter_map(|producer_index| self.claim_producer_at(producer_index)).next()
+	}
+
+	/// Claims the producer at `producer_index`.
+	///
+	/// Returns `None` if it is already claimed, if it has been quarantined (see `Self::quarantined_producers()`) or if `producer_index` equals or exceeds `self.number_of_producers()`.
+	#[inline(always)]
+	pub fn claim_producer_at(&self, producer_index: usize) -> Option<RingBufferProducer<T>>
+	{
+		if producer_index >= self.number_of_producers()
+		{
+			return None
+		}
+		self.ring_buffer.claim_producer(producer_index)
+	}
+
+	/// The producers which the consumer in this process has quarantined because they published impossible offsets; the consumer ignores their offsets.
+	///
+	/// Quarantined producers can not be claimed again, in any process (unless a hostile process clears the advisory flag in the memory; this process's consumer still ignores them).
+	#[inline(always)]
+	pub fn quarantined_producers(&self) -> Vec<usize>
+	{
+		self.hardening().quarantined_producers()
+	}
+
+	/// The number of times the consumer in this process found the hands in the memory (other than those of producers) to be impossible, and so consumed nothing.
+	#[inline(always)]
+	pub fn corrupt_hands(&self) -> usize
+	{
+		self.hardening().corrupt_hands()
+	}
+
+	/// Frees the producers claimed by processes which have since died (eg crashed), so that they can be claimed again, returning their indices.
+	///
+	/// A reservation which such a producer had acquired, but not produced, would otherwise stop `ring_buffer_consumer` from ever advancing past it; instead, its range is poisoned and `ring_buffer_consumer` releases it without consuming it.
+	/// If the process died part way through acquiring the reservation (before its range was recorded), the range is consumed as whatever it contains.
+	/// If the process died whilst the `seen` offset of its reservation was locked (in the middle of `RingBufferProducer::acquire()`), `ring_buffer_consumer` gives up waiting for it after a fraction of a second, reporting the reservation as `ConsumeStatus::BlockedByProducer`, so it can still be reaped.
+	/// If, however, it died whilst holding the lock of the `next` hand (whilst wrapping-around to the start of the buffer), the ring buffer can not be recovered: `ring_buffer_consumer` consumes nothing from then on (reporting `ConsumeStatus::Empty`), and producers in every process spin forever in `RingBufferProducer::acquire()`.
+	///
+	/// A process is considered to have died once its process identifier no longer exists, or (on Linux) once it has been reused by a process which started at a different time than the one which claimed the producer; a process which exited but has not been waited for by its parent still exists.
+	///
+	/// Process identifiers are only meaningful within one PID namespace, so every process using the ring buffer must be in the same PID namespace as this one (eg not in different containers); otherwise, a producer might be reaped whilst its process is alive, or never be reaped.
+	///
+	/// Must be called in the process which claimed `ring_buffer_consumer`, as only it can poison ranges; panics if `ring_buffer_consumer` was not claimed from this ring buffer.
+	#[inline(always)]
+	pub fn reap_dead_producers(&self, ring_buffer_consumer: &RingBufferConsumer<T>) -> Vec<usize>
+	{
+		assert_eq!(ring_buffer_consumer.0.ring_buffer_inner_non_null, self.ring_buffer.ring_buffer_inner_non_null, "ring_buffer_consumer was not claimed from this ring buffer");
+
+		let this_process_identifier = process_identifier() as usize;
+		self.ring_buffer.reference().reap_dead_producers(|owner_process_identifier, owner_process_start_time| owner_process_identifier != this_process_identifier && Self::process_has_died(owner_process_identifier, owner_process_start_time))
+	}
+
+	#[inline(always)]
+	fn process_has_died(owner_process_identifier: usize, owner_process_start_time: usize) -> bool
+	{
+		let result = unsafe { kill(owner_process_identifier as pid_t, 0) };
+		if result == -1 && io::Error::last_os_error().raw_os_error() == Some(ESRCH)
+		{
+			return true
+		}
+
+		// A process with the identifier exists, but it might have been reused; if either start time is unknown, the process is assumed to be the same one.
+		let current_process_start_time = process_start_time(owner_process_identifier);
+		owner_process_start_time != 0 && current_process_start_time != 0 && current_process_start_time != owner_process_start_time
+	}
+
+	#[inline(always)]
+	fn hardening(&self) -> &ConsumerHardening
+	{
+		self.ring_buffer.reference().hardening.as_ref().unwrap()
+	}
+
+	#[inline(always)]
+	fn create(file_descriptor: RawFd, capacity: usize, maximum_reservations_per_producer: &[usize]) -> io::Result<Self>
+	{
+		assert!(maximum_reservations_per_producer.iter().all(|&maximum_reservations| maximum_reservations != 0), "maximum_reservations_per_producer can not contain zero");
+
+		let file_descriptor = Self::file_descriptor_or_error(file_descriptor)?;
+		let shared_header = RingBufferInner::<T>::shared_header(capacity, maximum_reservations_per_producer);
+		let format = shared_header.format;
+		let length = format.size();
+
+		if unsafe { ftruncate(file_descriptor, length as off_t) } != 0
+		{
+			return Self::close_on_error(file_descriptor, io::Error::last_os_error())
+		}
+
+		let memory = match Self::map(file_descriptor, length)
+		{
+			Err(error) => return Self::close_on_error(file_descriptor, error),
+			Ok(memory) => memory,
+		};
+
+		unsafe
+		{
+			RingBufferInner::<T>::initialize(memory, shared_header, maximum_reservations_per_producer);
+			Ok(Self::new(memory, length, format, file_descriptor))
+		}
+	}
+
+	#[inline(always)]
+	fn attach(file_descriptor: RawFd) -> io::Result<Self>
+	{
+		let file_descriptor = Self::file_descriptor_or_error(file_descriptor)?;
+
+		let length =
+		{
+			let mut stat = unsafe { zeroed::<stat>() };
+			if unsafe { fstat(file_descriptor, &mut stat) } != 0
+			{
+				return Self::close_on_error(file_descriptor, io::Error::last_os_error())
+			}
+			stat.st_size as usize
+		};
+
+		if length < size_of::<RingBufferSharedHeader>()
+		{
+			return Self::close_on_error(file_descriptor, RingBufferFormatError::TooSmall { length, minimum_length: size_of::<RingBufferSharedHeader>() }.into())
+		}
+
+		let memory = match Self::map(file_descriptor, length)
+		{
+			Err(error) => return Self::close_on_error(file_descriptor, error),
+			Ok(memory) => memory,
+		};
+
+		// Another process could change the format at any time, so it is read once and only that snapshot is validated and used.
+		let format = unsafe { RingBufferFormat::snapshot(memory) };
+		if let Err(error) = Self::validate(memory, length, &format)
+		{
+			unsafe { munmap(memory.as_ptr() as *mut c_void, length) };
+			return Self::close_on_error(file_descriptor, error.into())
+		}
+
+		Ok(unsafe { Self::new(memory, length, format, file_descriptor) })
+	}
+
+	/// Checks that `format`, a snapshot of that of the memory, is of a ring buffer of `T`, in the format this process uses, which is exactly `length` bytes, so that offsets computed from it are within the memory.
+	#[inline(always)]
+	fn validate(memory: NonNull<u8>, length: usize, format: &RingBufferFormat) -> Result<(), RingBufferFormatError>
+	{
+		format.validate::<T>(length)?;
+
+		let expected_length = format.size();
+		if expected_length != length
+		{
+			return Err(RingBufferFormatError::DifferentLength { length, expected_length })
+		}
+
+		let first_ring_buffer_producer_slot = unsafe { NonNull::new_unchecked(memory.as_ptr().add(RingBufferSharedHeader::producer_slots_offset()) as *mut RingBufferProducerSlot) };
+		ConsumerHardening::validate_producer_slots(first_ring_buffer_producer_slot, format.number_of_producers(), format.number_of_producer_reservations()).map_err(|producer_index| RingBufferFormatError::ImpossibleProducerSlot { producer_index })
+	}
+
+	#[inline(always)]
+	unsafe fn new(memory: NonNull<u8>, length: usize, format: RingBufferFormat, file_descriptor: RawFd) -> Self
+	{
+		Self
+		{
+			ring_buffer: RingBuffer::from_inner(RingBufferInner::view(memory, format, RingBufferMemory::SharedMemory { length }, false)),
+			file_descriptor,
+		}
+	}
+
+	#[inline(always)]
+	fn map(file_descriptor: RawFd, length: usize) -> io::Result<NonNull<u8>>
+	{
+		let memory = unsafe { mmap(null_mut(), length, PROT_READ | PROT_WRITE, MAP_SHARED, file_descriptor, 0) };
+		if memory == MAP_FAILED
+		{
+			Err(io::Error::last_os_error())
+		}
+		else
+		{
+			Ok(unsafe { NonNull::new_unchecked(memory as *mut u8) })
+		}
+	}
+
+	#[inline(always)]
+	fn file_descriptor_or_error(file_descriptor: RawFd) -> io::Result<RawFd>
+	{
+		if file_descriptor == -1
+		{
+			Err(io::Error::last_os_error())
+		}
+		else
+		{
+			Ok(file_descriptor)
+		}
+	}
+
+	#[inline(always)]
+	fn close_on_error(file_descriptor: RawFd, error: io::Error) -> io::Result<Self>
+	{
+		unsafe { close(file_descriptor) };
+		Err(error)
+	}
+}
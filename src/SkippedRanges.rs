@@ -0,0 +1,101 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Ranges which the consumer releases without consuming once they are ready in order: those already consumed out of order, and those poisoned by `RingBufferInner::reap_dead_producers()`.
+///
+/// Their space is only released back to producers once everything before it has been released, so that the `written` offset only advances over a contiguous prefix.
+///
+/// Only used by the consumer; it is process-local, and the producers never touch it.
+/// However, an `OwnedBatch` can be released on another thread whilst the consumer's thread reaps dead producers (see `RingBufferInner::reap_dead_producers()`), so the ranges are behind a `Mutex`.
+/// Their count is kept in an atomic too, so that checking there are none, which the consumer does on every consume and release, does not lock.
+#[derive(Debug)]
+struct SkippedRanges
+{
+	ranges: Mutex<Vec<(RingBufferOffset, usize)>>,
+
+	count: AtomicUsize,
+}
+
+impl SkippedRanges
+{
+	#[inline(always)]
+	fn new() -> Self
+	{
+		Self
+		{
+			ranges: Mutex::new(Vec::new()),
+			count: AtomicUsize::new(0),
+		}
+	}
+
+	#[inline(always)]
+	fn is_empty(&self) -> bool
+	{
+		self.count.load(Acquire) == 0
+	}
+
+	#[inline(always)]
+	fn skip(&self, offset: RingBufferOffset, count: usize)
+	{
+		if count != 0
+		{
+			let mut ranges = self.ranges();
+			ranges.push((offset, count));
+			self.count.store(ranges.len(), Release)
+		}
+	}
+
+	/// The count of items from `offset` which are skipped, following ranges which start where the previous one ends (as those of a range consumed out of order in several releases do).
+	#[inline(always)]
+	fn skipped_from(&self, offset: RingBufferOffset) -> usize
+	{
+		let skipped_ranges = self.ranges();
+		let mut skipped_to = offset;
+		while let Some(&(_, skipped_count)) = skipped_ranges.iter().find(|&&(skipped_offset, _)| skipped_offset == skipped_to)
+		{
+			skipped_to += skipped_count;
+		}
+		skipped_to - offset
+	}
+
+	/// Cuts the segments returned by `RingBufferInnerHeader::consume_segments_in_order()` short of any skipped range.
+	///
+	/// Returns `Err(count)` if a skipped range of `count` items starts at `offset` and is ready; the consumer should release it without consuming it, then try again.
+	#[inline(always)]
+	fn cut_short(&self, mut to_write: usize, offset: RingBufferOffset, mut wrapped_to_write: usize) -> Result<(usize, usize, usize), usize>
+	{
+		let mut skipped_ranges = self.ranges();
+
+		for index in 0 .. skipped_ranges.len()
+		{
+			let (skipped_offset, skipped_count) = skipped_ranges[index];
+
+			if skipped_offset == offset && skipped_count <= to_write
+			{
+				skipped_ranges.swap_remove(index);
+				self.count.store(skipped_ranges.len(), Release);
+				return Err(skipped_count)
+			}
+
+			if skipped_offset >= offset && skipped_offset < offset + to_write
+			{
+				to_write = skipped_offset - offset;
+				wrapped_to_write = 0;
+			}
+
+			if skipped_offset < wrapped_to_write
+			{
+				wrapped_to_write = skipped_offset;
+			}
+		}
+
+		Ok((to_write, offset, wrapped_to_write))
+	}
+
+	#[inline(always)]
+	fn ranges<'a>(&'a self) -> MutexGuard<'a, Vec<(RingBufferOffset, usize)>>
+	{
+		self.ranges.lock().unwrap()
+	}
+}
@@ -0,0 +1,76 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// The ownership of a slot in the ring buffer, tracked by the `paranoid` feature.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum SlotState
+{
+	/// Can be acquired by a producer.
+	Free,
+
+	/// Acquired by the producer reservation with this index, but not yet produced.
+	Reserved(usize),
+
+	/// Produced, but not yet passed to the consumer.
+	Ready,
+
+	/// Passed to the consumer (perhaps more than once, if not released), but not yet released.
+	Consumed,
+}
+
+impl Display for SlotState
+{
+	#[inline(always)]
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result
+	{
+		match *self
+		{
+			SlotState::Free => write!(f, "free"),
+			SlotState::Reserved(producer_reservation_index) => write!(f, "reserved by producer reservation {}", producer_reservation_index),
+			SlotState::Ready => write!(f, "ready"),
+			SlotState::Consumed => write!(f, "consumed"),
+		}
+	}
+}
+
+impl From<usize> for SlotState
+{
+	#[inline(always)]
+	fn from(value: usize) -> Self
+	{
+		match value
+		{
+			Self::FreeValue => SlotState::Free,
+			Self::ReadyValue => SlotState::Ready,
+			Self::ConsumedValue => SlotState::Consumed,
+			_ => SlotState::Reserved(value - Self::FirstReservedValue),
+		}
+	}
+}
+
+impl From<SlotState> for usize
+{
+	#[inline(always)]
+	fn from(slot_state: SlotState) -> Self
+	{
+		match slot_state
+		{
+			SlotState::Free => SlotState::FreeValue,
+			SlotState::Reserved(producer_reservation_index) => SlotState::FirstReservedValue + producer_reservation_index,
+			SlotState::Ready => SlotState::ReadyValue,
+			SlotState::Consumed => SlotState::ConsumedValue,
+		}
+	}
+}
+
+impl SlotState
+{
+	const FreeValue: usize = 0;
+
+	const ReadyValue: usize = 1;
+
+	const ConsumedValue: usize = 2;
+
+	const FirstReservedValue: usize = 3;
+}
@@ -0,0 +1,121 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// The `SlotState` of every slot in the ring buffer, tracked by the `paranoid` feature.
+///
+/// This does not implement `Drop`, as the `RingBufferInnerHeader` which holds it is never dropped in place; instead, `free()` is called before the ring buffer is deallocated.
+#[derive(Debug)]
+struct SlotStates
+{
+	slot_states: NonNull<AtomicUsize>,
+	capacity: usize,
+}
+
+impl SlotStates
+{
+	#[inline(always)]
+	fn new(capacity: usize) -> Self
+	{
+		let slot_states: Box<[AtomicUsize]> = (0 .. capacity).map(|_| AtomicUsize::new(SlotState::Free.into())).collect::<Vec<_>>().into_boxed_slice();
+
+		Self
+		{
+			slot_states: unsafe { NonNull::new_unchecked(Box::into_raw(slot_states) as *mut AtomicUsize) },
+			capacity,
+		}
+	}
+
+	/// Slots are not tracked if the capacity is zero (as it is for memory shared with other processes).
+	#[inline(always)]
+	fn is_tracking(&self) -> bool
+	{
+		self.capacity != 0
+	}
+
+	/// Must only be called once.
+	#[inline(always)]
+	unsafe fn free(&self)
+	{
+		drop(Box::from_raw(slice_from_raw_parts_mut(self.slot_states.as_ptr(), self.capacity)))
+	}
+
+	/// Changes the state of each slot in `offset .. offset + count` which `from` accepts to `to`.
+	///
+	/// Stops at the first slot which `from` does not accept, returning its index and state.
+	#[inline(always)]
+	fn transition<F: Fn(SlotState) -> bool>(&self, offset: usize, count: usize, from: F, to: SlotState) -> Result<(), (usize, SlotState)>
+	{
+		for index in offset .. offset + count
+		{
+			let slot_state = self.slot_state(index);
+			let mut current = slot_state.load(SeqCst);
+			loop
+			{
+				if !from(SlotState::from(current))
+				{
+					return Err((index, SlotState::from(current)))
+				}
+
+				match slot_state.compare_exchange(current, to.into(), SeqCst, SeqCst)
+				{
+					Ok(_) => break,
+					Err(changed) => current = changed,
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Changes the state of every slot which is `from` to `to`, returning the number changed.
+	#[inline(always)]
+	fn transition_all(&self, from: SlotState, to: SlotState) -> usize
+	{
+		let mut changed = 0;
+		for index in 0 .. self.capacity
+		{
+			if self.slot_state(index).compare_exchange(from.into(), to.into(), SeqCst, SeqCst).is_ok()
+			{
+				changed += 1;
+			}
+		}
+		changed
+	}
+
+	/// Describes runs of slots with the same state, eg `0 .. 4 ready, 4 .. 10 free`.
+	#[inline(always)]
+	fn describe(&self) -> String
+	{
+		if !self.is_tracking()
+		{
+			return "not tracked".to_string()
+		}
+
+		let mut description = String::new();
+		let mut index = 0;
+		while index < self.capacity
+		{
+			let slot_state = SlotState::from(self.slot_state(index).load(SeqCst));
+			let start = index;
+			while index < self.capacity && SlotState::from(self.slot_state(index).load(SeqCst)) == slot_state
+			{
+				index += 1;
+			}
+
+			if start != 0
+			{
+				description.push_str(", ");
+			}
+			description.push_str(&format!("{} .. {} {}", start, index, slot_state));
+		}
+		description
+	}
+
+	#[inline(always)]
+	fn slot_state(&self, index: usize) -> &AtomicUsize
+	{
+		debug_assert!(index < self.capacity, "index '{}' equals or exceeds capacity '{}'", index, self.capacity);
+
+		unsafe { & * self.slot_states.as_ptr().add(index) }
+	}
+}
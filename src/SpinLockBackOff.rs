@@ -19,6 +19,7 @@ impl SpinLockBackOff
 	const Initial: Self = SpinLockBackOff(4);
 	
 	/// Exponential back-off for the spinning paths.
+	#[cfg(not(loom))]
 	#[inline(always)]
 	fn back_off(&mut self)
 	{
@@ -26,7 +27,7 @@ impl SpinLockBackOff
 		let mut i = original_count;
 		while i != 0
 		{
-			spin_loop_hint();
+			spin_loop();
 			i -= 1;
 		}
 		
@@ -36,4 +37,12 @@ impl SpinLockBackOff
 			self.0 += original_count;
 		}
 	}
+
+	/// When model checking with `loom`, spinning paths must yield so that other threads can make progress.
+	#[cfg(loom)]
+	#[inline(always)]
+	fn back_off(&mut self)
+	{
+		yield_now()
+	}
 }
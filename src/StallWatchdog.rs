@@ -0,0 +1,111 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// Called by `StallWatchdog` with the reservation which has been holding back items for too long.
+type StallWatchdogCallback = Box<dyn FnMut(&StalledProducer) + Send>;
+
+/// Times how long the consumer has observed a producer reservation holding back items, and calls a callback once it has done so for too long.
+///
+/// Only used by the consumer, which is on one thread at a time; it is process-local, and the producers never touch it.
+struct StallWatchdog
+{
+	/// The index of the producer reservation last observed holding back items, its offset, when it was first observed doing so and whether the callback has been called for it.
+	observation: Cell<Option<(usize, RingBufferOffset, Instant, bool)>>,
+
+	/// How long a reservation must be observed holding back items before `callback` is called.
+	threshold: Cell<Duration>,
+
+	callback: RefCell<Option<StallWatchdogCallback>>,
+}
+
+impl Debug for StallWatchdog
+{
+	#[inline(always)]
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result
+	{
+		f.debug_struct("StallWatchdog")
+			.field("observation", &self.observation.get())
+			.field("threshold", &self.threshold.get())
+			.field("callback", &self.is_set())
+			.finish()
+	}
+}
+
+impl StallWatchdog
+{
+	#[inline(always)]
+	fn new() -> Self
+	{
+		Self
+		{
+			observation: Cell::new(None),
+			threshold: Cell::new(Duration::from_secs(0)),
+			callback: RefCell::new(None),
+		}
+	}
+
+	#[inline(always)]
+	fn set(&self, threshold: Duration, callback: StallWatchdogCallback)
+	{
+		self.threshold.set(threshold);
+		*self.callback.borrow_mut() = Some(callback)
+	}
+
+	#[inline(always)]
+	fn clear(&self)
+	{
+		*self.callback.borrow_mut() = None
+	}
+
+	#[inline(always)]
+	fn is_set(&self) -> bool
+	{
+		self.callback.borrow().is_some()
+	}
+
+	/// Records that the producer reservation `producer_reservation_index`, at `offset`, is holding back items, returning for how long it has been observed doing so.
+	///
+	/// A reservation at a different offset is a different reservation, even if it is of the same producer reservation.
+	#[inline(always)]
+	fn observe(&self, producer_reservation_index: usize, offset: RingBufferOffset) -> Duration
+	{
+		let now = Instant::now();
+		match self.observation.get()
+		{
+			Some((observed_producer_reservation_index, observed_offset, since, _)) if observed_producer_reservation_index == producer_reservation_index && observed_offset == offset => now.duration_since(since),
+
+			_ =>
+			{
+				self.observation.set(Some((producer_reservation_index, offset, now, false)));
+				Duration::from_secs(0)
+			}
+		}
+	}
+
+	/// Records that no producer reservation is holding back items.
+	#[inline(always)]
+	fn forget(&self)
+	{
+		self.observation.set(None)
+	}
+
+	/// Calls the callback, if it is set and has not already been called for the reservation last observed, if `stalled_producer` has been stalled for at least the threshold.
+	#[inline(always)]
+	fn fire_if_exceeded(&self, stalled_producer: &StalledProducer)
+	{
+		if let Some(ref mut callback) = *self.callback.borrow_mut()
+		{
+			if stalled_producer.stalled_for < self.threshold.get()
+			{
+				return
+			}
+
+			if let Some((producer_reservation_index, offset, since, false)) = self.observation.get()
+			{
+				self.observation.set(Some((producer_reservation_index, offset, since, true)));
+				callback(stalled_producer)
+			}
+		}
+	}
+}
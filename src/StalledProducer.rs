@@ -0,0 +1,27 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// A reservation which has been acquired but not yet produced, and which is holding back the items after it from being consumed.
+///
+/// Returned by `RingBufferConsumer::stalled_producer()` and passed to the callback of `RingBufferConsumer::set_stall_watchdog()`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StalledProducer
+{
+	/// The index of the producer (its index in the `Vec` returned by `RingBuffer::new()`, or as passed to `SharedMemoryRingBuffer::claim_producer_at()`).
+	pub producer_index: usize,
+
+	/// Which of the producer's reservations it is, counting from zero.
+	pub reservation_index: usize,
+
+	/// The offset of the reservation in the buffer.
+	pub offset: usize,
+
+	/// The count of items, including those of the reservation itself, from its offset up to the end of the most recent reservation of any producer; none of these can be consumed until it is produced.
+	pub items_held_back: usize,
+
+	/// How long the consumer has observed the reservation holding back items.
+	///
+	/// Observations are only made by `RingBufferConsumer::stalled_producer()` and, whilst a watchdog is set, when consuming; the reservation might have been holding back items for longer.
+	pub stalled_for: Duration,
+}
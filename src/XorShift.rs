@@ -0,0 +1,27 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// A xorshift64* pseudo-random number generator.
+pub(crate) struct XorShift(u64);
+
+impl XorShift
+{
+	/// Any seed, including zero, is valid.
+	#[inline(always)]
+	pub(crate) fn new(seed: u64) -> Self
+	{
+		// A xorshift generator can not have a state of zero.
+		let state = seed ^ 0x9E3779B97F4A7C15;
+		XorShift(if state == 0 { 0x9E3779B97F4A7C15 } else { state })
+	}
+
+	#[inline(always)]
+	pub(crate) fn next(&mut self) -> u64
+	{
+		self.0 ^= self.0 >> 12;
+		self.0 ^= self.0 << 25;
+		self.0 ^= self.0 >> 27;
+		self.0.wrapping_mul(0x2545F4914F6CDD1D)
+	}
+}
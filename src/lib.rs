@@ -3,8 +3,10 @@
 
 
 #![allow(non_upper_case_globals)]
+#![allow(internal_features)]
+#![allow(clippy::new_ret_no_self, clippy::result_unit_err)]
 #![deny(missing_docs)]
-#![feature(allocator_api, core_intrinsics)]
+#![feature(core_intrinsics)]
 
 
 //! # lock-free-multi-producer-single-consumer-ring-buffer
@@ -12,33 +14,33 @@
 //! ## Usage
 //!
 //! ```
-//! extern crate lock_free_multi_produce_single_consume_ring_buffer;
+//! extern crate lock_free_multi_producer_single_consumer_ring_buffer;
 //!
-//! use ::lock_free_multi_produce_single_consume_ring_buffer::*;
+//! use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
 //!
-//! let (ring_buffer_consumer, ring_buffer_producers) = RingBuffer::new(capacity, number_of_producers);
+//! # let (capacity, number_of_producers, length, some_value) = (64, 1, 1, 42);
+//! let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::new(capacity, number_of_producers);
 //!
 //! // For each producer thread.
-//! let ring_buffer_producer = ring_buffer_producers.get(0);
+//! let ring_buffer_producer = &ring_buffer_producers[0];
 //!
 //! let result = ring_buffer_producer.acquire(length);
-//! // result is `None` if length was too much; try a shorter length.
+//! // result is `Err` if length was too much; try a shorter length.
 //!
-//! let slice_guard = result.unwrap();
+//! let mut slice_guard = result.unwrap();
 //!
 //! // Dereferences to a slice.
-//! // slice_guard[0] = some_value;
+//! slice_guard[0] = some_value;
 //!
 //! // Produce (relinquishes the slice).
 //! drop(slice_guard);
 //!
-//! ring_buffer_producer.produce();
-//!
 //! // For each consumer thread.
-//! let slice_guard = ring_buffer_consumer.consume();
+//! let mut slice_guard = ring_buffer_consumer.consume();
 //!
 //! // Iterate, move out, etc.
-//! println!("should be `some_value`", slice_guard.move_out(slice_guard.len())[0]);
+//! let length = slice_guard.len();
+//! println!("should be `some_value`: {}", slice_guard.move_out(length)[0]);
 //!
 //! // Releases the slice so producers can now use it.
 //! drop(slice_guard);
@@ -46,6 +48,44 @@
 //!
 //! Once all the producers and the consumer are dropped then the memory underlying the ring buffer is freed and any unconsumed items in it are safely `Drop`ped.
 //!
+//! A producer which is slow to produce a reservation holds back every item acquired after it by every other producer; `RingBufferConsumer::consume_detailed()` reports when one is why nothing was consumed, `RingBufferConsumer::stalled_producer()` reports which, and `RingBufferConsumer::set_stall_watchdog()` calls a callback once it has done so for too long.
+//! Alternatively, `RingBuffer::new_with_out_of_order_completion()` creates a ring buffer whose consumer is given reservations which have been produced before earlier ones which have not, whilst still consuming the items of each producer in order; their space is only given back to producers once everything before it has been released.
+//!
+//! For variable-length messages, `ByteRingBuffer` stores records of bytes, each with a length header and aligned to `ByteRingBuffer::RecordAlignment`, as the original C code does; the consumer only ever sees whole records.
+//! For a stream of bytes, `RingBufferProducer::writer()` and `RingBufferConsumer::reader()` adapt a `RingBuffer<u8>` to `io::Write` and to `io::Read` and `io::BufRead`.
+//! To avoid the copies, `RingBufferConsumer::write_to()` writes everything ready, across a wrap-around, with one vectored write, and `RingBufferProducer::read_from()` reads directly into a reservation and produces only the bytes read.
+//! For several kinds of message, `MessageRingBuffer` stores each value of a `RingMessage` enum as a record tagged with its variant, using only the space of that variant rather than that of the largest; `#[derive(RingMessage)]` implements it.
+//!
+//!
+//! ## Features
+//!
+//! * `paranoid`: checks invariants in release builds, too, and tracks the ownership of every slot (free, reserved by a producer reservation, ready or consumed) to detect double releases and overlapping reservations; panics with a dump of all the hands if an invariant is violated.
+//!   This is slow; producing a reservation scans every slot.
+//! * `fault-injection`: injects spurious compare-and-exchange failures, yields and sleeps, drawn from a seeded pseudo-random sequence, into the threads it is enabled on; see `FaultInjection`.
+//!   Tests can also use a hook at each `FaultInjectionPoint` to force particular interleavings.
+//! * `shared-memory`: adds `SharedMemoryRingBuffer`, a ring buffer of `PlainOldData` items in POSIX shared memory (`shm_open()`, or `memfd_create()` on Linux) with producers and the consumer in different processes.
+//!   The memory contains only offsets, never pointers, so each process can map it at a different address; the consumer and each producer can be claimed by only one process at a time.
+//!   It starts with a versioned description of itself (magic number, format version, offset width and layout, item size and alignment, capacity, producer counts and a layout checksum), which is validated when attaching; see `RingBufferFormatError`.
+//!   The consumer does not trust anything another process can write: it bounds-checks every hand, keeps its own copy of the `written` offset and quarantines a producer which publishes an impossible offset, so that it never accesses memory outside the buffer.
+//!   `SharedMemoryRingBuffer::reap_dead_producers()` frees the producers of processes which have died, releasing any reservation they had acquired but not produced without consuming it.
+//! * `derive`: re-exports `#[derive(RingMessage)]` from the `lock-free-multi-producer-single-consumer-ring-buffer-derive` crate.
+//!
+//!
+//! ## Verification
+//!
+//! The arithmetic on the `next` offset, which packs an offset, a wrap-around counter and a lock bit into one word, is proved for the layouts of all three pointer widths (16-bit, 32-bit and 64-bit) by Kani proof harnesses; run them with `cargo kani`.
+//!
+//! The tests in `tests/miri.rs` exercise every way of getting at the memory of the ring buffer and are small enough to run under Miri, with both Stacked Borrows and Tree Borrows: `cargo +nightly miri test --test miri` and `MIRIFLAGS=-Zmiri-tree-borrows cargo +nightly miri test --test miri`.
+//! Every hand is an atomic with `Acquire` and `Release` orderings, so Miri's data race detector also checks the tests which use several threads.
+//!
+//!
+//! ## Benchmarks
+//!
+//! The Criterion benchmarks compare this ring buffer with `std::sync::mpsc::sync_channel()`, `crossbeam_queue::ArrayQueue`, `crossbeam_channel::bounded()` and a `Mutex<VecDeque>` baseline.
+//!
+//! * `cargo bench --bench throughput`: single items; bursts of 1, 8 and 64 items of 8, 64 and 256 bytes; and 1 to 32 producers.
+//! * `cargo bench --bench latency`: one-way latency, unloaded and whilst other producers load the queue; the percentiles are printed as well.
+//!
 //!
 //! ## The following documentation is originally "Copyright (c) 2016-2017 Mindaugas Rasiukevicius <rmind at noxt eu>".
 //!
@@ -94,44 +134,160 @@
 
 
 #[macro_use] extern crate likely;
+#[cfg(kani)] extern crate kani;
+#[cfg(feature = "shared-memory")] extern crate libc;
+#[cfg(feature = "derive")] extern crate lock_free_multi_producer_single_consumer_ring_buffer_derive;
+#[cfg(loom)] extern crate loom;
 
 
-use ::std::alloc::Alloc;
-use ::std::alloc::Global;
+use ::std::alloc::alloc;
+use ::std::alloc::dealloc;
+use ::std::alloc::handle_alloc_error;
 use ::std::alloc::Layout;
 use ::std::cell::Cell;
-use ::std::cell::UnsafeCell;
+use ::std::cell::RefCell;
 use ::std::cmp::min;
 use ::std::cmp::max;
-use ::std::intrinsics::atomic_cxchgweak;
+#[cfg(feature = "shared-memory")] use ::std::error::Error;
+#[cfg(feature = "shared-memory")] use ::std::ffi::CStr;
+use ::std::fmt;
+#[cfg(feature = "paranoid")] use ::std::fmt::Arguments;
+use ::std::fmt::Debug;
+#[cfg(any(feature = "paranoid", feature = "shared-memory"))] use ::std::fmt::Display;
+use ::std::fmt::Formatter;
+#[cfg(all(feature = "shared-memory", target_os = "linux"))] use ::std::fs::read_to_string;
+#[cfg(not(loom))] use ::std::hint::spin_loop;
+use ::std::io;
+use ::std::io::BufRead;
+use ::std::io::IoSlice;
+use ::std::io::Read;
+use ::std::io::Write;
+use ::std::iter::Chain;
 use ::std::marker::PhantomData;
 use ::std::mem::align_of;
+use ::std::mem::forget;
 use ::std::mem::size_of;
-use ::std::mem::transmute_copy;
-use ::std::mem::uninitialized;
+use ::std::mem::take;
+#[cfg(feature = "shared-memory")] use ::std::mem::zeroed;
+use ::std::ops::Add;
+use ::std::ops::BitAnd;
+use ::std::ops::BitOr;
 use ::std::ops::Deref;
 use ::std::ops::DerefMut;
+#[cfg(feature = "shared-memory")] use ::std::os::unix::io::RawFd;
+#[cfg(kani)] use ::std::ops::Not;
+#[cfg(kani)] use ::std::ops::Sub;
+#[cfg(feature = "shared-memory")] use ::std::process::id as process_identifier;
 use ::std::ptr::drop_in_place;
 use ::std::ptr::NonNull;
+#[cfg(feature = "paranoid")] use ::std::ptr::slice_from_raw_parts_mut;
+#[cfg(feature = "shared-memory")] use ::std::ptr::null_mut;
+#[cfg(feature = "shared-memory")] use ::std::ptr::read_volatile;
 use ::std::ptr::write;
 use ::std::slice::from_raw_parts;
 use ::std::slice::from_raw_parts_mut;
-use ::std::sync::atomic::fence;
+use ::std::slice::Iter;
+use ::std::slice::IterMut;
+#[cfg(not(loom))] use ::std::sync::atomic::AtomicBool;
+#[cfg(not(loom))] use ::std::sync::atomic::AtomicUsize;
+#[cfg(not(loom))] use ::std::sync::atomic::fence;
+use ::std::sync::atomic::Ordering::AcqRel;
+use ::std::sync::atomic::Ordering::Acquire;
+#[cfg(feature = "shared-memory")] use ::std::sync::atomic::Ordering::Relaxed;
+use ::std::sync::atomic::Ordering::Release;
 use ::std::sync::atomic::Ordering::SeqCst;
-use ::std::sync::atomic::spin_loop_hint;
-use std::sync::Arc;
+use ::std::sync::Arc;
+#[cfg(not(loom))] use ::std::sync::Mutex;
+#[cfg(not(loom))] use ::std::sync::MutexGuard;
+#[cfg(feature = "fault-injection")] use ::std::thread::sleep;
+use ::std::time::Duration;
+use ::std::time::Instant;
+#[cfg(feature = "shared-memory")] use ::libc::c_void;
+#[cfg(feature = "shared-memory")] use ::libc::close;
+#[cfg(feature = "shared-memory")] use ::libc::ESRCH;
+#[cfg(feature = "shared-memory")] use ::libc::fcntl;
+#[cfg(feature = "shared-memory")] use ::libc::fstat;
+#[cfg(feature = "shared-memory")] use ::libc::ftruncate;
+#[cfg(feature = "shared-memory")] use ::libc::F_DUPFD_CLOEXEC;
+#[cfg(feature = "shared-memory")] use ::libc::kill;
+#[cfg(feature = "shared-memory")] use ::libc::MAP_FAILED;
+#[cfg(feature = "shared-memory")] use ::libc::MAP_SHARED;
+#[cfg(all(feature = "shared-memory", target_os = "linux"))] use ::libc::memfd_create;
+#[cfg(all(feature = "shared-memory", target_os = "linux"))] use ::libc::MFD_CLOEXEC;
+#[cfg(feature = "shared-memory")] use ::libc::mmap;
+#[cfg(feature = "shared-memory")] use ::libc::munmap;
+#[cfg(feature = "shared-memory")] use ::libc::O_CLOEXEC;
+#[cfg(feature = "shared-memory")] use ::libc::O_CREAT;
+#[cfg(feature = "shared-memory")] use ::libc::O_EXCL;
+#[cfg(feature = "shared-memory")] use ::libc::O_RDWR;
+#[cfg(feature = "shared-memory")] use ::libc::off_t;
+#[cfg(feature = "shared-memory")] use ::libc::pid_t;
+#[cfg(feature = "shared-memory")] use ::libc::PROT_READ;
+#[cfg(feature = "shared-memory")] use ::libc::PROT_WRITE;
+#[cfg(feature = "shared-memory")] use ::libc::shm_open;
+#[cfg(feature = "shared-memory")] use ::libc::shm_unlink;
+#[cfg(feature = "shared-memory")] use ::libc::stat;
+#[cfg(loom)] use ::loom::sync::atomic::AtomicBool;
+#[cfg(loom)] use ::loom::sync::atomic::AtomicUsize;
+#[cfg(loom)] use ::loom::sync::atomic::fence;
+#[cfg(loom)] use ::loom::sync::Mutex;
+#[cfg(loom)] use ::loom::sync::MutexGuard;
+#[cfg(loom)] use ::loom::thread::yield_now;
+
+
+#[cfg(feature = "derive")] pub use ::lock_free_multi_producer_single_consumer_ring_buffer_derive::RingMessage;
 
 
 include!("fence_stores.rs");
+include!("paranoid_assert.rs");
+#[cfg(feature = "shared-memory")] include!("process_start_time.rs");
+include!("AtomicRingBufferOffset.rs");
+include!("ByteRecords.rs");
+include!("ByteRingBuffer.rs");
+include!("ByteRingBufferConsumer.rs");
+include!("ByteRingBufferConsumerGuard.rs");
+include!("ByteRingBufferProducer.rs");
+include!("ByteRingBufferProducerGuard.rs");
+include!("ConsumedBatch.rs");
+include!("ConsumeStatus.rs");
+#[cfg(feature = "shared-memory")] include!("ConsumerHardening.rs");
+#[cfg(feature = "fault-injection")] include!("FaultInjection.rs");
+#[cfg(feature = "fault-injection")] include!("FaultInjectionConfiguration.rs");
+#[cfg(feature = "fault-injection")] include!("FaultInjectionPoint.rs");
+#[cfg(feature = "fault-injection")] include!("FaultInjector.rs");
+include!("MessageRingBuffer.rs");
+include!("MessageRingBufferConsumer.rs");
+include!("MessageRingBufferConsumerGuard.rs");
+include!("MessageRingBufferProducer.rs");
+include!("OffsetArithmetic.rs");
+#[cfg(kani)] include!("offset_arithmetic_proofs.rs");
+include!("OwnedBatch.rs");
+include!("OwnedReservation.rs");
+#[cfg(feature = "shared-memory")] include!("PlainOldData.rs");
 include!("RingBuffer.rs");
 include!("RingBufferConsumer.rs");
 include!("RingBufferConsumerGuard.rs");
+include!("RingBufferConsumerReader.rs");
+include!("RingBufferFormat.rs");
+#[cfg(feature = "shared-memory")] include!("RingBufferFormatError.rs");
 include!("RingBufferInner.rs");
 include!("RingBufferInnerHeader.rs");
 include!("RingBufferInnerDropHandler.rs");
+include!("RingBufferMemory.rs");
 include!("RingBufferOffset.rs");
 include!("RingBufferProducer.rs");
 include!("RingBufferProducerGuard.rs");
 include!("RingBufferProducerInner.rs");
+include!("RingBufferProducerSlot.rs");
+include!("RingBufferProducerWriter.rs");
+include!("RingBufferSharedHeader.rs");
+include!("RingMessage.rs");
+include!("RingMessageTag.rs");
+#[cfg(feature = "paranoid")] include!("SlotState.rs");
+#[cfg(feature = "paranoid")] include!("SlotStates.rs");
+#[cfg(feature = "shared-memory")] include!("SharedMemoryRingBuffer.rs");
+include!("SkippedRanges.rs");
 include!("SpinLockBackOff.rs");
-include!("VolatileRingBufferOffset.rs");
+include!("StallWatchdog.rs");
+include!("StalledProducer.rs");
+#[cfg(feature = "fault-injection")] include!("XorShift.rs");
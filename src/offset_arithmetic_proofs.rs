@@ -0,0 +1,268 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+// Proofs, for the Kani model checker, that the arithmetic of `OffsetArithmetic` is correct for the layouts of all three pointer widths.
+//
+// Run with `cargo kani`; Kani also checks every addition and subtraction for overflow.
+//
+// The proofs are of the hands between operations, ie whilst no producer or consumer is part way through one; `loom` and the fuzz targets cover the interleavings.
+
+
+/// Bounds of `OffsetArithmetic` that the proofs need but the ring buffer does not.
+trait ProvableOffsetArithmetic: OffsetArithmetic + Sub<Output=Self> + Not<Output=Self> + From<u8> + kani::Arbitrary
+{
+}
+
+impl<O: OffsetArithmetic + Sub<Output=Self> + Not<Output=Self> + From<u8> + kani::Arbitrary> ProvableOffsetArithmetic for O
+{
+}
+
+/// The value of the `end` offset when it is not set.
+#[inline(always)]
+fn maximum_offset<O: ProvableOffsetArithmetic>() -> O
+{
+	!O::WrapLockBit
+}
+
+/// `RingBufferInnerHeader::new()` asserts that the capacity is less than `OffsetMask`.
+fn any_capacity<O: ProvableOffsetArithmetic>() -> O
+{
+	let capacity: O = kani::any();
+	kani::assume(capacity > O::Zero && capacity < O::OffsetMask);
+	capacity
+}
+
+/// Any `(next, end, written)` offsets which satisfy the invariants (as asserted by `RingBufferInnerHeader::assert_invariants()`); `next` is without its wrap-around counter.
+fn any_hands<O: ProvableOffsetArithmetic>(capacity: O) -> (O, O, O)
+{
+	let next: O = kani::any();
+	let end: O = kani::any();
+	let written: O = kani::any();
+	kani::assume(next < capacity);
+	kani::assume(written < capacity);
+	kani::assume(end == maximum_offset() || (end < capacity && written <= end && next < written));
+	(next, end, written)
+}
+
+fn assert_hands<O: ProvableOffsetArithmetic>(capacity: O, next: O, end: O, written: O)
+{
+	assert!(next < capacity, "next equals or exceeds capacity");
+	assert!(written < capacity, "written equals or exceeds capacity");
+	if end != maximum_offset()
+	{
+		assert!(end < capacity, "end equals or exceeds capacity");
+		assert!(written <= end, "written is after end");
+		assert!(next < written, "next has caught up with written after wrap-around");
+	}
+}
+
+/// The count of items acquired but not yet released, excluding any space wasted at the end of the buffer by a wrap-around.
+fn unreleased<O: ProvableOffsetArithmetic>(capacity: O, next: O, end: O, written: O) -> O
+{
+	if next >= written
+	{
+		next - written
+	}
+	else
+	{
+		min(capacity, end) - written + next
+	}
+}
+
+/// The count of items the consumer can consume contiguously from `written`.
+fn consumable<O: ProvableOffsetArithmetic>(capacity: O, next: O, end: O, written: O) -> O
+{
+	if next >= written
+	{
+		next - written
+	}
+	else
+	{
+		min(capacity, end) - written
+	}
+}
+
+/// As `RingBufferInnerHeader::consume_segments()` does when no producer reservation is outstanding: once the consumer has released everything up to the `end` offset it clears `end` and wraps-around.
+///
+/// Returns `(end, written)`.
+fn consumer_wrap_around<O: ProvableOffsetArithmetic>(capacity: O, next: O, end: O, written: O) -> (O, O)
+{
+	if next < written && written == min(capacity, end)
+	{
+		(maximum_offset(), O::Zero)
+	}
+	else
+	{
+		(end, written)
+	}
+}
+
+fn layout_partitions_the_word<O: ProvableOffsetArithmetic>()
+{
+	assert!(O::OffsetMask & O::WrapCounter == O::Zero);
+	assert!(O::OffsetMask & O::WrapLockBit == O::Zero);
+	assert!(O::WrapCounter & O::WrapLockBit == O::Zero);
+	assert!(O::OffsetMask | O::WrapCounter | O::WrapLockBit == !O::Zero, "a bit is in none of the offset, the wrap-around counter or the wrap lock bit");
+
+	// The offset is the least significant bits, and the wrap-around counter is immediately above it and immediately below the wrap lock bit.
+	assert!(O::OffsetMask & (O::OffsetMask + O::from(1)) == O::Zero);
+	assert!(O::OffsetMask + O::from(1) == O::WrapCounterIncrement);
+	assert!(O::WrapCounter + O::WrapCounterIncrement == O::WrapLockBit);
+}
+
+/// `acquire_target()` never overflows into the wrap-around counter or the wrap lock bit, never produces an offset which equals or exceeds the capacity, and never lets the producer catch up with the consumer.
+fn acquire_target_is_correct<O: ProvableOffsetArithmetic>()
+{
+	let capacity = any_capacity::<O>();
+	let (next, end, written) = any_hands(capacity);
+
+	let wrap_counter: O = kani::any::<O>() & O::WrapCounter;
+	let seen = wrap_counter | next;
+
+	let count: O = kani::any();
+	kani::assume(count > O::Zero && count <= capacity);
+
+	let target = match O::acquire_target(seen, count, written, capacity)
+	{
+		Err(()) => return,
+		Ok(target) => target,
+	};
+
+	let target_offset = target & O::OffsetMask;
+	let target_wrap_counter = target & O::WrapCounter;
+	let wrap_locked = target & O::WrapLockBit != O::Zero;
+
+	let (start, next_after, end_after) = if next + count < capacity
+	{
+		assert!(!wrap_locked);
+		assert!(target_offset == next + count);
+		assert!(target_wrap_counter == wrap_counter, "the wrap-around counter changed without a wrap-around");
+		(next, target_offset, end)
+	}
+	else
+	{
+		assert!(target_wrap_counter == (wrap_counter + O::WrapCounterIncrement) & O::WrapCounter, "the wrap-around counter was not incremented");
+		assert!(target_wrap_counter != wrap_counter);
+		if wrap_locked
+		{
+			// `acquire()` sets `end` to `next` whilst wrap-locked.
+			assert!(next + count > capacity);
+			assert!(target_offset == count);
+			assert!(end == maximum_offset(), "wrapped-around again before the consumer wrapped-around");
+			(O::Zero, target_offset, next)
+		}
+		else
+		{
+			// Used all the space exactly to the end.
+			assert!(next + count == capacity);
+			assert!(target_offset == O::Zero);
+			(next, target_offset, end)
+		}
+	};
+
+	// The reservation is within the buffer.
+	assert!(start + count <= capacity);
+
+	assert_hands(capacity, next_after, end_after, written);
+	assert!(unreleased(capacity, next_after, end_after, written) == unreleased(capacity, next, end, written) + count, "the producer caught up with the consumer or the reservation overlaps another");
+}
+
+/// `released_written()` never produces an offset which equals or exceeds the capacity, and wrapping-around to `0` is consistent with `consume_segments()`: nothing is lost and the consumer sees the ring buffer as empty if and only if everything has been released.
+fn released_written_is_consistent_with_consume<O: ProvableOffsetArithmetic>()
+{
+	let capacity = any_capacity::<O>();
+	let (next, end, written) = any_hands(capacity);
+	let before = unreleased(capacity, next, end, written);
+
+	let (end, written) = consumer_wrap_around(capacity, next, end, written);
+	assert_hands(capacity, next, end, written);
+	assert!(unreleased(capacity, next, end, written) == before);
+
+	let count: O = kani::any();
+	kani::assume(count <= consumable(capacity, next, end, written));
+
+	let written_after = O::released_written(written, count, capacity);
+	if written + count == capacity
+	{
+		assert!(written_after == O::Zero);
+	}
+	else
+	{
+		assert!(written_after == written + count);
+	}
+
+	assert_hands(capacity, next, end, written_after);
+	let after = unreleased(capacity, next, end, written_after);
+	assert!(after + count == before);
+
+	let (_end, written_after) = consumer_wrap_around(capacity, next, end, written_after);
+	assert!((written_after == next) == (after == O::Zero), "the consumer's view of whether the ring buffer is empty is wrong");
+}
+
+#[kani::proof]
+fn ring_buffer_offset_has_the_layout_for_its_pointer_width()
+{
+	#[cfg(target_pointer_width = "64")] type PointerWidth = u64;
+	#[cfg(target_pointer_width = "32")] type PointerWidth = u32;
+	#[cfg(target_pointer_width = "16")] type PointerWidth = u16;
+
+	assert!(<RingBufferOffset as OffsetArithmetic>::WrapCounter as PointerWidth == <PointerWidth as OffsetArithmetic>::WrapCounter);
+	assert!(<RingBufferOffset as OffsetArithmetic>::WrapCounterIncrement as PointerWidth == <PointerWidth as OffsetArithmetic>::WrapCounterIncrement);
+	assert!(<RingBufferOffset as OffsetArithmetic>::WrapLockBit as PointerWidth == <PointerWidth as OffsetArithmetic>::WrapLockBit);
+	assert!(<RingBufferOffset as OffsetArithmetic>::OffsetMask as PointerWidth == <PointerWidth as OffsetArithmetic>::OffsetMask);
+}
+
+#[kani::proof]
+fn layout_partitions_the_word_16_bit()
+{
+	layout_partitions_the_word::<u16>()
+}
+
+#[kani::proof]
+fn layout_partitions_the_word_32_bit()
+{
+	layout_partitions_the_word::<u32>()
+}
+
+#[kani::proof]
+fn layout_partitions_the_word_64_bit()
+{
+	layout_partitions_the_word::<u64>()
+}
+
+#[kani::proof]
+fn acquire_target_is_correct_16_bit()
+{
+	acquire_target_is_correct::<u16>()
+}
+
+#[kani::proof]
+fn acquire_target_is_correct_32_bit()
+{
+	acquire_target_is_correct::<u32>()
+}
+
+#[kani::proof]
+fn acquire_target_is_correct_64_bit()
+{
+	acquire_target_is_correct::<u64>()
+}
+
+#[kani::proof]
+fn released_written_is_consistent_with_consume_16_bit()
+{
+	released_written_is_consistent_with_consume::<u16>()
+}
+
+#[kani::proof]
+fn released_written_is_consistent_with_consume_32_bit()
+{
+	released_written_is_consistent_with_consume::<u32>()
+}
+
+#[kani::proof]
+fn released_written_is_consistent_with_consume_64_bit()
+{
+	released_written_is_consistent_with_consume::<u64>()
+}
@@ -0,0 +1,40 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// As `debug_assert!()`, but with the `paranoid` feature it is also checked in release builds and panics with a dump of all the hands and slots of the ring buffer.
+///
+/// The first argument is the `RingBufferInnerHeader`.
+#[cfg(not(feature = "paranoid"))]
+macro_rules! paranoid_assert
+{
+	($ring_buffer_inner_header: expr, $condition: expr) =>
+	{
+		debug_assert!($condition)
+	};
+
+	($ring_buffer_inner_header: expr, $condition: expr, $($argument: tt)+) =>
+	{
+		debug_assert!($condition, $($argument)+)
+	};
+}
+
+/// As `debug_assert!()`, but with the `paranoid` feature it is also checked in release builds and panics with a dump of all the hands and slots of the ring buffer.
+///
+/// The first argument is the `RingBufferInnerHeader`.
+#[cfg(feature = "paranoid")]
+macro_rules! paranoid_assert
+{
+	($ring_buffer_inner_header: expr, $condition: expr) =>
+	{
+		paranoid_assert!($ring_buffer_inner_header, $condition, "assertion failed: {}", stringify!($condition))
+	};
+
+	($ring_buffer_inner_header: expr, $condition: expr, $($argument: tt)+) =>
+	{
+		if !$condition
+		{
+			$ring_buffer_inner_header.paranoid_violation(format_args!($($argument)+))
+		}
+	};
+}
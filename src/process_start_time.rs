@@ -0,0 +1,30 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+/// When the process `process_identifier` started, in clock ticks since boot, so that a process identifier which has been reused by a later process can be told apart from it; `0` if unknown (eg the process does not exist, or is not visible in this process's PID namespace).
+#[cfg(target_os = "linux")]
+#[inline(always)]
+fn process_start_time(process_identifier: usize) -> usize
+{
+	let stat = match read_to_string(format!("/proc/{}/stat", process_identifier))
+	{
+		Err(_) => return 0,
+		Ok(stat) => stat,
+	};
+
+	// The second field, the command, is in parentheses and can itself contain spaces and parentheses; the start time is the 22nd field.
+	match stat.rfind(')')
+	{
+		None => 0,
+		Some(end_of_command) => stat[end_of_command + 1 ..].split_whitespace().nth(19).and_then(|start_time| start_time.parse().ok()).unwrap_or(0),
+	}
+}
+
+/// Not known on this platform, so a reused process identifier can not be detected.
+#[cfg(not(target_os = "linux"))]
+#[inline(always)]
+fn process_start_time(_process_identifier: usize) -> usize
+{
+	0
+}
@@ -0,0 +1,114 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Tests of `ByteRingBuffer`.
+
+
+#![allow(non_upper_case_globals)]
+
+
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+
+
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+
+
+fn consume_all(byte_ring_buffer_consumer: &mut ByteRingBufferConsumer) -> Vec<Vec<u8>>
+{
+	let mut consumer_guard = byte_ring_buffer_consumer.consume();
+	let mut records = Vec::new();
+	while let Some(record) = consumer_guard.next_record()
+	{
+		records.push(record.to_vec())
+	}
+	records
+}
+
+#[test]
+fn records_of_any_length_are_consumed_whole_and_aligned()
+{
+	let (mut byte_ring_buffer_consumer, byte_ring_buffer_producers) = ByteRingBuffer::new(256, 1);
+
+	byte_ring_buffer_producers[0].produce(b"").unwrap();
+	byte_ring_buffer_producers[0].produce(b"a").unwrap();
+	byte_ring_buffer_producers[0].produce(b"12345678").unwrap();
+	byte_ring_buffer_producers[0].produce(b"123456789").unwrap();
+
+	{
+		let consumer_guard = byte_ring_buffer_consumer.consume();
+		assert_eq!(consumer_guard.len(), 4);
+		assert!(consumer_guard.records().all(|record| (record.as_ptr() as usize).is_multiple_of(ByteRingBuffer::RecordAlignment)));
+	}
+
+	assert_eq!(consume_all(&mut byte_ring_buffer_consumer), vec![b"".to_vec(), b"a".to_vec(), b"12345678".to_vec(), b"123456789".to_vec()]);
+	assert_eq!(consume_all(&mut byte_ring_buffer_consumer), Vec::<Vec<u8>>::new());
+}
+
+#[test]
+fn a_record_is_never_split_by_a_wrap_around()
+{
+	let (mut byte_ring_buffer_consumer, byte_ring_buffer_producers) = ByteRingBuffer::new(64, 1);
+	assert_eq!(ByteRingBuffer::record_size(20), 32);
+
+	byte_ring_buffer_producers[0].produce(&[1; 20]).unwrap();
+	byte_ring_buffer_producers[0].produce(&[2; 4]).unwrap();
+	assert_eq!(consume_all(&mut byte_ring_buffer_consumer), vec![vec![1; 20], vec![2; 4]]);
+
+	// Only 16 bytes remain before the end of the buffer, so the record is acquired at its start.
+	byte_ring_buffer_producers[0].produce(&[3; 20]).unwrap();
+	assert_eq!(consume_all(&mut byte_ring_buffer_consumer), vec![vec![3; 20]]);
+	assert_eq!(consume_all(&mut byte_ring_buffer_consumer), Vec::<Vec<u8>>::new());
+}
+
+#[test]
+fn unread_records_are_returned_to_the_queue()
+{
+	let (mut byte_ring_buffer_consumer, byte_ring_buffer_producers) = ByteRingBuffer::new(256, 2);
+
+	byte_ring_buffer_producers[0].produce(b"first").unwrap();
+	byte_ring_buffer_producers[1].produce(b"second").unwrap();
+	byte_ring_buffer_producers[0].produce(b"third").unwrap();
+
+	{
+		let mut consumer_guard = byte_ring_buffer_consumer.consume();
+		assert_eq!(consumer_guard.next_record(), Some(&b"first"[..]));
+	}
+
+	{
+		let mut consumer_guard = byte_ring_buffer_consumer.consume();
+		consumer_guard.release(1);
+		assert_eq!(consumer_guard.records().collect::<Vec<_>>(), vec![&b"third"[..]]);
+	}
+
+	assert_eq!(consume_all(&mut byte_ring_buffer_consumer), vec![b"third".to_vec()]);
+}
+
+#[test]
+fn a_record_larger_than_the_capacity_can_not_be_acquired()
+{
+	let (_byte_ring_buffer_consumer, byte_ring_buffer_producers) = ByteRingBuffer::new(64, 1);
+
+	assert!(byte_ring_buffer_producers[0].acquire(64).is_err());
+
+	let mut producer_guard = byte_ring_buffer_producers[0].acquire(3).unwrap();
+	producer_guard.copy_from_slice(b"abc");
+	assert_eq!(&producer_guard[..], b"abc");
+}
+
+#[test]
+fn an_acquired_record_is_zeroed_rather_than_holding_the_bytes_of_an_earlier_record()
+{
+	let (mut byte_ring_buffer_consumer, byte_ring_buffer_producers) = ByteRingBuffer::new(64, 1);
+
+	byte_ring_buffer_producers[0].produce(&[0xFF; 20]).unwrap();
+	byte_ring_buffer_producers[0].produce(&[0xFF; 4]).unwrap();
+	assert_eq!(consume_all(&mut byte_ring_buffer_consumer), vec![vec![0xFF; 20], vec![0xFF; 4]]);
+
+	// Acquired at the start of the buffer, where the first record was.
+	let producer_guard = byte_ring_buffer_producers[0].acquire(20).unwrap();
+	assert_eq!(&producer_guard[..], &[0; 20][..]);
+	drop(producer_guard);
+
+	assert_eq!(consume_all(&mut byte_ring_buffer_consumer), vec![vec![0; 20]]);
+}
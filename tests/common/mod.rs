@@ -0,0 +1,27 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Helpers shared by the tests.
+
+
+/// A small xorshift64 pseudo-random number generator, so that the tests need no dependency for one.
+pub struct XorShift(u64);
+
+impl XorShift
+{
+	/// Any seed, including zero, is valid.
+	pub fn new(seed: u64) -> Self
+	{
+		// A xorshift generator can not have a state of zero.
+		XorShift(seed | 1)
+	}
+
+	pub fn next(&mut self) -> u64
+	{
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+}
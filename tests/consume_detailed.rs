@@ -0,0 +1,88 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Tests of `RingBufferConsumer::consume_detailed()`.
+
+
+#![allow(non_upper_case_globals)]
+
+
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+
+
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+
+
+fn length_and_status(ring_buffer_consumer: &mut RingBufferConsumer<u8>) -> (usize, ConsumeStatus)
+{
+	let (slice_guard, consume_status) = ring_buffer_consumer.consume_detailed();
+	(slice_guard.len(), consume_status)
+}
+
+#[test]
+fn empty_then_ready()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(16, 1);
+	assert_eq!(length_and_status(&mut ring_buffer_consumer), (0, ConsumeStatus::Empty));
+
+	drop(ring_buffer_producers[0].acquire(4).unwrap());
+	assert_eq!(length_and_status(&mut ring_buffer_consumer), (4, ConsumeStatus::Ready(4)));
+
+	let (mut slice_guard, _) = ring_buffer_consumer.consume_detailed();
+	slice_guard.release(4);
+	drop(slice_guard);
+	assert_eq!(length_and_status(&mut ring_buffer_consumer), (0, ConsumeStatus::Empty));
+}
+
+#[test]
+fn blocked_by_the_producer_at_the_written_offset()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new_with_maximum_reservations(16, &[1, 2]);
+
+	let first = ring_buffer_producers[1].acquire(2).unwrap();
+	let second = ring_buffer_producers[1].acquire(2).unwrap();
+	drop(ring_buffer_producers[0].acquire(3).unwrap());
+	drop(first);
+	assert_eq!(length_and_status(&mut ring_buffer_consumer), (2, ConsumeStatus::Ready(2)));
+
+	let (mut slice_guard, _) = ring_buffer_consumer.consume_detailed();
+	slice_guard.release(2);
+	drop(slice_guard);
+	assert_eq!(length_and_status(&mut ring_buffer_consumer), (0, ConsumeStatus::BlockedByProducer { producer_index: 1, reservation_index: 1 }));
+
+	drop(second);
+	assert_eq!(length_and_status(&mut ring_buffer_consumer), (5, ConsumeStatus::Ready(5)));
+}
+
+#[test]
+fn wrap_pending_once_the_producers_have_wrapped_around()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(10, 2);
+
+	drop(ring_buffer_producers[0].acquire(6).unwrap());
+	ring_buffer_consumer.consume().release(6);
+
+	drop(ring_buffer_producers[0].acquire(3).unwrap());
+	drop(ring_buffer_producers[1].acquire(2).unwrap());
+
+	let (mut slice_guard, consume_status) = ring_buffer_consumer.consume_detailed();
+	assert_eq!(consume_status, ConsumeStatus::WrapPending { count: 3, wrapped_count: 2 });
+	slice_guard.release(3);
+	drop(slice_guard);
+
+	assert_eq!(length_and_status(&mut ring_buffer_consumer), (2, ConsumeStatus::Ready(2)));
+}
+
+#[test]
+fn nothing_is_consumed_whilst_an_owned_batch_is_outstanding()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(16, 1);
+	drop(ring_buffer_producers[0].acquire(4).unwrap());
+
+	let owned_batch = ring_buffer_consumer.consume_owned().unwrap();
+	assert_eq!(length_and_status(&mut ring_buffer_consumer), (0, ConsumeStatus::OwnedBatchOutstanding));
+
+	drop(owned_batch);
+	assert_eq!(length_and_status(&mut ring_buffer_consumer), (0, ConsumeStatus::Empty));
+}
@@ -0,0 +1,78 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Tests of `RingBufferConsumerGuard`.
+
+
+#![allow(non_upper_case_globals)]
+
+
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+
+
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+use ::std::rc::Rc;
+
+
+fn produce(ring_buffer_producer: &RingBufferProducer<u8>, items: &[u8])
+{
+	ring_buffer_producer.acquire(items.len()).unwrap().copy_from_slice(items)
+}
+
+#[test]
+fn dropping_a_guard_returns_the_unconsumed_items_to_the_queue()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(16, 1);
+	produce(&ring_buffer_producers[0], &[0, 1, 2]);
+
+	drop(ring_buffer_consumer.consume());
+
+	assert_eq!(ring_buffer_consumer.consume().as_slice(), &[0, 1, 2]);
+}
+
+#[test]
+fn a_partial_release_then_drop_releases_only_the_consumed_items()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(8, 1);
+	produce(&ring_buffer_producers[0], &[0, 1, 2, 3, 4, 5, 6]);
+
+	{
+		let mut slice_guard = ring_buffer_consumer.consume();
+		slice_guard.release(2);
+		assert_eq!(slice_guard.as_slice(), &[2, 3, 4, 5, 6]);
+
+		// The space released is immediately available to producers.
+		produce(&ring_buffer_producers[0], &[7]);
+
+		assert_eq!(slice_guard.next(), Some(2));
+	}
+
+	assert_eq!(ring_buffer_consumer.consume().as_slice(), &[3, 4, 5, 6, 7]);
+}
+
+#[test]
+fn discarded_items_are_dropped_and_released_when_the_guard_is_dropped()
+{
+	let item = Rc::new(());
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<Rc<()>>::new(8, 1);
+	{
+		let mut slice_guard = ring_buffer_producers[0].acquire(3).unwrap();
+		for index in 0 .. 3
+		{
+			unsafe { slice_guard.as_mut_ptr().add(index).write(item.clone()) }
+		}
+	}
+
+	{
+		let mut slice_guard = ring_buffer_consumer.consume();
+		slice_guard.discard(2);
+		assert_eq!(Rc::strong_count(&item), 2);
+		assert_eq!(slice_guard.len(), 1);
+	}
+
+	let mut slice_guard = ring_buffer_consumer.consume();
+	assert_eq!(slice_guard.len(), 1);
+	slice_guard.release(1);
+	assert_eq!(Rc::strong_count(&item), 1);
+}
@@ -0,0 +1,342 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Property-based differential tests of random single-threaded sequences of operations against a simple reference model.
+//!
+//! The model tracks the `next`, `written` and `end` hands and the in-flight reservations to predict which acquisitions are accepted and how much each `consume()` returns, and a `VecDeque` per producer to predict which items are consumed.
+//! Every item counts how many times it is dropped; each must be dropped exactly once, including those left in the ring buffer when it is dropped.
+
+
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+extern crate proptest;
+
+
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+use ::proptest::collection::vec;
+use ::proptest::prelude::*;
+use ::std::cell::RefCell;
+use ::std::cmp::min;
+use ::std::collections::VecDeque;
+use ::std::rc::Rc;
+
+
+/// Counts how many times each item, by identifier, has been dropped.
+type DropCounts = Rc<RefCell<Vec<usize>>>;
+
+#[derive(Debug)]
+struct Item
+{
+	identifier: usize,
+	producer_index: usize,
+	sequence_number: usize,
+	drop_counts: DropCounts,
+}
+
+impl Drop for Item
+{
+	fn drop(&mut self)
+	{
+		self.drop_counts.borrow_mut()[self.identifier] += 1;
+	}
+}
+
+/// Indices and counts are reduced modulo the number of producers, reservations held and capacity when applied.
+#[derive(Debug, Copy, Clone)]
+enum Operation
+{
+	/// Acquire, then write, `count` items.
+	Acquire
+	{
+		producer_index: usize,
+		count: usize,
+	},
+
+	/// Drop one of the guards held by a producer.
+	Produce
+	{
+		producer_index: usize,
+		reservation_index: usize,
+	},
+
+	/// Consume, move out at most `maximum_to_move_out` items, then drop the guard.
+	Consume
+	{
+		maximum_to_move_out: usize,
+	},
+}
+
+#[derive(Debug, Clone)]
+struct Scenario
+{
+	capacity: usize,
+	maximum_reservations_per_producer: Vec<usize>,
+	operations: Vec<Operation>,
+}
+
+fn operation() -> impl Strategy<Value=Operation>
+{
+	prop_oneof!
+	[
+		(0usize .. 8, 0usize .. 16).prop_map(|(producer_index, count)| Operation::Acquire { producer_index, count }),
+		(0usize .. 8, 0usize .. 2).prop_map(|(producer_index, reservation_index)| Operation::Produce { producer_index, reservation_index }),
+		(0usize .. 16).prop_map(|maximum_to_move_out| Operation::Consume { maximum_to_move_out }),
+	]
+}
+
+fn scenario() -> impl Strategy<Value=Scenario>
+{
+	(2usize ..= 12, vec(1usize ..= 2, 1 ..= 4), vec(operation(), 0 .. 96)).prop_map(|(capacity, maximum_reservations_per_producer, operations)| Scenario { capacity, maximum_reservations_per_producer, operations })
+}
+
+/// A sequential restatement of where the hands are.
+#[derive(Debug)]
+struct Model
+{
+	capacity: usize,
+	next: usize,
+	written: usize,
+	end: Option<usize>,
+
+	/// The `seen` offset of each in-flight reservation, by producer.
+	in_flight: Vec<Vec<usize>>,
+	maximum_reservations_per_producer: Vec<usize>,
+
+	/// Items acquired but not yet consumed, by producer, in acquisition order.
+	unconsumed: Vec<VecDeque<usize>>,
+	next_sequence_numbers: Vec<usize>,
+}
+
+impl Model
+{
+	fn new(capacity: usize, maximum_reservations_per_producer: &[usize]) -> Self
+	{
+		let number_of_producers = maximum_reservations_per_producer.len();
+		Self
+		{
+			capacity,
+			next: 0,
+			written: 0,
+			end: None,
+			in_flight: vec![Vec::new(); number_of_producers],
+			maximum_reservations_per_producer: maximum_reservations_per_producer.to_vec(),
+			unconsumed: vec![VecDeque::new(); number_of_producers],
+			next_sequence_numbers: vec![0; number_of_producers],
+		}
+	}
+
+	/// Returns the sequence number of the first item acquired, or `None` if the acquisition should be rejected.
+	fn acquire(&mut self, producer_index: usize, count: usize) -> Option<usize>
+	{
+		if self.in_flight[producer_index].len() == self.maximum_reservations_per_producer[producer_index]
+		{
+			return None
+		}
+
+		// Can not catch up with the `written` hand.
+		let next = self.next;
+		let target = next + count;
+		if next < self.written && target >= self.written
+		{
+			return None
+		}
+
+		self.next = if target < self.capacity
+		{
+			target
+		}
+		// Uses the space exactly to the end.
+		else if target == self.capacity
+		{
+			if self.written == 0
+			{
+				return None
+			}
+			0
+		}
+		// Wraps-around, wasting the space at the end.
+		else
+		{
+			if count >= self.written
+			{
+				return None
+			}
+			self.end = Some(next);
+			count
+		};
+
+		self.in_flight[producer_index].push(next);
+
+		let first_sequence_number = self.next_sequence_numbers[producer_index];
+		self.next_sequence_numbers[producer_index] += count;
+		self.unconsumed[producer_index].extend(first_sequence_number .. first_sequence_number + count);
+		Some(first_sequence_number)
+	}
+
+	fn produce(&mut self, producer_index: usize, reservation_index: usize)
+	{
+		self.in_flight[producer_index].remove(reservation_index);
+	}
+
+	/// Returns the count of items which should be consumed.
+	fn consume(&mut self) -> usize
+	{
+		loop
+		{
+			if self.written == self.next
+			{
+				return 0
+			}
+
+			let written = self.written;
+			let ready = self.in_flight.iter().flat_map(|seen_offsets| seen_offsets.iter()).cloned().filter(|&seen_offset| seen_offset >= written).min();
+
+			if self.next < written
+			{
+				let end = min(self.capacity, self.end.unwrap_or(self.capacity));
+				if ready.is_none() && written == end
+				{
+					self.end = None;
+					self.written = 0;
+					continue
+				}
+				return min(ready.unwrap_or(end), end) - written
+			}
+
+			return min(ready.unwrap_or(self.next), self.next) - written
+		}
+	}
+
+	fn release(&mut self, count: usize)
+	{
+		self.written += count;
+		if self.written == self.capacity
+		{
+			self.written = 0
+		}
+	}
+
+	fn consumed(&mut self, item: &Item)
+	{
+		assert_eq!(self.unconsumed[item.producer_index].pop_front(), Some(item.sequence_number), "lost, duplicated or reordered item {:?}", item);
+	}
+}
+
+fn run(scenario: Scenario)
+{
+	let Scenario { capacity, maximum_reservations_per_producer, operations } = scenario;
+	let number_of_producers = maximum_reservations_per_producer.len();
+
+	let drop_counts: DropCounts = Rc::new(RefCell::new(Vec::new()));
+	let mut model = Model::new(capacity, &maximum_reservations_per_producer);
+
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<Item>::new_with_maximum_reservations(capacity, &maximum_reservations_per_producer);
+	{
+		let mut held_guards: Vec<Vec<RingBufferProducerGuard<Item>>> = (0 .. number_of_producers).map(|_| Vec::new()).collect();
+
+		for operation in operations
+		{
+			match operation
+			{
+				Operation::Acquire { producer_index, count } =>
+				{
+					let producer_index = producer_index % number_of_producers;
+					let count = count % capacity + 1;
+
+					let expected = model.acquire(producer_index, count);
+					match ring_buffer_producers[producer_index].acquire(count)
+					{
+						Err(()) => assert_eq!(expected, None, "acquire({}) by producer {} was rejected", count, producer_index),
+
+						Ok(mut slice_guard) =>
+						{
+							let first_sequence_number = expected.expect("acquire() was accepted");
+							assert_eq!(slice_guard.len(), count);
+
+							// The slice is uninitialized, so must not be assigned to (which would drop its garbage contents).
+							for index in 0 .. count
+							{
+								let identifier =
+								{
+									let mut drop_counts = drop_counts.borrow_mut();
+									drop_counts.push(0);
+									drop_counts.len() - 1
+								};
+								let item = Item
+								{
+									identifier,
+									producer_index,
+									sequence_number: first_sequence_number + index,
+									drop_counts: drop_counts.clone(),
+								};
+								unsafe { slice_guard.as_mut_ptr().add(index).write(item) }
+							}
+
+							held_guards[producer_index].push(slice_guard);
+						}
+					}
+				}
+
+				Operation::Produce { producer_index, reservation_index } =>
+				{
+					let producer_index = producer_index % number_of_producers;
+					let guards = &mut held_guards[producer_index];
+					if guards.is_empty()
+					{
+						continue
+					}
+					let reservation_index = reservation_index % guards.len();
+
+					model.produce(producer_index, reservation_index);
+					drop(guards.remove(reservation_index));
+				}
+
+				Operation::Consume { maximum_to_move_out } =>
+				{
+					let expected = model.consume();
+
+					let mut slice_guard = ring_buffer_consumer.consume();
+					assert_eq!(slice_guard.len(), expected, "consume() returned the wrong count");
+
+					let moved_out = slice_guard.move_out(maximum_to_move_out);
+					drop(slice_guard);
+					assert_eq!(moved_out.len(), min(maximum_to_move_out, expected));
+
+					model.release(moved_out.len());
+					for item in moved_out.iter()
+					{
+						model.consumed(item);
+					}
+				}
+			}
+		}
+
+		for guards in held_guards.iter_mut()
+		{
+			guards.clear();
+		}
+	}
+
+	let produced = drop_counts.borrow().len();
+	let consumed = drop_counts.borrow().iter().filter(|&&drop_count| drop_count != 0).count();
+
+	// Everything not consumed is dropped by `drop_remaining_data()`.
+	drop(ring_buffer_consumer);
+	drop(ring_buffer_producers);
+
+	for (identifier, &drop_count) in drop_counts.borrow().iter().enumerate()
+	{
+		assert_eq!(drop_count, 1, "item {} of {} (with {} consumed) was dropped {} times", identifier, produced, consumed, drop_count);
+	}
+}
+
+proptest!
+{
+	#![proptest_config(ProptestConfig::with_cases(2048))]
+
+	#[test]
+	fn matches_reference_model(scenario in scenario())
+	{
+		run(scenario)
+	}
+}
@@ -0,0 +1,224 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Tests using the `fault-injection` feature.
+//!
+//! Run with `cargo test --features fault-injection --test fault_injection`.
+
+
+#![allow(non_upper_case_globals)]
+#![cfg(feature = "fault-injection")]
+
+
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+
+
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+use ::std::cell::RefCell;
+use ::std::rc::Rc;
+use ::std::sync::mpsc::channel;
+use ::std::thread::spawn;
+use ::std::thread::yield_now;
+use ::std::time::Duration;
+
+
+/// Acquires, writes, produces, consumes and releases `iterations` bursts on the current thread, returning the points visited.
+fn single_threaded_bursts(iterations: usize, configuration: FaultInjectionConfiguration) -> (Vec<FaultInjectionPoint>, u64)
+{
+	let points_visited = Rc::new(RefCell::new(Vec::new()));
+	let record_points_visited = points_visited.clone();
+
+	FaultInjection::enable_for_current_thread
+	(
+		FaultInjectionConfiguration
+		{
+			at_point: Some(Box::new(move |point| record_points_visited.borrow_mut().push(point))),
+			.. configuration
+		}
+	);
+
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::new(16, 1);
+	for iteration in 0 .. iterations
+	{
+		let count = iteration % 5 + 1;
+		{
+			let mut slice_guard = ring_buffer_producers[0].acquire(count).expect("spurious compare-and-exchange failures should be retried");
+			for (index, slot) in slice_guard.iter_mut().enumerate()
+			{
+				*slot = iteration + index;
+			}
+		}
+
+		let mut slice_guard = ring_buffer_consumer.consume();
+		let expected: Vec<usize> = (iteration .. iteration + count).collect();
+		assert_eq!(slice_guard.as_slice(), &expected[..]);
+		slice_guard.release(count);
+	}
+
+	let number_of_faults_injected = FaultInjection::number_of_faults_injected_on_current_thread();
+	FaultInjection::disable_for_current_thread();
+
+	let points_visited = points_visited.borrow().clone();
+	(points_visited, number_of_faults_injected)
+}
+
+#[test]
+fn spurious_compare_and_exchange_failures_are_retried()
+{
+	let (points_visited, number_of_faults_injected) = single_threaded_bursts(1000, FaultInjectionConfiguration { seed: 1, compare_and_exchange_failure_one_in: 2, .. FaultInjectionConfiguration::default() });
+
+	assert!(number_of_faults_injected > 0);
+
+	// Each spurious failure makes `acquire()` go around its loop again.
+	// On LL/SC architectures (eg ARM), a weak compare-and-exchange can also fail spuriously of its own accord, so there can be more.
+	let compare_and_exchanges = points_visited.iter().filter(|&&point| point == FaultInjectionPoint::AcquireBeforeCompareAndExchange).count();
+	assert!(compare_and_exchanges as u64 >= 1000 + number_of_faults_injected);
+}
+
+#[test]
+fn same_seed_injects_same_faults()
+{
+	let configuration = |seed| FaultInjectionConfiguration { seed, compare_and_exchange_failure_one_in: 3, yield_one_in: 7, .. FaultInjectionConfiguration::default() };
+
+	let first = single_threaded_bursts(200, configuration(42));
+	let second = single_threaded_bursts(200, configuration(42));
+	assert_eq!(first, second);
+
+	let different = single_threaded_bursts(200, configuration(43));
+	assert_ne!(first, different);
+}
+
+/// Producer 1 stalls immediately before its compare-and-exchange of `next`; producer 2 then acquires and produces.
+/// Producer 1's compare-and-exchange must fail, as `next` has moved, and it must then acquire the space after producer 2's.
+#[test]
+fn producer_stalled_before_compare_and_exchange_acquires_after_another_producer()
+{
+	let (mut ring_buffer_consumer, mut ring_buffer_producers) = RingBuffer::new(16, 2);
+	let ring_buffer_producer_2 = ring_buffer_producers.pop().unwrap();
+	let ring_buffer_producer_1 = ring_buffer_producers.pop().unwrap();
+
+	let (stalled_sender, stalled_receiver) = channel();
+	let (resume_sender, resume_receiver) = channel::<()>();
+
+	let producer_1_thread = spawn(move ||
+	{
+		let mut stalled = false;
+		FaultInjection::enable_for_current_thread
+		(
+			FaultInjectionConfiguration
+			{
+				at_point: Some(Box::new(move |point|
+				{
+					if point == FaultInjectionPoint::AcquireBeforeCompareAndExchange && !stalled
+					{
+						stalled = true;
+						stalled_sender.send(()).unwrap();
+						resume_receiver.recv().unwrap();
+					}
+				})),
+				.. FaultInjectionConfiguration::default()
+			}
+		);
+
+		let mut slice_guard = ring_buffer_producer_1.acquire(3).unwrap();
+		for slot in slice_guard.iter_mut()
+		{
+			*slot = 1;
+		}
+	});
+
+	stalled_receiver.recv().unwrap();
+	{
+		let mut slice_guard = ring_buffer_producer_2.acquire(2).unwrap();
+		for slot in slice_guard.iter_mut()
+		{
+			*slot = 2;
+		}
+	}
+	resume_sender.send(()).unwrap();
+	producer_1_thread.join().unwrap();
+
+	let mut slice_guard = ring_buffer_consumer.consume();
+	assert_eq!(slice_guard.as_slice(), &[2, 2, 1, 1, 1]);
+	slice_guard.release(5);
+}
+
+/// Several producers and the consumer run with random yields, sleeps and spurious compare-and-exchange failures; each seed is a reproducible run.
+#[test]
+fn delays_do_not_lose_or_reorder_messages()
+{
+	const NumberOfProducers: usize = 3;
+	const MessagesPerProducer: usize = 500;
+
+	let configuration = |seed|
+	{
+		FaultInjectionConfiguration
+		{
+			seed,
+			compare_and_exchange_failure_one_in: 4,
+			yield_one_in: 3,
+			sleep_one_in: 200,
+			maximum_sleep: Duration::from_micros(50),
+			at_point: None,
+		}
+	};
+
+	for seed in 0 .. 8
+	{
+		let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::new(32, NumberOfProducers);
+
+		let producer_threads: Vec<_> = ring_buffer_producers.into_iter().enumerate().map(|(producer_index, ring_buffer_producer)|
+		{
+			spawn(move ||
+			{
+				FaultInjection::enable_for_current_thread(configuration(seed * 100 + producer_index as u64 + 1));
+
+				let mut sequence_number = 0;
+				while sequence_number != MessagesPerProducer
+				{
+					let count = (sequence_number % 4 + 1).min(MessagesPerProducer - sequence_number);
+					match ring_buffer_producer.acquire(count)
+					{
+						Err(()) => yield_now(),
+
+						Ok(mut slice_guard) => for slot in slice_guard.iter_mut()
+						{
+							*slot = (producer_index, sequence_number);
+							sequence_number += 1;
+						},
+					}
+				}
+			})
+		}).collect();
+
+		FaultInjection::enable_for_current_thread(configuration(seed * 100));
+
+		let mut next_sequence_numbers = [0; NumberOfProducers];
+		while next_sequence_numbers.iter().any(|&next_sequence_number| next_sequence_number != MessagesPerProducer)
+		{
+			let mut slice_guard = ring_buffer_consumer.consume();
+			let length = slice_guard.len();
+			if length == 0
+			{
+				drop(slice_guard);
+				yield_now();
+				continue
+			}
+
+			for &(producer_index, sequence_number) in slice_guard.as_slice()
+			{
+				assert_eq!(sequence_number, next_sequence_numbers[producer_index], "lost, duplicated or reordered message with seed {}", seed);
+				next_sequence_numbers[producer_index] += 1;
+			}
+			slice_guard.release(length);
+		}
+
+		FaultInjection::disable_for_current_thread();
+
+		for producer_thread in producer_threads
+		{
+			producer_thread.join().unwrap();
+		}
+	}
+}
@@ -0,0 +1,111 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Tests of `RingBufferProducerWriter` and `RingBufferConsumerReader`.
+
+
+#![allow(non_upper_case_globals)]
+
+
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+
+
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+use ::std::io::BufRead;
+use ::std::io::ErrorKind;
+use ::std::io::Read;
+use ::std::io::Write;
+
+
+#[test]
+fn written_bytes_are_only_read_once_flushed()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(64, 1);
+	let mut writer = ring_buffer_producers[0].writer();
+	let mut reader = ring_buffer_consumer.reader();
+
+	writer.write_all(b"hello").unwrap();
+	assert_eq!(reader.fill_buf().unwrap_err().kind(), ErrorKind::WouldBlock);
+
+	writer.flush().unwrap();
+	let mut buf = [0; 16];
+	assert_eq!(reader.read(&mut buf).unwrap(), 5);
+	assert_eq!(&buf[.. 5], b"hello");
+	assert_eq!(reader.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+}
+
+#[test]
+fn consume_releases_exactly_the_bytes_consumed()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(64, 1);
+	ring_buffer_producers[0].writer().write_all(b"abcdef").unwrap();
+
+	let mut reader = ring_buffer_consumer.reader();
+	assert_eq!(reader.fill_buf().unwrap(), b"abcdef");
+	reader.consume(2);
+	assert_eq!(reader.fill_buf().unwrap(), b"cdef");
+	drop(reader);
+
+	let mut reader = ring_buffer_consumer.reader();
+	assert_eq!(reader.fill_buf().unwrap(), b"cdef");
+}
+
+#[test]
+fn a_write_fails_with_would_block_once_the_ring_buffer_is_full()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(16, 1);
+	let mut writer = ring_buffer_producers[0].writer();
+
+	assert_eq!(writer.write(&[1; 8]).unwrap(), 8);
+	writer.flush().unwrap();
+	assert_eq!(writer.write(&[2; 32]).unwrap(), 7);
+	assert_eq!(writer.write(&[3; 32]).unwrap_err().kind(), ErrorKind::WouldBlock);
+	drop(writer);
+
+	let mut reader = ring_buffer_consumer.reader();
+	let mut buf = [0; 32];
+	assert_eq!(reader.read(&mut buf).unwrap(), 15);
+	assert_eq!(&buf[.. 15], &[1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2]);
+}
+
+#[test]
+fn a_write_takes_all_of_the_contiguous_free_space()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(16, 1);
+	ring_buffer_producers[0].writer().write_all(&[1; 13]).unwrap();
+	assert_eq!(ring_buffer_consumer.reader().read(&mut [0; 13]).unwrap(), 13);
+
+	// Only 3 are free up to the end, so it wraps-around to use the 12 at the beginning.
+	assert_eq!(ring_buffer_producers[0].writer().write(&[2; 32]).unwrap(), 12);
+}
+
+#[test]
+fn reading_waits_for_an_owned_reservation_which_outlives_its_producer()
+{
+	let (mut ring_buffer_consumer, mut ring_buffer_producers) = RingBuffer::<u8>::new(64, 1);
+	let mut owned_reservation = ring_buffer_producers[0].acquire_owned(3).unwrap();
+	drop(ring_buffer_producers.pop());
+
+	let mut reader = ring_buffer_consumer.reader();
+	let mut buf = [0; 8];
+	assert_eq!(reader.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+
+	owned_reservation.copy_from_slice(b"abc");
+	owned_reservation.commit();
+	assert_eq!(reader.read(&mut buf).unwrap(), 3);
+	assert_eq!(&buf[.. 3], b"abc");
+	assert_eq!(reader.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn reading_reaches_end_of_file_once_every_producer_has_been_dropped()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(64, 2);
+	writeln!(ring_buffer_producers[0].writer(), "first").unwrap();
+	write!(ring_buffer_producers[1].writer(), "second\nthird").unwrap();
+	drop(ring_buffer_producers);
+
+	let lines: Vec<String> = ring_buffer_consumer.reader().lines().map(Result::unwrap).collect();
+	assert_eq!(lines, vec!["first", "second", "third"]);
+}
@@ -0,0 +1,256 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Model checks the acquire / produce / consume / release protocol with `loom`.
+//!
+//! Run with `RUSTFLAGS="--cfg loom" cargo test --release --test loom`.
+//!
+//! Each producer sends messages tagged with its index and a sequence number; the consumer checks that no message is lost, duplicated or observed before it was produced (in which case its sequence number would be stale), and that each producer's messages arrive in order.
+//!
+//! These models are not exhaustive.
+//! Each is bounded by a `preemption_bound`, so `loom` only explores the schedules which preempt a thread at most that many times (threads can still switch any number of times when they yield or block).
+//! The bounds are chosen so that each model finishes in a few minutes at most in release mode; the models with three producers only receive the messages once all the producers have finished.
+//! A bug which needs more preemptions than the bound to show itself will not be found.
+//!
+//! Scenarios covered are a burst exceeding the end of the ring buffer and wrapping around, a burst fitting exactly to the end, the consumer catching up to the end, a producer with two reservations produced out of order, and out of order completion (`RingBuffer::new_with_out_of_order_completion()`).
+//! Not covered are three producers racing a concurrent consumer, `SharedMemoryRingBuffer` and its consumer hardening, and the stall watchdog.
+
+
+#![allow(non_upper_case_globals)]
+#![cfg(loom)]
+
+
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+extern crate loom;
+
+
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+use ::loom::model::Builder;
+use ::loom::thread::spawn;
+use ::loom::thread::yield_now;
+
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+struct Message
+{
+	producer_index: usize,
+	sequence_number: usize,
+}
+
+/// Each execution is long (filling the ring buffer, wrapping around, and dropping the remaining data walks every hand several times), so the default of 1,000 is too low.
+const MaximumBranches: usize = 10_000;
+
+const Unwritten: Message = Message
+{
+	producer_index: ::std::usize::MAX,
+	sequence_number: ::std::usize::MAX,
+};
+
+fn produce_messages(ring_buffer_producer: RingBufferProducer<Message>, producer_index: usize, bursts: &'static [usize])
+{
+	let mut sequence_number = 0;
+	for &burst in bursts
+	{
+		// `acquire()` can fail spuriously if it checks a stale `next` offset against a newer `written` offset; retrying observes both afresh.
+		// Scenarios are sized so that there is always space, so this does not need to yield.
+		let mut slice_guard = loop
+		{
+			if let Ok(slice_guard) = ring_buffer_producer.acquire(burst)
+			{
+				break slice_guard
+			}
+		};
+		// `loom` only switches threads at atomic operations and yields; without this the consumer could never run whilst the reservation is unwritten.
+		yield_now();
+		for slot in slice_guard.iter_mut()
+		{
+			*slot = Message
+			{
+				producer_index,
+				sequence_number,
+			};
+			sequence_number += 1;
+		}
+	}
+}
+
+fn consume_messages(ring_buffer_consumer: &mut RingBufferConsumer<Message>, messages_per_producer: &[usize])
+{
+	let total: usize = messages_per_producer.iter().sum();
+	let mut next_sequence_numbers = vec![0; messages_per_producer.len()];
+
+	let mut received = 0;
+	while received != total
+	{
+		let mut slice_guard = ring_buffer_consumer.consume();
+		if slice_guard.is_empty()
+		{
+			drop(slice_guard);
+			yield_now();
+			continue
+		}
+
+		for slot in slice_guard.as_mut_slice().iter_mut()
+		{
+			let message = *slot;
+			assert_ne!(message, Unwritten, "observed a message before it was produced");
+			let next_sequence_number = &mut next_sequence_numbers[message.producer_index];
+			assert_eq!(message.sequence_number, *next_sequence_number, "lost, duplicated or reordered message {:?}", message);
+			*next_sequence_number += 1;
+
+			// Poison the slot so that a stale read on the next lap is detected.
+			*slot = Unwritten;
+		}
+
+		let length = slice_guard.len();
+		slice_guard.release(length);
+		received += length;
+	}
+
+	assert_eq!(&next_sequence_numbers[..], messages_per_producer);
+	assert!(ring_buffer_consumer.consume().is_empty());
+}
+
+/// Makes every slot detectably unwritten, then moves the `next` and `written` hands to `start_offset`.
+///
+/// As producers can not catch up with the consumer, this takes several acquisitions.
+fn prepare(ring_buffer_consumer: &mut RingBufferConsumer<Message>, ring_buffer_producer: &RingBufferProducer<Message>, capacity: usize, start_offset: usize)
+{
+	for &count in &[capacity - 1, 1, start_offset]
+	{
+		if count == 0
+		{
+			continue
+		}
+
+		for slot in ring_buffer_producer.acquire(count).unwrap().iter_mut()
+		{
+			*slot = Unwritten;
+		}
+		ring_buffer_consumer.drain_with(|batch| batch.len());
+	}
+}
+
+/// Producers send `bursts_per_producer` concurrently with the consumer receiving them, starting with the `next` and `written` hands at `start_offset`.
+///
+/// Scenarios must be sized so that producers always have space to acquire (ie the total of all bursts, plus any space wasted at the end of the ring buffer by a wrap-around, is less than `capacity`).
+/// Otherwise, a producer waiting for space whilst the consumer waits for data would let `loom` explore schedules which starve the other producers forever.
+fn model(capacity: usize, start_offset: usize, bursts_per_producer: &'static [&'static [usize]], preemption_bound: usize)
+{
+	model_with(RingBuffer::new_with_maximum_reservations, true, capacity, start_offset, bursts_per_producer, preemption_bound)
+}
+
+/// As `model()`, but the consumer only receives the messages once every producer has finished, so that `loom` only explores the schedules of the producers racing each other.
+///
+/// A consumer polling concurrently with three producers has too many schedules to explore with any preemptions.
+fn model_consuming_afterwards(capacity: usize, start_offset: usize, bursts_per_producer: &'static [&'static [usize]], preemption_bound: usize)
+{
+	model_with(RingBuffer::new_with_maximum_reservations, false, capacity, start_offset, bursts_per_producer, preemption_bound)
+}
+
+/// As `model()`, but creating the ring buffer with `new`, which is given one reservation for each producer, and only receiving concurrently if `consume_concurrently`.
+fn model_with(new: fn(usize, &[usize]) -> (RingBufferConsumer<Message>, Vec<RingBufferProducer<Message>>), consume_concurrently: bool, capacity: usize, start_offset: usize, bursts_per_producer: &'static [&'static [usize]], preemption_bound: usize)
+{
+	let mut builder = Builder::new();
+	builder.max_branches = MaximumBranches;
+	builder.preemption_bound = Some(preemption_bound);
+	builder.check(move ||
+	{
+		let (mut ring_buffer_consumer, ring_buffer_producers) = new(capacity, &vec![1; bursts_per_producer.len()]);
+		prepare(&mut ring_buffer_consumer, &ring_buffer_producers[0], capacity, start_offset);
+
+		let messages_per_producer: Vec<usize> = bursts_per_producer.iter().map(|bursts| bursts.iter().sum()).collect();
+
+		let producer_threads: Vec<_> = ring_buffer_producers.into_iter().zip(bursts_per_producer.iter()).enumerate().map(|(producer_index, (ring_buffer_producer, &bursts))|
+		{
+			spawn(move || produce_messages(ring_buffer_producer, producer_index, bursts))
+		}).collect();
+
+		if consume_concurrently
+		{
+			consume_messages(&mut ring_buffer_consumer, &messages_per_producer);
+		}
+
+		for producer_thread in producer_threads
+		{
+			producer_thread.join().unwrap();
+		}
+
+		if !consume_concurrently
+		{
+			consume_messages(&mut ring_buffer_consumer, &messages_per_producer);
+		}
+	});
+}
+
+#[test]
+fn two_producers_single_messages_wrap_around_exactly_at_end()
+{
+	model(4, 2, &[&[1, 1], &[1]], 1)
+}
+
+#[test]
+fn one_producer_burst_exceeds_end_and_wraps_around()
+{
+	model(6, 5, &[&[2, 1]], 2)
+}
+
+#[test]
+fn two_producers_bursts_exceed_end_and_wrap_around()
+{
+	model(8, 6, &[&[1, 2], &[2]], 1)
+}
+
+#[test]
+fn two_producers_bursts_consumer_catching_up_to_end()
+{
+	model(6, 3, &[&[2], &[2]], 2)
+}
+
+#[test]
+fn three_producers_single_messages_wrap_around_consumed_afterwards()
+{
+	model_consuming_afterwards(4, 3, &[&[1], &[1], &[1]], 3)
+}
+
+#[test]
+fn three_producers_mixed_bursts_wrap_around_consumed_afterwards()
+{
+	model_consuming_afterwards(6, 4, &[&[1], &[1], &[2]], 3)
+}
+
+#[test]
+fn two_producers_completing_out_of_order_wrap_around()
+{
+	model_with(RingBuffer::new_with_out_of_order_completion, true, 6, 4, &[&[2], &[1]], 2)
+}
+
+#[test]
+fn one_producer_with_two_reservations_produced_out_of_order()
+{
+	let mut builder = Builder::new();
+	builder.max_branches = MaximumBranches;
+	builder.preemption_bound = Some(2);
+	builder.check(||
+	{
+		let (mut ring_buffer_consumer, mut ring_buffer_producers) = RingBuffer::new_with_maximum_reservations(5, &[2]);
+		let ring_buffer_producer = ring_buffer_producers.pop().unwrap();
+		prepare(&mut ring_buffer_consumer, &ring_buffer_producer, 5, 3);
+
+		let producer_thread = spawn(move ||
+		{
+			let mut first = ring_buffer_producer.acquire_owned(1).unwrap();
+			let mut second = ring_buffer_producer.acquire_owned(2).unwrap();
+			first[0] = Message { producer_index: 0, sequence_number: 0 };
+			second[0] = Message { producer_index: 0, sequence_number: 1 };
+			second[1] = Message { producer_index: 0, sequence_number: 2 };
+			second.commit();
+			first.commit();
+		});
+
+		consume_messages(&mut ring_buffer_consumer, &[3]);
+
+		producer_thread.join().unwrap();
+	});
+}
@@ -0,0 +1,320 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Small tests of every way of getting at the memory of the ring buffer, sized to run under Miri.
+//!
+//! Run with `cargo miri test --test miri` (Stacked Borrows) and `MIRIFLAGS=-Zmiri-tree-borrows cargo miri test --test miri` (Tree Borrows), and `cargo miri test --doc` for the doctests which check that the consumer can not be re-entered.
+//!
+//! Items own heap memory so that Miri also detects leaks, double drops and reads of uninitialized or dropped items.
+
+
+#![allow(non_upper_case_globals)]
+
+
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+
+
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+use ::std::slice::from_raw_parts_mut;
+use ::std::sync::Arc;
+use ::std::sync::atomic::AtomicUsize;
+use ::std::sync::atomic::Ordering::SeqCst;
+use ::std::thread::spawn;
+use ::std::thread::yield_now;
+
+
+/// Counts the number of times it is dropped.
+#[derive(Debug)]
+struct Counted(Box<usize>, Arc<AtomicUsize>);
+
+impl Drop for Counted
+{
+	fn drop(&mut self)
+	{
+		self.1.fetch_add(1, SeqCst);
+	}
+}
+
+/// Produces `values` in one burst; the slice acquired is uninitialized, so the values are written rather than assigned.
+fn produce<T>(ring_buffer_producer: &RingBufferProducer<T>, values: Vec<T>)
+{
+	let mut slice_guard = ring_buffer_producer.acquire(values.len()).unwrap();
+	for (index, value) in values.into_iter().enumerate()
+	{
+		unsafe { slice_guard.as_mut_ptr().add(index).write(value) }
+	}
+}
+
+fn strings(range: ::std::ops::Range<usize>) -> Vec<String>
+{
+	range.map(|value| value.to_string()).collect()
+}
+
+#[test]
+fn every_producer_reservation_can_be_written_and_consumed()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<String>::new_with_maximum_reservations(16, &[2, 1]);
+
+	{
+		let mut first = ring_buffer_producers[0].acquire(2).unwrap();
+		let mut second = ring_buffer_producers[0].acquire(3).unwrap();
+		let mut third = ring_buffer_producers[1].acquire(1).unwrap();
+		assert!(ring_buffer_producers[0].acquire(1).is_err());
+
+		unsafe
+		{
+			third.as_mut_ptr().write("5".to_string());
+			second.as_mut_ptr().write("2".to_string());
+			second.as_mut_ptr().add(1).write("3".to_string());
+			second.as_mut_ptr().add(2).write("4".to_string());
+			first.as_mut_ptr().write("0".to_string());
+			first.as_mut_ptr().add(1).write("1".to_string());
+		}
+	}
+
+	let consumed: Vec<String> = ring_buffer_consumer.consume().collect();
+	assert_eq!(consumed, strings(0 .. 6));
+}
+
+#[test]
+fn consume_with_passes_both_segments_after_a_wrap_around()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::new(8, 1);
+
+	produce(&ring_buffer_producers[0], strings(0 .. 4));
+	assert_eq!(ring_buffer_consumer.consume().count(), 4);
+
+	produce(&ring_buffer_producers[0], strings(4 .. 7));
+	produce(&ring_buffer_producers[0], strings(7 .. 10));
+
+	let handled = ring_buffer_consumer.consume_with(|batch|
+	{
+		{
+			let (first, second) = batch.as_slices();
+			assert_eq!(first, &strings(4 .. 7)[..]);
+			assert_eq!(second, &strings(7 .. 10)[..]);
+		}
+		for value in batch.iter_mut()
+		{
+			value.push('!');
+		}
+		assert_eq!(batch.get(5).map(String::as_str), Some("9!"));
+		5
+	});
+	assert_eq!(handled, 5);
+
+	let remaining: Vec<String> = ring_buffer_consumer.consume().collect();
+	assert_eq!(remaining, vec!["9!".to_string()]);
+}
+
+/// A handler can not consume again, and so be given the same items twice, as `consume_with()` borrows the consumer mutably; the doctest of `consume_with()` checks that this does not compile (run it under Miri with `cargo miri test --doc`).
+#[test]
+fn items_handled_by_consume_with_and_drain_with_are_dropped_exactly_once()
+{
+	let drops = Arc::new(AtomicUsize::new(0));
+	let counted = |value| Counted(Box::new(value), drops.clone());
+
+	{
+		let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::new(8, 1);
+		produce(&ring_buffer_producers[0], vec![counted(0), counted(1), counted(2), counted(3)]);
+
+		assert_eq!(ring_buffer_consumer.consume_with(|_batch| 2), 2);
+		assert_eq!(drops.load(SeqCst), 2);
+
+		assert_eq!(ring_buffer_consumer.drain_with(|batch| batch.len()), 2);
+		assert_eq!(drops.load(SeqCst), 4);
+
+		produce(&ring_buffer_producers[0], vec![counted(4), counted(5)]);
+		assert_eq!(ring_buffer_consumer.consume_with(|_batch| 0), 0);
+		assert_eq!(drops.load(SeqCst), 4);
+	}
+
+	assert_eq!(drops.load(SeqCst), 6);
+}
+
+#[test]
+fn unconsumed_items_are_dropped_exactly_once()
+{
+	let drops = Arc::new(AtomicUsize::new(0));
+	let counted = |value| Counted(Box::new(value), drops.clone());
+
+	{
+		let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::new(8, 2);
+
+		produce(&ring_buffer_producers[0], vec![counted(0), counted(1), counted(2), counted(3)]);
+		produce(&ring_buffer_producers[1], vec![counted(4), counted(5)]);
+
+		{
+			let mut slice_guard = ring_buffer_consumer.consume();
+			assert_eq!(*slice_guard.next().unwrap().0, 0);
+			slice_guard.discard(2);
+			assert_eq!(*slice_guard.peek().unwrap().0, 3);
+		}
+		assert_eq!(drops.load(SeqCst), 3);
+
+		// Fill to the end and then wrap-around, so that the items left are in two segments.
+		produce(&ring_buffer_producers[0], vec![counted(6), counted(7)]);
+		produce(&ring_buffer_producers[1], vec![counted(8), counted(9)]);
+		assert_eq!(drops.load(SeqCst), 3);
+	}
+
+	assert_eq!(drops.load(SeqCst), 10);
+}
+
+#[test]
+fn items_can_be_moved_out()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::new(16, 1);
+
+	let mut populate_with = strings(0 .. 10);
+	ring_buffer_producers[0].repeatedly_acquire_and_try_to_populate(&mut populate_with);
+	assert!(populate_with.is_empty());
+
+	let mut slice_guard = ring_buffer_consumer.consume();
+	assert_eq!(slice_guard.len(), 10);
+
+	assert_eq!(&*slice_guard.move_out(3), &strings(0 .. 3)[..]);
+
+	let mut into = strings(100 .. 103);
+	slice_guard.move_into_slice(&mut into);
+	assert_eq!(into, strings(3 .. 6));
+
+	let mut into_unsafe: Vec<String> = Vec::with_capacity(2);
+	unsafe
+	{
+		slice_guard.move_into_slice_unsafe(from_raw_parts_mut(into_unsafe.as_mut_ptr(), 2));
+		into_unsafe.set_len(2);
+	}
+	assert_eq!(into_unsafe, strings(6 .. 8));
+
+	slice_guard.as_mut_slice()[0].push('!');
+	slice_guard.release(1);
+	assert_eq!(slice_guard.as_slice(), &strings(9 .. 10)[..]);
+}
+
+#[test]
+fn owned_reservations_and_batches_can_be_used_on_other_threads()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<String>::new(8, 1);
+
+	let mut owned_reservation = ring_buffer_producers[0].acquire_owned(3).unwrap();
+	drop(ring_buffer_producers);
+	spawn(move ||
+	{
+		for (index, value) in strings(0 .. 3).into_iter().enumerate()
+		{
+			unsafe { owned_reservation.as_mut_ptr().add(index).write(value) }
+		}
+		owned_reservation.commit()
+	}).join().unwrap();
+
+	let mut owned_batch = ring_buffer_consumer.consume_owned().unwrap();
+	assert!(ring_buffer_consumer.consume_owned().is_none());
+	assert_eq!(owned_batch.next(), Some("0".to_string()));
+	drop(ring_buffer_consumer);
+
+	let remaining = spawn(move ||
+	{
+		owned_batch[0].push('!');
+		owned_batch.collect::<Vec<_>>()
+	}).join().unwrap();
+	assert_eq!(remaining, vec!["1!".to_string(), "2".to_string()]);
+}
+
+#[test]
+fn over_aligned_items_are_aligned()
+{
+	#[repr(align(64))]
+	#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+	struct OverAligned(u8);
+
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::new(4, 3);
+
+	for (index, ring_buffer_producer) in ring_buffer_producers.iter().enumerate()
+	{
+		let mut slice_guard = ring_buffer_producer.acquire(1).unwrap();
+		assert_eq!(slice_guard.as_ptr() as usize % 64, 0);
+		slice_guard[0] = OverAligned(index as u8);
+	}
+
+	assert_eq!(ring_buffer_consumer.consume().collect::<Vec<_>>(), vec![OverAligned(0), OverAligned(1), OverAligned(2)]);
+}
+
+#[test]
+fn concurrent_producers_and_consumer()
+{
+	const NumberOfProducers: usize = 2;
+	const MessagesPerProducer: usize = 20;
+
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<(usize, String)>::new(8, NumberOfProducers);
+
+	let producer_threads: Vec<_> = ring_buffer_producers.into_iter().enumerate().map(|(producer_index, ring_buffer_producer)|
+	{
+		spawn(move ||
+		{
+			let mut sequence_number = 0;
+			while sequence_number != MessagesPerProducer
+			{
+				match ring_buffer_producer.acquire(2)
+				{
+					Err(()) => yield_now(),
+
+					Ok(mut slice_guard) => for index in 0 .. 2
+					{
+						unsafe { slice_guard.as_mut_ptr().add(index).write((producer_index, sequence_number.to_string())) };
+						sequence_number += 1;
+					},
+				}
+			}
+		})
+	}).collect();
+
+	let mut next_sequence_numbers = [0; NumberOfProducers];
+	while next_sequence_numbers.iter().any(|&next_sequence_number| next_sequence_number != MessagesPerProducer)
+	{
+		let mut consumed = 0;
+		for (producer_index, sequence_number) in ring_buffer_consumer.consume()
+		{
+			assert_eq!(sequence_number, next_sequence_numbers[producer_index].to_string());
+			next_sequence_numbers[producer_index] += 1;
+			consumed += 1;
+		}
+		if consumed == 0
+		{
+			yield_now()
+		}
+	}
+
+	for producer_thread in producer_threads
+	{
+		producer_thread.join().unwrap();
+	}
+}
+
+#[test]
+fn owned_reservations_and_batches_can_be_borrowed_more_than_once()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<String>::new(4, 1);
+
+	let mut owned_reservation = ring_buffer_producers[0].acquire_owned(2).unwrap();
+	for (index, value) in strings(0 .. 2).into_iter().enumerate()
+	{
+		unsafe { owned_reservation.as_mut_ptr().add(index).write(value) }
+	}
+	{
+		let first = &*owned_reservation;
+		let second = &*owned_reservation;
+		assert_eq!(first[0], "0");
+		assert_eq!(second[1], "1");
+		assert_eq!(first[1], "1");
+	}
+	owned_reservation.commit();
+
+	let owned_batch = ring_buffer_consumer.consume_owned().unwrap();
+	let first = &*owned_batch;
+	let second = &*owned_batch;
+	assert_eq!(first[0], "0");
+	assert_eq!(second[1], "1");
+	assert_eq!(first[1], "1");
+}
@@ -0,0 +1,172 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Tests of `RingBuffer::new_with_out_of_order_completion()`.
+
+
+#![allow(non_upper_case_globals)]
+
+
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+
+
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+use ::std::thread::spawn;
+use ::std::thread::yield_now;
+
+
+fn produce(ring_buffer_producer: &RingBufferProducer<u8>, value: u8, count: usize)
+{
+	let mut producer_guard = ring_buffer_producer.acquire(count).unwrap();
+	for item in producer_guard.iter_mut()
+	{
+		*item = value
+	}
+}
+
+fn consume_all(ring_buffer_consumer: &mut RingBufferConsumer<u8>) -> Vec<u8>
+{
+	let mut slice_guard = ring_buffer_consumer.consume();
+	let items = slice_guard.as_slice().to_vec();
+	let count = slice_guard.len();
+	slice_guard.release(count);
+	items
+}
+
+#[test]
+fn a_slow_producer_does_not_hold_back_the_items_of_others()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new_with_out_of_order_completion(16, &[1, 1]);
+
+	let mut slow = ring_buffer_producers[0].acquire(4).unwrap();
+	produce(&ring_buffer_producers[1], 2, 3);
+
+	assert_eq!(consume_all(&mut ring_buffer_consumer), vec![2, 2, 2]);
+	assert_eq!(consume_all(&mut ring_buffer_consumer), vec![]);
+
+	for item in slow.iter_mut()
+	{
+		*item = 1
+	}
+	drop(slow);
+
+	assert_eq!(consume_all(&mut ring_buffer_consumer), vec![1, 1, 1, 1]);
+	assert_eq!(consume_all(&mut ring_buffer_consumer), vec![]);
+}
+
+#[test]
+fn the_items_of_each_producer_are_consumed_in_order()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new_with_out_of_order_completion(16, &[1, 2]);
+
+	let slow = ring_buffer_producers[0].acquire(2).unwrap();
+	let mut first = ring_buffer_producers[1].acquire(2).unwrap();
+	produce(&ring_buffer_producers[1], 2, 2);
+
+	assert_eq!(consume_all(&mut ring_buffer_consumer), vec![]);
+
+	for item in first.iter_mut()
+	{
+		*item = 1
+	}
+	drop(first);
+
+	assert_eq!(consume_all(&mut ring_buffer_consumer), vec![1, 1]);
+	assert_eq!(consume_all(&mut ring_buffer_consumer), vec![2, 2]);
+	assert_eq!(consume_all(&mut ring_buffer_consumer), vec![]);
+
+	drop(slow);
+	assert_eq!(consume_all(&mut ring_buffer_consumer).len(), 2);
+}
+
+#[test]
+fn space_is_only_released_once_everything_before_it_has_been()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new_with_out_of_order_completion(8, &[1, 1]);
+
+	let slow = ring_buffer_producers[0].acquire(2).unwrap();
+	produce(&ring_buffer_producers[1], 2, 5);
+	assert_eq!(consume_all(&mut ring_buffer_consumer), vec![2, 2, 2, 2, 2]);
+
+	assert!(ring_buffer_producers[1].acquire(1).is_err(), "the space of the released items is still behind the slow reservation");
+	assert_eq!(ring_buffer_consumer.stalled_producer().map(|stalled_producer| (stalled_producer.producer_index, stalled_producer.offset, stalled_producer.items_held_back)), Some((0, 0, 7)));
+
+	drop(slow);
+	assert_eq!(consume_all(&mut ring_buffer_consumer).len(), 2);
+	assert!(ring_buffer_consumer.stalled_producer().is_none());
+
+	produce(&ring_buffer_producers[1], 3, 6);
+	assert_eq!(consume_all(&mut ring_buffer_consumer), vec![3, 3, 3, 3, 3, 3]);
+}
+
+#[test]
+fn items_not_released_are_consumed_again()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new_with_out_of_order_completion(16, &[1, 1]);
+
+	let slow = ring_buffer_producers[0].acquire(2).unwrap();
+	{
+		let mut producer_guard = ring_buffer_producers[1].acquire(4).unwrap();
+		producer_guard.copy_from_slice(&[1, 2, 3, 4]);
+	}
+
+	let (mut slice_guard, consume_status) = ring_buffer_consumer.consume_detailed();
+	assert_eq!(consume_status, ConsumeStatus::Ready(4));
+	slice_guard.release(1);
+	drop(slice_guard);
+
+	assert!(ring_buffer_producers[1].acquire(1).is_err(), "the reservation is in use until all of its items have been released");
+	assert_eq!(consume_all(&mut ring_buffer_consumer), vec![2, 3, 4]);
+	assert!(ring_buffer_producers[1].acquire(1).is_ok());
+
+	drop(slow);
+}
+
+#[test]
+fn the_items_of_each_of_several_threads_are_consumed_once_and_in_order()
+{
+	const NumberOfProducers: usize = 4;
+	const ItemsPerProducer: u64 = 2_000;
+
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u64>::new_with_out_of_order_completion(64, &[1; NumberOfProducers]);
+
+	let producer_threads: Vec<_> = ring_buffer_producers.into_iter().enumerate().map(|(producer_index, ring_buffer_producer)| spawn(move ||
+	{
+		let mut sequence = 0;
+		while sequence != ItemsPerProducer
+		{
+			let count = ((sequence % 7) + 1).min(ItemsPerProducer - sequence);
+			match ring_buffer_producer.acquire(count as usize)
+			{
+				Err(()) => yield_now(),
+
+				Ok(mut producer_guard) => for item in producer_guard.iter_mut()
+				{
+					*item = ((producer_index as u64) << 32) | sequence;
+					sequence += 1;
+				},
+			}
+		}
+	})).collect();
+
+	let mut next_sequences = [0; NumberOfProducers];
+	while next_sequences.iter().any(|&next_sequence| next_sequence != ItemsPerProducer)
+	{
+		let mut slice_guard = ring_buffer_consumer.consume();
+		for &item in slice_guard.as_slice()
+		{
+			let producer_index = (item >> 32) as usize;
+			assert_eq!(item & 0xFFFF_FFFF, next_sequences[producer_index], "item of producer {} out of order, lost or duplicated", producer_index);
+			next_sequences[producer_index] += 1;
+		}
+		let count = slice_guard.len();
+		slice_guard.release(count);
+	}
+
+	for producer_thread in producer_threads
+	{
+		producer_thread.join().unwrap()
+	}
+	assert_eq!(ring_buffer_consumer.consume().len(), 0);
+}
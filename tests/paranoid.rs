@@ -0,0 +1,55 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Tests of the `paranoid` feature.
+
+
+#![allow(non_upper_case_globals)]
+#![cfg(feature = "paranoid")]
+
+
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+
+
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+use ::std::panic::AssertUnwindSafe;
+use ::std::panic::catch_unwind;
+
+
+fn violation_message<F: FnOnce()>(violate: F) -> String
+{
+	let payload = catch_unwind(AssertUnwindSafe(violate)).expect_err("violating an invariant should panic");
+	match payload.downcast::<String>()
+	{
+		Ok(message) => *message,
+		Err(_) => panic!("the panic payload should be a formatted message"),
+	}
+}
+
+#[test]
+fn acquiring_nothing_panics_with_a_dump()
+{
+	let (_ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(4, 1);
+
+	let message = violation_message(|| drop(ring_buffer_producers[0].acquire(0)));
+
+	assert!(message.starts_with("ring buffer invariant violated: length can not be zero\n"), "{}", message);
+	assert!(message.contains("\ncapacity: 4\nnext: 0 "), "{}", message);
+	assert!(message.contains("\nwritten: 0\n"), "{}", message);
+	assert!(message.contains("\nslots: "), "{}", message);
+}
+
+#[test]
+fn acquiring_more_than_the_capacity_panics_with_a_dump()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(4, 1);
+	drop(ring_buffer_producers[0].acquire(3).unwrap());
+	ring_buffer_consumer.consume().release(3);
+
+	let message = violation_message(|| drop(ring_buffer_producers[0].acquire(5)));
+
+	assert!(message.starts_with("ring buffer invariant violated: count '5' exceeds self.capacity '4'\n"), "{}", message);
+	assert!(message.contains("\ncapacity: 4\nnext: 3 "), "{}", message);
+	assert!(message.contains("\nwritten: 3\n"), "{}", message);
+}
@@ -0,0 +1,97 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Tests of `MessageRingBuffer` and `#[derive(RingMessage)]`.
+//!
+//! Run with `cargo test --features derive --test ring_message`.
+
+
+#![allow(non_upper_case_globals)]
+#![cfg(feature = "derive")]
+
+
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+
+
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+use ::std::mem::size_of;
+use ::std::rc::Rc;
+
+
+#[derive(RingMessage, Debug, PartialEq)]
+enum Message
+{
+	Ping,
+	Move(i32, i32),
+	Text { text: String, urgent: bool },
+	Bulk([u64; 16]),
+}
+
+#[derive(RingMessage, Debug, PartialEq)]
+enum Generic<T>
+{
+	Value(T),
+	Nothing,
+}
+
+fn consume_all<M: RingMessage>(message_ring_buffer_consumer: &mut MessageRingBufferConsumer<M>) -> Vec<M>
+{
+	message_ring_buffer_consumer.consume().collect()
+}
+
+#[test]
+fn messages_of_every_variant_are_consumed_in_order_by_value()
+{
+	let (mut message_ring_buffer_consumer, message_ring_buffer_producers) = MessageRingBuffer::<Message>::new(1024, 1);
+
+	message_ring_buffer_producers[0].produce(Message::Ping).unwrap();
+	message_ring_buffer_producers[0].produce(Message::Move(-1, 2)).unwrap();
+	message_ring_buffer_producers[0].produce(Message::Text { text: "hello".to_string(), urgent: true }).unwrap();
+	message_ring_buffer_producers[0].produce(Message::Bulk([7; 16])).unwrap();
+
+	let mut consumer_guard = message_ring_buffer_consumer.consume();
+	match consumer_guard.next()
+	{
+		Some(Message::Ping) => (),
+		unexpected => panic!("unexpected {:?}", unexpected),
+	}
+	assert_eq!(consumer_guard.collect::<Vec<_>>(), vec![Message::Move(-1, 2), Message::Text { text: "hello".to_string(), urgent: true }, Message::Bulk([7; 16])]);
+
+	assert_eq!(consume_all(&mut message_ring_buffer_consumer), Vec::new());
+}
+
+#[test]
+fn a_message_uses_only_the_space_of_its_own_variant()
+{
+	assert_eq!(MessageRingBuffer::<Message>::message_size(&Message::Ping), ByteRingBuffer::RecordAlignment);
+	assert_eq!(MessageRingBuffer::<Message>::message_size(&Message::Move(1, 2)), 2 * ByteRingBuffer::RecordAlignment);
+	assert_eq!(MessageRingBuffer::<Message>::message_size(&Message::Bulk([0; 16])), ByteRingBuffer::record_size(size_of::<[u64; 16]>()));
+	assert!(size_of::<Message>() > size_of::<[u64; 16]>());
+
+	let (_message_ring_buffer_consumer, message_ring_buffer_producers) = MessageRingBuffer::<Message>::new(64, 1);
+	assert_eq!(message_ring_buffer_producers[0].produce(Message::Bulk([0; 16])), Err(Message::Bulk([0; 16])));
+	assert_eq!(message_ring_buffer_producers[0].produce(Message::Move(3, 4)), Ok(()));
+}
+
+#[test]
+fn messages_not_moved_out_are_returned_to_the_queue_and_never_dropped_twice()
+{
+	let (mut message_ring_buffer_consumer, message_ring_buffer_producers) = MessageRingBuffer::<Generic<Rc<u32>>>::new(256, 2);
+	let shared = Rc::new(5);
+
+	message_ring_buffer_producers[0].produce(Generic::Value(shared.clone())).unwrap();
+	message_ring_buffer_producers[1].produce(Generic::Nothing).unwrap();
+	message_ring_buffer_producers[0].produce(Generic::Value(shared.clone())).unwrap();
+	assert_eq!(Rc::strong_count(&shared), 3);
+
+	{
+		let mut consumer_guard = message_ring_buffer_consumer.consume();
+		assert_eq!(consumer_guard.next(), Some(Generic::Value(shared.clone())));
+		assert!(!consumer_guard.is_empty());
+	}
+	assert_eq!(Rc::strong_count(&shared), 2);
+
+	assert_eq!(consume_all(&mut message_ring_buffer_consumer), vec![Generic::Nothing, Generic::Value(shared.clone())]);
+	assert_eq!(Rc::strong_count(&shared), 1);
+}
@@ -0,0 +1,247 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! A port of the unit tests in the original C code's `t_ringbuf.c`.
+//!
+//! The C code returns offsets into the ring buffer; here they are recovered from the slices' addresses relative to the first slice acquired, which is always at offset zero.
+
+
+#![allow(non_upper_case_globals)]
+
+
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+
+
+mod common;
+
+
+use ::common::XorShift;
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+
+
+const MaximumWorkers: usize = 2;
+
+fn offset(slice: &[u8], zero: *const u8) -> usize
+{
+	slice.as_ptr() as usize - zero as usize
+}
+
+/// Acquires `count` bytes, returning their offset, and produces them.
+fn acquire_and_produce(ring_buffer_producer: &RingBufferProducer<u8>, count: usize, zero: *const u8) -> Option<usize>
+{
+	ring_buffer_producer.acquire(count).ok().map(|slice_guard| offset(&slice_guard, zero))
+}
+
+/// Consumes then releases everything ready, returning its length and offset.
+fn consume_and_release(ring_buffer_consumer: &mut RingBufferConsumer<u8>, zero: *const u8) -> (usize, usize)
+{
+	let mut slice_guard = ring_buffer_consumer.consume();
+	let length = slice_guard.len();
+	let offset = offset(slice_guard.as_slice(), zero);
+	slice_guard.release(length);
+	(length, offset)
+}
+
+#[test]
+fn wrap_around()
+{
+	const N: usize = 1000;
+
+	// Size `N`, but only `N - 1` can be produced at a time.
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::new(N, MaximumWorkers);
+	let ring_buffer_producer = &ring_buffer_producers[0];
+
+	// Produce `N / 2 + 1` and then attempt another `N / 2 - 1`.
+	let zero = ring_buffer_producer.acquire(N / 2 + 1).unwrap().as_ptr();
+	assert_eq!(acquire_and_produce(ring_buffer_producer, N / 2 - 1, zero), None);
+
+	// Consume `N / 2 + 1`.
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero), (N / 2 + 1, 0));
+
+	// All consumed, attempt `N / 2 + 1` now.
+	assert_eq!(acquire_and_produce(ring_buffer_producer, N / 2 + 1, zero), None);
+
+	// However, wrap-around can be successful with `N / 2`.
+	assert_eq!(acquire_and_produce(ring_buffer_producer, N / 2, zero), Some(0));
+
+	// Consume `N / 2`.
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero), (N / 2, 0));
+}
+
+#[test]
+fn multiple()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::new(3, MaximumWorkers);
+	let ring_buffer_producer = &ring_buffer_producers[0];
+
+	// Produce 2.
+	let zero = ring_buffer_producer.acquire(1).unwrap().as_ptr();
+	assert_eq!(acquire_and_produce(ring_buffer_producer, 1, zero), Some(1));
+	assert_eq!(acquire_and_produce(ring_buffer_producer, 1, zero), None);
+
+	// Consume 2.
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero), (2, 0));
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero).0, 0);
+
+	// Produce another 2 with wrap-around.
+	assert_eq!(acquire_and_produce(ring_buffer_producer, 2, zero), None);
+	assert_eq!(acquire_and_produce(ring_buffer_producer, 1, zero), Some(2));
+	assert_eq!(acquire_and_produce(ring_buffer_producer, 1, zero), Some(0));
+	assert_eq!(acquire_and_produce(ring_buffer_producer, 1, zero), None);
+
+	// Consume 1 at the end and 1 at the beginning.
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero), (1, 2));
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero), (1, 0));
+}
+
+#[test]
+fn overlap()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::new(10, MaximumWorkers);
+	let ring_buffer_producer_1 = &ring_buffer_producers[0];
+	let ring_buffer_producer_2 = &ring_buffer_producers[1];
+
+	// Producer 1: acquire 5.
+	// Consumer should fail.
+	let slice_guard_1 = ring_buffer_producer_1.acquire(5).unwrap();
+	let zero = slice_guard_1.as_ptr();
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero).0, 0);
+
+	// Producer 2: acquire 3.
+	// Consumer should still fail.
+	let slice_guard_2 = ring_buffer_producer_2.acquire(3).unwrap();
+	assert_eq!(offset(&slice_guard_2, zero), 5);
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero).0, 0);
+
+	// Producer 1: produce.
+	// Now the consumer can get 5.
+	drop(slice_guard_1);
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero), (5, 0));
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero).0, 0);
+
+	// Producer 1: acquire and produce 4, triggering wrap-around.
+	// Consumer should still fail.
+	let slice_guard_1 = ring_buffer_producer_1.acquire(4).unwrap();
+	assert_eq!(offset(&slice_guard_1, zero), 0);
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero).0, 0);
+	drop(slice_guard_1);
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero).0, 0);
+
+	// Finally, producer 2 produces.
+	// The consumer should get all of it.
+	drop(slice_guard_2);
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero), (3, 5));
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero), (4, 0));
+}
+
+#[test]
+fn consumer_catches_up_to_end()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::new(10, MaximumWorkers);
+	let ring_buffer_producer_1 = &ring_buffer_producers[0];
+	let ring_buffer_producer_2 = &ring_buffer_producers[1];
+
+	// Move the hands to 7.
+	let zero = ring_buffer_producer_1.acquire(7).unwrap().as_ptr();
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero), (7, 0));
+
+	// Producer 1 exceeds the end, so wraps-around leaving `end` at 7, but has not yet produced; producer 2 then acquires after it.
+	let slice_guard_1 = ring_buffer_producer_1.acquire(4).unwrap();
+	assert_eq!(offset(&slice_guard_1, zero), 0);
+	assert_eq!(acquire_and_produce(ring_buffer_producer_2, 2, zero), Some(4));
+
+	// The consumer has caught up with `end`, but must not wrap-around until producer 1 has produced.
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero).0, 0);
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero).0, 0);
+
+	drop(slice_guard_1);
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero), (6, 0));
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero).0, 0);
+}
+
+#[test]
+fn exact_fit_to_end()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::new(10, MaximumWorkers);
+	let ring_buffer_producer = &ring_buffer_producers[0];
+
+	// Filling the ring buffer exactly to the end would catch up with the `written` offset.
+	let zero = ring_buffer_producer.acquire(4).unwrap().as_ptr();
+	assert_eq!(acquire_and_produce(ring_buffer_producer, 6, zero), None);
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero), (4, 0));
+
+	// Once consumed, it fits exactly and `next` wraps-around to 0 without wasting any space.
+	assert_eq!(acquire_and_produce(ring_buffer_producer, 6, zero), Some(4));
+	assert_eq!(acquire_and_produce(ring_buffer_producer, 3, zero), Some(0));
+	assert_eq!(acquire_and_produce(ring_buffer_producer, 1, zero), None);
+
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero), (6, 4));
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero), (3, 0));
+	assert_eq!(consume_and_release(&mut ring_buffer_consumer, zero).0, 0);
+}
+
+#[test]
+#[should_panic(expected = "maximum_reservations_per_producer can not contain zero")]
+fn a_producer_without_any_reservations_is_rejected()
+{
+	let _ = RingBuffer::<u8>::new_with_maximum_reservations(16, &[1, 0]);
+}
+
+/// Two producers randomly acquire and produce length-prefixed messages whilst the consumer checks that each range consumed is made up of whole messages.
+///
+/// The length is only completed when the message is produced, so a message consumed before it is produced is detected.
+#[test]
+fn random()
+{
+	const Size: usize = 500;
+	const Iterations: usize = 1_000_000;
+
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::new(Size, MaximumWorkers);
+
+	let mut slice_guards = [None, None];
+	let mut random = XorShift::new(0);
+
+	for _ in 0 .. Iterations
+	{
+		let length = (random.next() as usize) % (Size / 2) + 1;
+		match (random.next() as usize) % 3
+		{
+			0 =>
+			{
+				let mut slice_guard = ring_buffer_consumer.consume();
+				let length = slice_guard.len();
+				{
+					let slice = slice_guard.as_slice();
+					let mut verified_length = 0;
+					while verified_length < length
+					{
+						let message_length = slice[verified_length] as usize;
+						assert!(message_length > 0);
+						verified_length += message_length;
+					}
+					assert_eq!(verified_length, length);
+				}
+				slice_guard.release(length);
+			}
+
+			worker =>
+			{
+				let index = worker - 1;
+				match slice_guards[index].take()
+				{
+					None => if let Ok(mut slice_guard) = ring_buffer_producers[index].acquire(length)
+					{
+						slice_guard[0] = (length - 1) as u8;
+						slice_guards[index] = Some(slice_guard);
+					},
+
+					Some(mut slice_guard) =>
+					{
+						slice_guard[0] += 1;
+					}
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,579 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Tests of `SharedMemoryRingBuffer`.
+//!
+//! Run with `cargo test --features shared-memory --test shared_memory`.
+
+
+#![allow(non_upper_case_globals)]
+#![cfg(all(unix, feature = "shared-memory"))]
+
+
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+
+
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+use ::std::env::current_exe;
+use ::std::env::var;
+use ::std::ffi::CString;
+#[cfg(target_os = "linux")] use ::std::fs::File;
+#[cfg(target_os = "linux")] use ::std::fs::OpenOptions;
+use ::std::io;
+use ::std::io::ErrorKind;
+#[cfg(target_os = "linux")] use ::std::io::Read;
+use ::std::mem::forget;
+#[cfg(target_os = "linux")] use ::std::os::unix::fs::FileExt;
+#[cfg(target_os = "linux")] use ::std::os::unix::io::AsRawFd;
+use ::std::process::Command;
+#[cfg(target_os = "linux")] use ::std::process::Stdio;
+use ::std::process::exit;
+use ::std::process::id;
+use ::std::thread::yield_now;
+
+
+/// Set in the environment of the producer process started by `producer_process_and_consumer_process()`.
+const ProducerProcessPath: &str = "RING_BUFFER_TEST_PRODUCER_PROCESS_PATH";
+
+const MessagesFromProducerProcess: u64 = 10_000;
+
+/// Set in the environment of the producer process started by `a_producer_whose_process_died_is_reaped()` and `a_producer_whose_process_died_holding_a_wrap_around_lock_is_reaped()`.
+const DeadProducerProcessPath: &str = "RING_BUFFER_TEST_DEAD_PRODUCER_PROCESS_PATH";
+
+/// Set in the environment of the producer process started by `a_producer_whose_process_identifier_was_reused_is_reaped()`.
+#[cfg(target_os = "linux")] const LivingProducerProcessPath: &str = "RING_BUFFER_TEST_LIVING_PRODUCER_PROCESS_PATH";
+
+/// The hands follow the 88 bytes of the fixed-width format description at the start of the memory.
+#[cfg(target_os = "linux")] const NextOffset: u64 = 88;
+#[cfg(target_os = "linux")] const WrittenOffset: u64 = 104;
+
+/// `MaximumOffset`, the value of the `end` hand and of the `seen` offset of a producer reservation which is not in progress.
+#[cfg(target_os = "linux")] const UnsetOffset: usize = usize::MAX >> 1;
+
+/// Set in the `next` hand, or in the `seen` offset of a producer reservation, whilst a producer is acquiring.
+#[cfg(target_os = "linux")] const WrapLockBit: usize = !UnsetOffset;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Message
+{
+	producer_index: u32,
+	sequence_number: u64,
+}
+
+/// Contains only integers, so has no pointers and every bit pattern is valid.
+unsafe impl PlainOldData for Message
+{
+}
+
+fn path(suffix: &str) -> CString
+{
+	CString::new(format!("/lock-free-ring-buffer-test-{}-{}", id(), suffix)).unwrap()
+}
+
+fn produce(ring_buffer_producer: &RingBufferProducer<Message>, producer_index: u32, first_sequence_number: u64, count: usize)
+{
+	let mut slice_guard = ring_buffer_producer.acquire(count).unwrap();
+	for (index, slot) in slice_guard.iter_mut().enumerate()
+	{
+		*slot = Message { producer_index, sequence_number: first_sequence_number + index as u64 };
+	}
+}
+
+/// A second mapping of the same memory is at a different address, so this checks that nothing in the memory depends on where it is mapped.
+#[cfg(target_os = "linux")]
+#[test]
+fn producers_attached_to_a_file_descriptor_send_to_the_consumer()
+{
+	let created = SharedMemoryRingBuffer::<Message>::create_anonymous(&CString::new("test").unwrap(), 16, &[1, 2]).unwrap();
+	let attached = SharedMemoryRingBuffer::<Message>::attach_to_file_descriptor(created.file_descriptor()).unwrap();
+	assert_eq!(attached.number_of_producers(), 2);
+
+	let mut ring_buffer_consumer = created.claim_consumer().unwrap();
+	let ring_buffer_producer_0 = attached.claim_producer().unwrap();
+	let ring_buffer_producer_1 = attached.claim_producer().unwrap();
+	assert_eq!(ring_buffer_producer_1.maximum_reservations(), 2);
+
+	for round in 0 .. 10
+	{
+		produce(&ring_buffer_producer_0, 0, round * 5, 5);
+		produce(&ring_buffer_producer_1, 1, round * 5, 5);
+
+		let mut messages = Vec::new();
+		while messages.len() != 10
+		{
+			messages.extend(ring_buffer_consumer.consume());
+		}
+		let expected: Vec<Message> = (0 .. 2).flat_map(|producer_index| (round * 5 .. round * 5 + 5).map(move |sequence_number| Message { producer_index, sequence_number })).collect();
+		assert_eq!(messages, expected);
+	}
+}
+
+#[test]
+fn producers_and_the_consumer_can_only_be_claimed_once_at_a_time()
+{
+	let path = path("claims");
+	let created = SharedMemoryRingBuffer::<Message>::create_named(&path, 16, &[1, 1]).unwrap();
+	let attached = SharedMemoryRingBuffer::<Message>::attach_to_path(&path).unwrap();
+	SharedMemoryRingBuffer::<Message>::unlink(&path).unwrap();
+
+	let ring_buffer_consumer = created.claim_consumer().unwrap();
+	assert!(attached.claim_consumer().is_none());
+	drop(ring_buffer_consumer);
+	assert!(attached.claim_consumer().is_some());
+
+	let ring_buffer_producer_1 = created.claim_producer_at(1).unwrap();
+	let ring_buffer_producer_0 = attached.claim_producer().unwrap();
+	assert!(created.claim_producer().is_none());
+	assert!(attached.claim_producer_at(1).is_none());
+	assert!(attached.claim_producer_at(2).is_none());
+
+	drop(ring_buffer_producer_0);
+	assert!(created.claim_producer().is_some());
+	drop(ring_buffer_producer_1);
+}
+
+fn format_error(error: io::Error) -> RingBufferFormatError
+{
+	assert_eq!(error.kind(), ErrorKind::InvalidData);
+	*error.get_ref().unwrap().downcast_ref::<RingBufferFormatError>().unwrap()
+}
+
+/// Opens the memory of a named ring buffer (named POSIX shared memory objects are files in `/dev/shm` on Linux).
+#[cfg(target_os = "linux")]
+fn memory(path: &CString) -> File
+{
+	OpenOptions::new().read(true).write(true).open(format!("/dev/shm{}", path.to_str().unwrap())).unwrap()
+}
+
+#[cfg(target_os = "linux")]
+fn write_word(memory: &File, offset: u64, value: usize)
+{
+	memory.write_all_at(&value.to_ne_bytes(), offset).unwrap()
+}
+
+/// Finds the `seen` offset of each producer reservation, which are the words after the hands which are unset whilst no reservation is in progress (the buffer is zeroed when created).
+#[cfg(target_os = "linux")]
+fn seen_offset_offsets(memory: &File) -> Vec<u64>
+{
+	let mut bytes = vec![0; memory.metadata().unwrap().len() as usize];
+	memory.read_exact_at(&mut bytes, 0).unwrap();
+	let word_size = ::std::mem::size_of::<usize>();
+	(WrittenOffset as usize + word_size .. bytes.len()).step_by(word_size).filter(|&offset|
+	{
+		let mut word = [0; 8];
+		word[.. word_size].copy_from_slice(&bytes[offset .. offset + word_size]);
+		u64::from_ne_bytes(word) as usize == UnsetOffset
+	}).map(|offset| offset as u64).collect()
+}
+
+/// Overwrites the memory of a named ring buffer at `offset` (named POSIX shared memory objects are files in `/dev/shm` on Linux) and then tries to attach to it.
+#[cfg(target_os = "linux")]
+fn attach_after_overwriting(suffix: &str, offset: u64, bytes: &[u8]) -> RingBufferFormatError
+{
+	let path = path(suffix);
+	let _created = SharedMemoryRingBuffer::<Message>::create_named(&path, 16, &[1]).unwrap();
+
+	memory(&path).write_all_at(bytes, offset).unwrap();
+
+	let error = SharedMemoryRingBuffer::<Message>::attach_to_path(&path).unwrap_err();
+	SharedMemoryRingBuffer::<Message>::unlink(&path).unwrap();
+	format_error(error)
+}
+
+#[test]
+fn attaching_as_a_different_type_of_item_fails()
+{
+	let path = path("different-type");
+	let _created = SharedMemoryRingBuffer::<Message>::create_named(&path, 16, &[1]).unwrap();
+
+	let error = SharedMemoryRingBuffer::<u8>::attach_to_path(&path).unwrap_err();
+	SharedMemoryRingBuffer::<Message>::unlink(&path).unwrap();
+	assert_eq!(format_error(error), RingBufferFormatError::DifferentItemType { item_size: 16, item_alignment: 8, expected_item_size: 1, expected_item_alignment: 1 });
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn attaching_to_memory_which_is_not_a_ring_buffer_fails()
+{
+	assert_eq!(attach_after_overwriting("not-a-ring-buffer", 0, &[0xFF; 8]), RingBufferFormatError::NotARingBuffer { magic: 0xFFFFFFFFFFFFFFFF });
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn attaching_to_a_ring_buffer_of_the_other_endianness_fails()
+{
+	let magic = if cfg!(target_endian = "little") { *b"RINGBUF\0" } else { *b"\0FUBGNIR" };
+	assert_eq!(attach_after_overwriting("other-endianness", 0, &magic), RingBufferFormatError::DifferentEndianness);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn attaching_to_a_ring_buffer_of_another_version_fails()
+{
+	assert_eq!(attach_after_overwriting("other-version", 8, &1u32.to_ne_bytes()), RingBufferFormatError::UnsupportedVersion { version: 1, supported_version: 6 });
+}
+
+/// A smaller capacity still fits in the memory, so only the layout checksum detects it.
+#[cfg(target_os = "linux")]
+#[test]
+fn attaching_to_a_ring_buffer_with_a_corrupt_description_fails()
+{
+	match attach_after_overwriting("corrupt-capacity", 56, &8u64.to_ne_bytes())
+	{
+		RingBufferFormatError::LayoutChecksumMismatch { .. } => (),
+		error => panic!("unexpected error {}", error),
+	}
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn attaching_to_a_ring_buffer_which_has_been_truncated_fails()
+{
+	let path = path("truncated");
+	let _created = SharedMemoryRingBuffer::<Message>::create_named(&path, 16, &[1]).unwrap();
+	let file = memory(&path);
+	let length = file.metadata().unwrap().len();
+	file.set_len(length - 16).unwrap();
+
+	let error = SharedMemoryRingBuffer::<Message>::attach_to_path(&path).unwrap_err();
+	SharedMemoryRingBuffer::<Message>::unlink(&path).unwrap();
+	assert_eq!(format_error(error), RingBufferFormatError::DifferentLength { length: (length - 16) as usize, expected_length: length as usize });
+
+	file.set_len(8).unwrap();
+	match format_error(SharedMemoryRingBuffer::<Message>::attach_to_file_descriptor(file.as_raw_fd()).unwrap_err())
+	{
+		RingBufferFormatError::TooSmall { length: 8, .. } => (),
+		error => panic!("unexpected error {}", error),
+	}
+}
+
+#[test]
+fn creating_a_named_ring_buffer_which_exists_fails()
+{
+	let path = path("exists");
+	let _created = SharedMemoryRingBuffer::<Message>::create_named(&path, 16, &[1]).unwrap();
+
+	let error = SharedMemoryRingBuffer::<Message>::create_named(&path, 16, &[1]).unwrap_err();
+	SharedMemoryRingBuffer::<Message>::unlink(&path).unwrap();
+	assert_eq!(error.kind(), ErrorKind::AlreadyExists);
+}
+
+/// A producer reservation whose `seen` offset is impossible has its producer quarantined; the other producers are unaffected.
+#[cfg(target_os = "linux")]
+#[test]
+fn a_producer_which_publishes_an_impossible_offset_is_quarantined()
+{
+	let path = path("quarantine");
+	let created = SharedMemoryRingBuffer::<Message>::create_named(&path, 16, &[1, 1]).unwrap();
+	let attached = SharedMemoryRingBuffer::<Message>::attach_to_path(&path).unwrap();
+	let memory = memory(&path);
+	SharedMemoryRingBuffer::<Message>::unlink(&path).unwrap();
+
+	let seen_offset_offsets = seen_offset_offsets(&memory);
+	assert_eq!(seen_offset_offsets.len(), 2);
+
+	let mut ring_buffer_consumer = created.claim_consumer().unwrap();
+	let hostile_ring_buffer_producer = attached.claim_producer_at(0).unwrap();
+	let ring_buffer_producer = attached.claim_producer_at(1).unwrap();
+
+	produce(&ring_buffer_producer, 1, 0, 2);
+	write_word(&memory, seen_offset_offsets[0], 1_000_000);
+	assert_eq!(ring_buffer_consumer.consume().collect::<Vec<_>>(), vec![Message { producer_index: 1, sequence_number: 0 }, Message { producer_index: 1, sequence_number: 1 }]);
+	assert_eq!(created.quarantined_producers(), vec![0]);
+	assert!(attached.quarantined_producers().is_empty());
+
+	// The quarantined producer's offsets are ignored from now on, even if they become possible again.
+	write_word(&memory, seen_offset_offsets[0], 2);
+	produce(&ring_buffer_producer, 1, 2, 2);
+	assert_eq!(ring_buffer_consumer.consume().count(), 2);
+
+	drop(hostile_ring_buffer_producer);
+	assert!(attached.claim_producer_at(0).is_none());
+	assert!(created.claim_producer_at(0).is_none());
+	assert_eq!(created.corrupt_hands(), 0);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn an_impossible_next_offset_is_not_consumed()
+{
+	let path = path("corrupt-next");
+	let created = SharedMemoryRingBuffer::<Message>::create_named(&path, 16, &[1]).unwrap();
+	let memory = memory(&path);
+	SharedMemoryRingBuffer::<Message>::unlink(&path).unwrap();
+
+	let mut ring_buffer_consumer = created.claim_consumer().unwrap();
+	let ring_buffer_producer = created.claim_producer().unwrap();
+	produce(&ring_buffer_producer, 0, 0, 3);
+
+	write_word(&memory, NextOffset, 1_000);
+	assert!(ring_buffer_consumer.consume().is_empty());
+	assert_eq!(created.corrupt_hands(), 1);
+
+	write_word(&memory, NextOffset, 3);
+	assert_eq!(ring_buffer_consumer.consume().count(), 3);
+	assert_eq!(created.corrupt_hands(), 1);
+}
+
+/// The consumer gives up waiting for a wrap-around lock which is not released, rather than hanging.
+#[cfg(target_os = "linux")]
+#[test]
+fn a_wrap_around_lock_held_for_too_long_does_not_hang_the_consumer()
+{
+	let path = path("held-lock");
+	let created = SharedMemoryRingBuffer::<Message>::create_named(&path, 16, &[1, 1]).unwrap();
+	let memory = memory(&path);
+	SharedMemoryRingBuffer::<Message>::unlink(&path).unwrap();
+
+	let seen_offset_offsets = seen_offset_offsets(&memory);
+	let mut ring_buffer_consumer = created.claim_consumer().unwrap();
+	let ring_buffer_producer = created.claim_producer_at(1).unwrap();
+	produce(&ring_buffer_producer, 1, 0, 2);
+
+	write_word(&memory, seen_offset_offsets[0], WrapLockBit);
+	assert_eq!(ring_buffer_consumer.consume_detailed().1, ConsumeStatus::BlockedByProducer { producer_index: 0, reservation_index: 0 });
+	write_word(&memory, seen_offset_offsets[0], UnsetOffset);
+
+	write_word(&memory, NextOffset, WrapLockBit | 2);
+	assert_eq!(ring_buffer_consumer.consume_detailed().1, ConsumeStatus::Empty);
+	write_word(&memory, NextOffset, 2);
+
+	assert_eq!(ring_buffer_consumer.consume().count(), 2);
+	assert!(created.quarantined_producers().is_empty());
+	assert_eq!(created.corrupt_hands(), 0);
+}
+
+/// The consumer keeps its own copy of the `written` offset, so changing the one in the memory does not affect what it consumes.
+#[cfg(target_os = "linux")]
+#[test]
+fn the_written_offset_in_memory_is_not_trusted_by_the_consumer()
+{
+	let path = path("corrupt-written");
+	let created = SharedMemoryRingBuffer::<Message>::create_named(&path, 16, &[1]).unwrap();
+	let memory = memory(&path);
+	SharedMemoryRingBuffer::<Message>::unlink(&path).unwrap();
+
+	let mut ring_buffer_consumer = created.claim_consumer().unwrap();
+	let ring_buffer_producer = created.claim_producer().unwrap();
+	produce(&ring_buffer_producer, 0, 0, 3);
+	assert_eq!(ring_buffer_consumer.consume().count(), 3);
+
+	produce(&ring_buffer_producer, 0, 3, 2);
+	write_word(&memory, WrittenOffset, 1_000_000);
+	assert_eq!(ring_buffer_consumer.consume().collect::<Vec<_>>(), vec![Message { producer_index: 0, sequence_number: 3 }, Message { producer_index: 0, sequence_number: 4 }]);
+}
+
+/// Runs this test binary again, as a producer process (see `producer_process()`), whilst this process consumes.
+#[test]
+fn producer_process_and_consumer_process()
+{
+	let path = path("processes");
+	let created = SharedMemoryRingBuffer::<Message>::create_named(&path, 64, &[1, 1]).unwrap();
+	let mut ring_buffer_consumer = created.claim_consumer().unwrap();
+	let ring_buffer_producer = created.claim_producer().unwrap();
+
+	let mut producer_process = Command::new(current_exe().unwrap()).args(["--exact", "producer_process", "--nocapture", "--test-threads", "1"]).env(ProducerProcessPath, path.to_str().unwrap()).spawn().unwrap();
+
+	let mut next_sequence_numbers = [0, 0];
+	let mut sent_from_this_process = 0;
+	while next_sequence_numbers != [MessagesFromProducerProcess, MessagesFromProducerProcess]
+	{
+		if sent_from_this_process != MessagesFromProducerProcess
+		{
+			if let Ok(mut slice_guard) = ring_buffer_producer.acquire(1)
+			{
+				slice_guard[0] = Message { producer_index: 0, sequence_number: sent_from_this_process };
+				sent_from_this_process += 1;
+			}
+		}
+
+		let mut slice_guard = ring_buffer_consumer.consume();
+		if slice_guard.is_empty()
+		{
+			drop(slice_guard);
+			yield_now();
+			continue
+		}
+		for message in &mut slice_guard
+		{
+			let next_sequence_number = &mut next_sequence_numbers[message.producer_index as usize];
+			assert_eq!(message.sequence_number, *next_sequence_number);
+			*next_sequence_number += 1;
+		}
+	}
+
+	assert!(producer_process.wait().unwrap().success());
+	SharedMemoryRingBuffer::<Message>::unlink(&path).unwrap();
+}
+
+/// Only does anything when run by `producer_process_and_consumer_process()`.
+#[test]
+fn producer_process()
+{
+	let path = match var(ProducerProcessPath)
+	{
+		Err(_) => return,
+		Ok(path) => CString::new(path).unwrap(),
+	};
+
+	let attached = SharedMemoryRingBuffer::<Message>::attach_to_path(&path).unwrap();
+	let ring_buffer_producer = attached.claim_producer().unwrap();
+
+	let mut sequence_number = 0;
+	while sequence_number != MessagesFromProducerProcess
+	{
+		match ring_buffer_producer.acquire(4)
+		{
+			Err(()) => yield_now(),
+
+			Ok(mut slice_guard) => for slot in slice_guard.iter_mut()
+			{
+				*slot = Message { producer_index: 1, sequence_number };
+				sequence_number += 1;
+			},
+		}
+	}
+}
+
+/// Runs this test binary again, as a producer process which dies whilst it has a reservation (see `dead_producer_process()`).
+#[test]
+fn a_producer_whose_process_died_is_reaped()
+{
+	let path = path("dead-producer");
+	let created = SharedMemoryRingBuffer::<Message>::create_named(&path, 16, &[1, 1]).unwrap();
+	let mut ring_buffer_consumer = created.claim_consumer().unwrap();
+	let ring_buffer_producer = created.claim_producer_at(0).unwrap();
+
+	let mut dead_producer_process = Command::new(current_exe().unwrap()).args(["--exact", "dead_producer_process", "--nocapture", "--test-threads", "1"]).env(DeadProducerProcessPath, path.to_str().unwrap()).spawn().unwrap();
+	assert!(dead_producer_process.wait().unwrap().success());
+	SharedMemoryRingBuffer::<Message>::unlink(&path).unwrap();
+
+	produce(&ring_buffer_producer, 0, 0, 2);
+	assert!(ring_buffer_consumer.consume().is_empty());
+	assert!(created.claim_producer_at(1).is_none());
+
+	assert_eq!(created.reap_dead_producers(&ring_buffer_consumer), vec![1]);
+	assert_eq!(ring_buffer_consumer.consume().collect::<Vec<_>>(), vec![Message { producer_index: 0, sequence_number: 0 }, Message { producer_index: 0, sequence_number: 1 }]);
+	assert!(created.reap_dead_producers(&ring_buffer_consumer).is_empty());
+
+	let ring_buffer_producer = created.claim_producer_at(1).unwrap();
+	produce(&ring_buffer_producer, 1, 0, 1);
+	assert_eq!(ring_buffer_consumer.consume().collect::<Vec<_>>(), vec![Message { producer_index: 1, sequence_number: 0 }]);
+}
+
+/// As `a_producer_whose_process_died_is_reaped()`, but the process died whilst the `seen` offset of its reservation was locked, so the consumer must give up waiting for it before it can be reaped.
+#[cfg(target_os = "linux")]
+#[test]
+fn a_producer_whose_process_died_holding_a_wrap_around_lock_is_reaped()
+{
+	let path = path("dead-producer-holding-lock");
+	let created = SharedMemoryRingBuffer::<Message>::create_named(&path, 16, &[1, 1]).unwrap();
+	let memory = memory(&path);
+	let mut ring_buffer_consumer = created.claim_consumer().unwrap();
+	let ring_buffer_producer = created.claim_producer_at(0).unwrap();
+	let unset_seen_offset_offsets = seen_offset_offsets(&memory);
+
+	let mut dead_producer_process = Command::new(current_exe().unwrap()).args(["--exact", "dead_producer_process", "--nocapture", "--test-threads", "1"]).env(DeadProducerProcessPath, path.to_str().unwrap()).spawn().unwrap();
+	assert!(dead_producer_process.wait().unwrap().success());
+	SharedMemoryRingBuffer::<Message>::unlink(&path).unwrap();
+
+	let still_unset_seen_offset_offsets = seen_offset_offsets(&memory);
+	let dead_seen_offset_offset = *unset_seen_offset_offsets.iter().find(|offset| !still_unset_seen_offset_offsets.contains(offset)).unwrap();
+	write_word(&memory, dead_seen_offset_offset, WrapLockBit);
+
+	produce(&ring_buffer_producer, 0, 0, 2);
+	assert_eq!(ring_buffer_consumer.consume_detailed().1, ConsumeStatus::BlockedByProducer { producer_index: 1, reservation_index: 0 });
+
+	assert_eq!(created.reap_dead_producers(&ring_buffer_consumer), vec![1]);
+	assert_eq!(ring_buffer_consumer.consume().collect::<Vec<_>>(), vec![Message { producer_index: 0, sequence_number: 0 }, Message { producer_index: 0, sequence_number: 1 }]);
+}
+
+/// Only does anything when run by `a_producer_whose_process_died_is_reaped()` or `a_producer_whose_process_died_holding_a_wrap_around_lock_is_reaped()`.
+#[test]
+fn dead_producer_process()
+{
+	let path = match var(DeadProducerProcessPath)
+	{
+		Err(_) => return,
+		Ok(path) => CString::new(path).unwrap(),
+	};
+
+	let attached = SharedMemoryRingBuffer::<Message>::attach_to_path(&path).unwrap();
+	let ring_buffer_producer = attached.claim_producer_at(1).unwrap();
+
+	// Dies without producing (or dropping) the reservation.
+	forget(ring_buffer_producer.acquire(3).unwrap());
+	exit(0)
+}
+
+/// Runs this test binary again, as a producer process which stays alive (see `living_producer_process()`), and then changes the start time recorded for it, as if its process identifier had been reused by a later process.
+#[cfg(target_os = "linux")]
+#[test]
+fn a_producer_whose_process_identifier_was_reused_is_reaped()
+{
+	let path = path("reused-process-identifier");
+	let created = SharedMemoryRingBuffer::<Message>::create_named(&path, 16, &[1, 1]).unwrap();
+	let ring_buffer_consumer = created.claim_consumer().unwrap();
+
+	let mut living_producer_process = Command::new(current_exe().unwrap()).args(["--exact", "living_producer_process", "--nocapture", "--test-threads", "1"]).env(LivingProducerProcessPath, path.to_str().unwrap()).stdin(Stdio::piped()).stdout(Stdio::null()).spawn().unwrap();
+	while created.claim_producer_at(1).is_some()
+	{
+		yield_now()
+	}
+	assert!(created.reap_dead_producers(&ring_buffer_consumer).is_empty());
+
+	let memory = memory(&path);
+	SharedMemoryRingBuffer::<Message>::unlink(&path).unwrap();
+	let process_identifier = living_producer_process.id() as usize;
+	let start_time = process_start_time(process_identifier);
+	let start_time_offset = word_offsets(&memory).windows(2).find(|words| words[0].1 == process_identifier && words[1].1 == start_time).unwrap()[1].0;
+	write_word(&memory, start_time_offset, start_time + 1);
+
+	assert_eq!(created.reap_dead_producers(&ring_buffer_consumer), vec![1]);
+	assert!(created.claim_producer_at(1).is_some());
+
+	drop(living_producer_process.stdin.take());
+	assert!(living_producer_process.wait().unwrap().success());
+}
+
+/// Only does anything when run by `a_producer_whose_process_identifier_was_reused_is_reaped()`.
+#[cfg(target_os = "linux")]
+#[test]
+fn living_producer_process()
+{
+	let path = match var(LivingProducerProcessPath)
+	{
+		Err(_) => return,
+		Ok(path) => CString::new(path).unwrap(),
+	};
+
+	let attached = SharedMemoryRingBuffer::<Message>::attach_to_path(&path).unwrap();
+	let _ring_buffer_producer = attached.claim_producer_at(1).unwrap();
+
+	// Stays alive until the test closes standard input.
+	io::stdin().read_to_end(&mut Vec::new()).unwrap();
+}
+
+/// The 22nd field of `/proc/<process_identifier>/stat`.
+#[cfg(target_os = "linux")]
+fn process_start_time(process_identifier: usize) -> usize
+{
+	let stat = ::std::fs::read_to_string(format!("/proc/{}/stat", process_identifier)).unwrap();
+	stat[stat.rfind(')').unwrap() + 1 ..].split_whitespace().nth(19).unwrap().parse().unwrap()
+}
+
+/// Every word of the memory, with its offset.
+#[cfg(target_os = "linux")]
+fn word_offsets(memory: &File) -> Vec<(u64, usize)>
+{
+	let mut bytes = vec![0; memory.metadata().unwrap().len() as usize];
+	memory.read_exact_at(&mut bytes, 0).unwrap();
+	let word_size = ::std::mem::size_of::<usize>();
+	bytes.chunks_exact(word_size).enumerate().map(|(index, word)|
+	{
+		let mut bytes = [0; ::std::mem::size_of::<usize>()];
+		bytes.copy_from_slice(word);
+		((index * word_size) as u64, usize::from_ne_bytes(bytes))
+	}).collect()
+}
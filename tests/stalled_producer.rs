@@ -0,0 +1,114 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Tests of `RingBufferConsumer::stalled_producer()` and `RingBufferConsumer::set_stall_watchdog()`.
+
+
+#![allow(non_upper_case_globals)]
+
+
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+
+
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+use ::std::sync::Arc;
+use ::std::sync::Mutex;
+use ::std::thread::sleep;
+use ::std::time::Duration;
+
+
+const Threshold: Duration = Duration::from_millis(20);
+
+#[test]
+fn nothing_is_stalled_whilst_no_reservation_is_in_progress()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(16, 2);
+	assert_eq!(ring_buffer_consumer.stalled_producer(), None);
+
+	drop(ring_buffer_producers[0].acquire(4).unwrap());
+	assert_eq!(ring_buffer_consumer.stalled_producer(), None);
+	assert_eq!(ring_buffer_consumer.consume().len(), 4);
+}
+
+#[test]
+fn the_reservation_nearest_the_consumer_is_reported()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new_with_maximum_reservations(16, &[1, 2]);
+
+	let first = ring_buffer_producers[0].acquire(2).unwrap();
+	let second = ring_buffer_producers[1].acquire(1).unwrap();
+	let third = ring_buffer_producers[1].acquire(4).unwrap();
+	drop(second);
+
+	let stalled_producer = ring_buffer_consumer.stalled_producer().unwrap();
+	assert_eq!((stalled_producer.producer_index, stalled_producer.reservation_index, stalled_producer.offset, stalled_producer.items_held_back), (0, 0, 0, 7));
+	assert!(ring_buffer_consumer.consume().is_empty());
+
+	sleep(Threshold);
+	assert!(ring_buffer_consumer.stalled_producer().unwrap().stalled_for >= Threshold);
+
+	drop(first);
+	let stalled_producer = ring_buffer_consumer.stalled_producer().unwrap();
+	assert_eq!((stalled_producer.producer_index, stalled_producer.reservation_index, stalled_producer.offset, stalled_producer.items_held_back), (1, 1, 3, 4));
+	assert!(stalled_producer.stalled_for < Threshold);
+	assert_eq!(ring_buffer_consumer.consume().len(), 3);
+
+	drop(third);
+	assert_eq!(ring_buffer_consumer.stalled_producer(), None);
+}
+
+/// After a wrap-around, a reservation at the end of the buffer holds back those at the start.
+#[test]
+fn a_reservation_before_a_wrap_around_holds_back_those_after_it()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(10, 2);
+
+	drop(ring_buffer_producers[0].acquire(6).unwrap());
+	ring_buffer_consumer.consume().release(6);
+
+	let _end = ring_buffer_producers[0].acquire(3).unwrap();
+	drop(ring_buffer_producers[1].acquire(2).unwrap());
+
+	let stalled_producer = ring_buffer_consumer.stalled_producer().unwrap();
+	assert_eq!((stalled_producer.producer_index, stalled_producer.offset, stalled_producer.items_held_back), (0, 6, 5));
+}
+
+#[test]
+fn the_watchdog_is_called_once_for_each_stalled_reservation()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(16, 2);
+
+	let called = Arc::new(Mutex::new(Vec::new()));
+	{
+		let called = called.clone();
+		ring_buffer_consumer.set_stall_watchdog(Threshold, move |stalled_producer| called.lock().unwrap().push(*stalled_producer));
+	}
+
+	let slice_guard = ring_buffer_producers[0].acquire(2).unwrap();
+	drop(ring_buffer_producers[1].acquire(3).unwrap());
+	assert!(ring_buffer_consumer.consume().is_empty());
+	assert!(called.lock().unwrap().is_empty());
+
+	sleep(Threshold);
+	assert!(ring_buffer_consumer.consume().is_empty());
+	assert!(ring_buffer_consumer.consume().is_empty());
+	{
+		let called = called.lock().unwrap();
+		assert_eq!(called.len(), 1);
+		assert_eq!((called[0].producer_index, called[0].offset, called[0].items_held_back), (0, 0, 5));
+		assert!(called[0].stalled_for >= Threshold);
+	}
+
+	drop(slice_guard);
+	let mut consumed = ring_buffer_consumer.consume();
+	assert_eq!(consumed.len(), 5);
+	consumed.release(5);
+	drop(consumed);
+
+	let _slice_guard = ring_buffer_producers[0].acquire(1).unwrap();
+	ring_buffer_consumer.clear_stall_watchdog();
+	sleep(Threshold);
+	assert!(ring_buffer_consumer.consume().is_empty());
+	assert_eq!(called.lock().unwrap().len(), 1);
+}
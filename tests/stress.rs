@@ -0,0 +1,154 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! A port of the original C code's `t_stress.c`.
+//!
+//! Producer threads send messages of random length with a checksum whilst the consumer checks every message it receives.
+//!
+//! This is a test without the default harness so that it can also be run for longer: `cargo test --release --test stress -- <seconds> <number of producers>`.
+//! It defaults to running for 1 second with 4 producers.
+
+
+#![allow(non_upper_case_globals)]
+
+
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+
+
+mod common;
+
+
+use ::common::XorShift;
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+use ::std::env::args;
+use ::std::sync::Arc;
+use ::std::sync::atomic::AtomicBool;
+use ::std::sync::atomic::Ordering::Relaxed;
+use ::std::thread::spawn;
+use ::std::thread::yield_now;
+use ::std::time::Duration;
+use ::std::time::Instant;
+
+
+const RingBufferSize: usize = 512;
+
+const MagicByte: u8 = 0x5A;
+
+/// A message is a magic byte, a length, a random payload and a checksum of the payload.
+const MessageOverhead: usize = 3;
+
+const MaximumMessageLength: usize = 255;
+
+fn checksum(payload: &[u8]) -> u8
+{
+	payload.iter().fold(0, |checksum: u8, &byte| checksum.wrapping_add(byte))
+}
+
+fn generate_message(message: &mut [u8], random: &mut XorShift)
+{
+	let length = message.len();
+	message[0] = MagicByte;
+	message[1] = length as u8;
+	for byte in message[2 .. length - 1].iter_mut()
+	{
+		*byte = random.next() as u8;
+	}
+	message[length - 1] = checksum(&message[2 .. length - 1]);
+}
+
+/// Returns the length of the message.
+fn verify_message(messages: &[u8]) -> usize
+{
+	assert!(messages.len() >= MessageOverhead, "truncated message");
+	assert_eq!(messages[0], MagicByte, "bad magic byte");
+	let length = messages[1] as usize;
+	assert!(length >= MessageOverhead && length <= messages.len(), "bad length {}", length);
+	assert_eq!(messages[length - 1], checksum(&messages[2 .. length - 1]), "bad checksum");
+	length
+}
+
+/// Returns the number of messages consumed.
+fn consume_messages(ring_buffer_consumer: &mut RingBufferConsumer<u8>) -> usize
+{
+	let mut slice_guard = ring_buffer_consumer.consume();
+
+	let mut number_of_messages = 0;
+	let length = slice_guard.len();
+	{
+		let mut messages = slice_guard.as_slice();
+		while !messages.is_empty()
+		{
+			let message_length = verify_message(messages);
+			messages = &messages[message_length ..];
+			number_of_messages += 1;
+		}
+	}
+	slice_guard.release(length);
+
+	number_of_messages
+}
+
+/// Returns the number of messages produced.
+fn produce_messages(ring_buffer_producer: RingBufferProducer<u8>, stop: Arc<AtomicBool>, seed: u64) -> usize
+{
+	let mut random = XorShift::new(seed);
+
+	let mut number_of_messages = 0;
+	while !stop.load(Relaxed)
+	{
+		let length = (random.next() as usize) % (MaximumMessageLength - MessageOverhead + 1) + MessageOverhead;
+		match ring_buffer_producer.acquire(length)
+		{
+			Err(()) => yield_now(),
+
+			Ok(mut slice_guard) =>
+			{
+				generate_message(&mut slice_guard, &mut random);
+				number_of_messages += 1;
+			}
+		}
+	}
+
+	number_of_messages
+}
+
+fn main()
+{
+	let arguments: Vec<String> = args().skip(1).filter(|argument| !argument.starts_with('-')).collect();
+	let seconds = arguments.first().map(|argument| argument.parse().expect("seconds")).unwrap_or(1);
+	let number_of_producers = arguments.get(1).map(|argument| argument.parse().expect("number of producers")).unwrap_or(4);
+
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::new(RingBufferSize, number_of_producers);
+
+	let stop = Arc::new(AtomicBool::new(false));
+	let producer_threads: Vec<_> = ring_buffer_producers.into_iter().enumerate().map(|(index, ring_buffer_producer)|
+	{
+		let stop = stop.clone();
+		spawn(move || produce_messages(ring_buffer_producer, stop, 0x9E3779B97F4A7C15 ^ (index as u64 + 1)))
+	}).collect();
+
+	let mut consumed = 0;
+	let finish_at = Instant::now() + Duration::from_secs(seconds);
+	while Instant::now() < finish_at
+	{
+		consumed += consume_messages(&mut ring_buffer_consumer);
+	}
+
+	stop.store(true, Relaxed);
+	let produced: usize = producer_threads.into_iter().map(|producer_thread| producer_thread.join().unwrap()).sum();
+
+	// Everything produced has either been consumed or is still in the ring buffer; a wrap-around may leave it in two ranges.
+	loop
+	{
+		let number_of_messages = consume_messages(&mut ring_buffer_consumer);
+		if number_of_messages == 0
+		{
+			break
+		}
+		consumed += number_of_messages;
+	}
+	assert_eq!(consumed, produced, "messages lost or duplicated");
+
+	println!("stress: {} producers sent {} messages in {} seconds", number_of_producers, produced, seconds);
+}
@@ -0,0 +1,179 @@
+// This file is part of lock-free-multi-producer-single-consumer-ring-buffer. It is subject to the license terms in the COPYRIGHT file found in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT. No part of lock-free-multi-producer-single-consumer-ring-buffer, including this file, may be copied, modified, propagated, or distributed except according to the terms contained in the COPYRIGHT file.
+// Copyright © 2019 The developers of lock-free-multi-producer-single-consumer-ring-buffer. See the COPYRIGHT file in the top-level directory of this distribution and at https://raw.githubusercontent.com/lemonrock/lock-free-multi-producer-single-consumer-ring-buffer/master/COPYRIGHT.
+
+
+//! Tests of `RingBufferConsumer::write_to()` and `RingBufferProducer::read_from()`.
+
+
+#![allow(non_upper_case_globals)]
+
+
+extern crate lock_free_multi_producer_single_consumer_ring_buffer;
+
+
+use ::lock_free_multi_producer_single_consumer_ring_buffer::*;
+use ::std::io;
+use ::std::io::ErrorKind;
+use ::std::io::IoSlice;
+use ::std::io::Read;
+use ::std::io::Write;
+
+
+/// Accepts at most `limit` bytes in total.
+struct LimitedWriter
+{
+	written: Vec<u8>,
+	limit: usize,
+}
+
+impl Write for LimitedWriter
+{
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize>
+	{
+		self.write_vectored(&[IoSlice::new(buf)])
+	}
+
+	fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize>
+	{
+		let before = self.written.len();
+		for buf in bufs
+		{
+			let count = buf.len().min(self.limit - self.written.len());
+			self.written.extend_from_slice(&buf[.. count]);
+		}
+		Ok(self.written.len() - before)
+	}
+
+	fn flush(&mut self) -> io::Result<()>
+	{
+		Ok(())
+	}
+}
+
+fn consume_all(ring_buffer_consumer: &mut RingBufferConsumer<u8>) -> Vec<u8>
+{
+	let mut writer = LimitedWriter { written: Vec::new(), limit: usize::MAX };
+	while ring_buffer_consumer.write_to(&mut writer).unwrap() != 0
+	{
+	}
+	writer.written
+}
+
+#[test]
+fn write_to_writes_across_a_wrap_around_and_releases_only_what_was_written()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(16, 1);
+
+	ring_buffer_producers[0].acquire(12).unwrap().copy_from_slice(&[0; 12]);
+	assert_eq!(consume_all(&mut ring_buffer_consumer), vec![0; 12]);
+
+	ring_buffer_producers[0].acquire(3).unwrap().copy_from_slice(&[1, 2, 3]);
+	ring_buffer_producers[0].acquire(4).unwrap().copy_from_slice(&[4, 5, 6, 7]);
+
+	let mut writer = LimitedWriter { written: Vec::new(), limit: 5 };
+	assert_eq!(ring_buffer_consumer.write_to(&mut writer).unwrap(), 5);
+	assert_eq!(writer.written, vec![1, 2, 3, 4, 5]);
+	assert_eq!(ring_buffer_consumer.write_to(&mut writer).unwrap(), 0);
+
+	assert_eq!(consume_all(&mut ring_buffer_consumer), vec![6, 7]);
+}
+
+#[test]
+fn read_from_produces_only_the_bytes_read()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(64, 2);
+
+	let mut reader: &[u8] = b"abc";
+	assert_eq!(ring_buffer_producers[0].read_from(&mut reader, 16).unwrap(), 3);
+	assert_eq!(consume_all(&mut ring_buffer_consumer), b"abc".to_vec());
+
+	assert_eq!(ring_buffer_producers[0].read_from(&mut reader, 16).unwrap(), 0);
+	assert_eq!(consume_all(&mut ring_buffer_consumer), Vec::<u8>::new());
+
+	assert_eq!(ring_buffer_producers[0].read_from(&mut io::repeat(9).take(4), 0).unwrap(), 0);
+	assert_eq!(consume_all(&mut ring_buffer_consumer), Vec::<u8>::new());
+}
+
+#[test]
+fn the_unread_tail_of_read_from_is_skipped_by_the_consumer()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(64, 2);
+
+	let slice_guard = ring_buffer_producers[1].acquire(8);
+
+	let mut reader: &[u8] = b"short";
+	assert_eq!(ring_buffer_producers[0].read_from(&mut reader, 32).unwrap(), 5);
+
+	slice_guard.unwrap().copy_from_slice(b"12345678");
+	ring_buffer_producers[1].acquire(2).unwrap().copy_from_slice(b"xy");
+
+	assert_eq!(consume_all(&mut ring_buffer_consumer), b"12345678shortxy".to_vec());
+}
+
+#[test]
+fn read_from_produces_nothing_if_the_reader_fails()
+{
+	struct FailingReader;
+
+	impl Read for FailingReader
+	{
+		fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize>
+		{
+			Err(io::Error::from(ErrorKind::Interrupted))
+		}
+	}
+
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(64, 1);
+
+	assert_eq!(ring_buffer_producers[0].read_from(&mut FailingReader, 16).unwrap_err().kind(), ErrorKind::Interrupted);
+	assert_eq!(consume_all(&mut ring_buffer_consumer), Vec::<u8>::new());
+
+	ring_buffer_producers[0].acquire(2).unwrap().copy_from_slice(b"ok");
+	assert_eq!(consume_all(&mut ring_buffer_consumer), b"ok".to_vec());
+}
+
+#[test]
+fn read_from_passes_the_reader_zeroed_space()
+{
+	/// Reads nothing, but remembers what it was passed.
+	struct InspectingReader(Vec<u8>);
+
+	impl Read for InspectingReader
+	{
+		fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>
+		{
+			self.0 = buf.to_vec();
+			Ok(0)
+		}
+	}
+
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new(16, 1);
+
+	ring_buffer_producers[0].acquire(8).unwrap().copy_from_slice(&[0xFF; 8]);
+	assert_eq!(consume_all(&mut ring_buffer_consumer), vec![0xFF; 8]);
+	ring_buffer_producers[0].acquire(8).unwrap().copy_from_slice(&[0xFF; 8]);
+	assert_eq!(consume_all(&mut ring_buffer_consumer), vec![0xFF; 8]);
+
+	let mut reader = InspectingReader(Vec::new());
+	assert_eq!(ring_buffer_producers[0].read_from(&mut reader, 8).unwrap(), 0);
+	assert_eq!(reader.0, vec![0; 8]);
+}
+
+#[test]
+fn the_unread_tail_of_read_from_is_skipped_out_of_order()
+{
+	let (mut ring_buffer_consumer, ring_buffer_producers) = RingBuffer::<u8>::new_with_out_of_order_completion(64, &[2, 2]);
+
+	let mut slice_guard = ring_buffer_producers[1].acquire(8).unwrap();
+
+	let mut reader: &[u8] = b"short";
+	assert_eq!(ring_buffer_producers[0].read_from(&mut reader, 32).unwrap(), 5);
+	assert_eq!(consume_all(&mut ring_buffer_consumer), b"short".to_vec());
+
+	slice_guard.copy_from_slice(b"12345678");
+	drop(slice_guard);
+	assert_eq!(consume_all(&mut ring_buffer_consumer), b"12345678".to_vec());
+
+	ring_buffer_producers[0].acquire(2).unwrap().copy_from_slice(b"xy");
+	assert_eq!(consume_all(&mut ring_buffer_consumer), b"xy".to_vec());
+}